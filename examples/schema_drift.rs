@@ -0,0 +1,41 @@
+//! Prints a JSON report of the differences between Checkout's OpenAPI spec
+//! and the endpoints and request fields modeled by this library
+//!
+//! Usage: `cargo run --example schema_drift -- <spec url or file>`
+//!
+//! Exits with status 1 if the spec no longer has endpoints or fields the
+//! library sends.
+
+use std::{env, error::Error, process};
+
+use checkout::drift::schema_drift;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let source = match env::args().nth(1) {
+        Some(source) => source,
+        None => {
+            eprintln!("usage: schema_drift <spec url or file>");
+            process::exit(2);
+        }
+    };
+
+    let spec: serde_json::Value = if source.starts_with("http://") || source.starts_with("https://")
+    {
+        reqwest::get(&source)
+            .await?
+            .error_for_status()?
+            .json()
+            .await?
+    } else {
+        serde_json::from_slice(&std::fs::read(&source)?)?
+    };
+
+    let report = schema_drift(&spec);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+
+    if report.is_breaking() {
+        process::exit(1);
+    }
+    Ok(())
+}
@@ -0,0 +1,349 @@
+//! Hooks for writing compliance (PCI/AML) audit trails of outbound requests.
+//!
+//! A hook registered with [`Client::with_audit_hook`](crate::Client::with_audit_hook)
+//! receives a [`RequestSummary`] for every request sent to the Checkout API.
+//! Summaries never contain full card numbers, CVVs or credentials.
+
+use std::{fmt, sync::Arc, time::Duration};
+
+use reqwest::{Method, Response, StatusCode};
+
+use crate::{
+    types::requests::EmptyBody, BankPayoutDestination, BankPayoutRequest, CapturePaymentBody,
+    CardMetadataRequest, CardMetadataSource, CardPayoutRequest, CreateBankAccountInstrumentBody,
+    CreateHostedPaymentRequest, CreateInstrumentBody, CreatePaymentLinkRequest,
+    CreatePaymentRequest, CreateSessionRequest, PaymentRequestDestination, PaymentRequestRef,
+    PaymentRequestSource, PaymentSourceRef, ProvideDisputeEvidenceBody, RefundPaymentBody,
+    UpdateCustomerBody, UpdateInstrumentBody, VoidPaymentBody,
+};
+
+/// The header containing the unique identifier Checkout assigns to a request
+pub const CKO_REQUEST_ID_HEADER: &str = "cko-request-id";
+
+/// The header used to pin and report the version of the API
+pub const CKO_VERSION_HEADER: &str = "cko-version";
+
+/// The header used to send an idempotency key with a request
+pub const CKO_IDEMPOTENCY_KEY_HEADER: &str = "cko-idempotency-key";
+
+/// A sanitized summary of an outbound request
+#[derive(Debug, Clone)]
+pub struct RequestSummary {
+    /// The HTTP method of the request
+    pub method: Method,
+
+    /// The URL the request was sent to
+    pub endpoint: String,
+
+    /// The amount of the request in minor units, if any
+    pub amount: Option<u64>,
+
+    /// The three-letter ISO currency code of the request, if any
+    pub currency: Option<String>,
+
+    /// The source (or destination for payouts) of the request with any card
+    /// number masked to its BIN and last four digits
+    pub masked_source: Option<String>,
+
+    /// The idempotency key sent with the request, if any
+    pub idempotency_key: Option<String>,
+
+    /// The `Cko-Request-Id` header of the response, if a response was received
+    pub cko_request_id: Option<String>,
+
+    /// The HTTP status of the response, if a response was received
+    pub status: Option<StatusCode>,
+
+    /// The `Cko-Version` header of the response, if any
+    pub api_version: Option<String>,
+
+    /// The time until the response was received, if a response was received
+    pub latency: Option<Duration>,
+
+    /// The number of the attempt, counting retries but not sending the
+    /// request again to another host or with a renewed token
+    pub attempts: u32,
+
+    /// The number of times the request was sent to another host because a
+    /// host could not be connected to
+    pub redirects: u32,
+}
+
+/// A callback that receives a [`RequestSummary`] for every outbound request
+#[derive(Clone)]
+pub struct AuditHook(Arc<dyn Fn(&RequestSummary) + Send + Sync>);
+
+impl AuditHook {
+    /// Creates a new hook from a callback
+    #[must_use]
+    pub fn new<F>(hook: F) -> AuditHook
+    where
+        F: Fn(&RequestSummary) + Send + Sync + 'static,
+    {
+        AuditHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, summary: &RequestSummary) {
+        (self.0)(summary);
+    }
+}
+
+impl fmt::Debug for AuditHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuditHook")
+    }
+}
+
+/// Request bodies that can be described in a [`RequestSummary`]
+pub trait Auditable {
+    /// The amount of the request in minor units
+    fn audit_amount(&self) -> Option<u64> {
+        None
+    }
+
+    /// The three-letter ISO currency code of the request
+    fn audit_currency(&self) -> Option<String> {
+        None
+    }
+
+    /// The masked source or destination of the request
+    fn audit_masked_source(&self) -> Option<String> {
+        None
+    }
+}
+
+impl RequestSummary {
+    pub(crate) fn new(method: Method, endpoint: &str, body: Option<&dyn Auditable>) -> Self {
+        RequestSummary {
+            method,
+            endpoint: endpoint.to_string(),
+            amount: body.and_then(Auditable::audit_amount),
+            currency: body.and_then(Auditable::audit_currency),
+            masked_source: body.and_then(Auditable::audit_masked_source),
+            idempotency_key: None,
+            cko_request_id: None,
+            status: None,
+            api_version: None,
+            latency: None,
+            attempts: 0,
+            redirects: 0,
+        }
+    }
+
+    pub(crate) fn record_response(&mut self, response: &Response) {
+        self.status = Some(response.status());
+        self.cko_request_id = header_value(response, CKO_REQUEST_ID_HEADER);
+        self.api_version = header_value(response, CKO_VERSION_HEADER);
+    }
+}
+
+pub(crate) fn header_value(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// The fields of request and response bodies whose `number` is a card
+/// number, as opposed to e.g. the `number` of a `phone`
+pub(crate) const CARD_OBJECTS: [&str; 4] = ["source", "destination", "card", "instrument"];
+
+/// Masks a card number, keeping only the first six and last four digits
+#[must_use]
+pub fn mask_card_number(number: &str) -> String {
+    let digits: Vec<char> = number.chars().filter(char::is_ascii_digit).collect();
+    if digits.len() < 10 {
+        return "*".repeat(digits.len());
+    }
+
+    let (bin, rest) = digits.split_at(6);
+    let (middle, last4) = rest.split_at(rest.len() - 4);
+    format!(
+        "{}{}{}",
+        bin.iter().collect::<String>(),
+        "*".repeat(middle.len()),
+        last4.iter().collect::<String>()
+    )
+}
+
+/// Masks a bank account number or IBAN, keeping only the last four
+/// characters
+pub(crate) fn mask_account_number(number: &str) -> String {
+    let chars: Vec<char> = number.chars().collect();
+    let visible = chars.len().saturating_sub(4);
+    format!("****{}", chars[visible..].iter().collect::<String>())
+}
+
+impl Auditable for PaymentRequestSource {
+    fn audit_masked_source(&self) -> Option<String> {
+        match self {
+            PaymentRequestSource::Card { number, .. } => {
+                Some(format!("card:{}", mask_card_number(number)))
+            }
+            PaymentRequestSource::Token { token } => Some(format!("token:{token}")),
+            PaymentRequestSource::Id { id, .. } => Some(format!("id:{id}")),
+            PaymentRequestSource::Customer { id } => Some(format!("customer:{id}")),
+        }
+    }
+}
+
+impl Auditable for PaymentRequestDestination {
+    fn audit_masked_source(&self) -> Option<String> {
+        match self {
+            PaymentRequestDestination::Card { number, .. } => {
+                Some(format!("card:{}", mask_card_number(number)))
+            }
+        }
+    }
+}
+
+impl Auditable for CreatePaymentRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.amount().minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.amount.currency()))
+    }
+
+    fn audit_masked_source(&self) -> Option<String> {
+        self.source
+            .as_ref()
+            .and_then(Auditable::audit_masked_source)
+            .or_else(|| {
+                self.destination
+                    .as_ref()
+                    .and_then(Auditable::audit_masked_source)
+            })
+    }
+}
+
+impl Auditable for PaymentRequestRef<'_> {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.currency))
+    }
+
+    fn audit_masked_source(&self) -> Option<String> {
+        Some(match self.source {
+            PaymentSourceRef::Card { number, .. } => format!("card:{}", mask_card_number(number)),
+            PaymentSourceRef::Token { token } => format!("token:{token}"),
+            PaymentSourceRef::Id { id, .. } => format!("id:{id}"),
+            PaymentSourceRef::Customer { id } => format!("customer:{id}"),
+        })
+    }
+}
+
+impl Auditable for CapturePaymentBody {
+    fn audit_amount(&self) -> Option<u64> {
+        self.amount.map(|money| money.amount.minor_units())
+    }
+}
+
+impl Auditable for RefundPaymentBody {
+    fn audit_amount(&self) -> Option<u64> {
+        self.amount.map(|money| money.amount.minor_units())
+    }
+}
+
+impl Auditable for VoidPaymentBody {}
+
+impl Auditable for CreateInstrumentBody {}
+
+impl Auditable for ProvideDisputeEvidenceBody {}
+
+impl Auditable for EmptyBody {}
+
+impl Auditable for CreateSessionRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.amount().minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.amount.currency()))
+    }
+
+    fn audit_masked_source(&self) -> Option<String> {
+        self.source.audit_masked_source()
+    }
+}
+
+impl Auditable for CreateHostedPaymentRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.currency))
+    }
+}
+
+impl Auditable for CreatePaymentLinkRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.currency))
+    }
+}
+
+impl Auditable for CardPayoutRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.currency))
+    }
+
+    fn audit_masked_source(&self) -> Option<String> {
+        self.destination.audit_masked_source()
+    }
+}
+
+impl Auditable for BankPayoutRequest {
+    fn audit_amount(&self) -> Option<u64> {
+        Some(self.amount.minor_units())
+    }
+
+    fn audit_currency(&self) -> Option<String> {
+        Some(format!("{:?}", self.currency))
+    }
+
+    fn audit_masked_source(&self) -> Option<String> {
+        match &self.destination {
+            BankPayoutDestination::BankAccount(account) => {
+                let number = account.iban.as_ref().or(account.account_number.as_ref())?;
+                Some(format!("bank_account:{}", mask_account_number(number)))
+            }
+            BankPayoutDestination::Id { id } => Some(format!("id:{id}")),
+        }
+    }
+}
+
+impl Auditable for CreateBankAccountInstrumentBody {
+    fn audit_masked_source(&self) -> Option<String> {
+        let number = self.iban.as_ref().or(self.account_number.as_ref())?;
+        Some(format!("bank_account:{}", mask_account_number(number)))
+    }
+}
+
+impl Auditable for CardMetadataRequest {
+    fn audit_masked_source(&self) -> Option<String> {
+        Some(match &self.source {
+            CardMetadataSource::Card { number } => format!("card:{}", mask_card_number(number)),
+            CardMetadataSource::Bin { bin } => format!("bin:{bin}"),
+            CardMetadataSource::Token { token } => format!("token:{token}"),
+            CardMetadataSource::Id { id } => format!("id:{id}"),
+        })
+    }
+}
+
+impl Auditable for UpdateInstrumentBody {}
+
+impl Auditable for UpdateCustomerBody {}
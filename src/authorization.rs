@@ -0,0 +1,184 @@
+//! Authorizations that are captured or voided later, e.g. when an order ships
+//!
+//! [`Client::authorize_payment`](crate::Client::authorize_payment) returns an
+//! [`AuthorizationHandle`] that can be stored with the order, e.g. as JSON,
+//! and used in a later job to capture or void the payment. The handle tracks
+//! the amount that can still be captured and the authorization's expiry, and
+//! derives the idempotency key of each capture and void from a key that is
+//! generated once, so that a job that is run again after a crash doesn't
+//! capture twice.
+//!
+//! ```ignore
+//! let handle = client.authorize_payment(&request).await?;
+//! orders.save(order_id, serde_json::to_string(&handle)?)?;
+//!
+//! // Later, when the order ships
+//! let mut handle: AuthorizationHandle = serde_json::from_str(&orders.load(order_id)?)?;
+//! client.capture_authorization(&mut handle, &capture).await?;
+//! orders.save(order_id, serde_json::to_string(&handle)?)?;
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+use crate::{
+    Amount, CaptureFailure, CaptureType, Currency, PaymentDetails, PaymentId, PaymentProcessed,
+    PaymentStatus,
+};
+
+/// A payment that was authorized and can be captured or voided later
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthorizationHandle {
+    /// The payment's unique identifier
+    pub payment_id: PaymentId,
+
+    /// The authorized amount
+    pub amount: Amount,
+
+    /// The currency of the payment
+    pub currency: Currency,
+
+    /// Your reference for the payment
+    pub reference: Option<String>,
+
+    /// When the authorization expires and can no longer be captured, if known
+    pub expires_on: Option<DateTime<Utc>>,
+
+    /// The amount captured through the handle, in minor units
+    captured: u64,
+
+    /// The number of captures sent through the handle
+    captures: u32,
+
+    /// Whether no further captures are expected
+    closed: bool,
+
+    /// The key the idempotency keys of the captures and the void are derived
+    /// from
+    idempotency_key: String,
+}
+
+impl AuthorizationHandle {
+    /// Creates the handle of an approved payment that was authorized and not
+    /// captured, or `None` for any other payment
+    #[must_use]
+    pub fn new(payment: &PaymentProcessed) -> Option<AuthorizationHandle> {
+        if !payment.approved || payment.status != PaymentStatus::Authorized {
+            return None;
+        }
+        Some(AuthorizationHandle::authorized(
+            &payment.id,
+            payment.amount,
+            payment.currency,
+            payment.reference.clone(),
+            payment.expires_on,
+        ))
+    }
+
+    /// Creates the handle of a payment that was authorized after a redirect,
+    /// e.g. 3D Secure, from its details, or `None` if it is not authorized
+    #[must_use]
+    pub fn from_details(payment: &PaymentDetails) -> Option<AuthorizationHandle> {
+        if !payment.approved || payment.status != PaymentStatus::Authorized {
+            return None;
+        }
+        Some(AuthorizationHandle::authorized(
+            &payment.id,
+            payment.amount,
+            payment.currency,
+            payment.reference.clone(),
+            payment.expires_on,
+        ))
+    }
+
+    fn authorized(
+        payment_id: &str,
+        amount: Amount,
+        currency: Currency,
+        reference: Option<String>,
+        expires_on: Option<DateTime<Utc>>,
+    ) -> AuthorizationHandle {
+        AuthorizationHandle {
+            payment_id: PaymentId(payment_id.to_string()),
+            amount,
+            currency,
+            reference,
+            expires_on,
+            captured: 0,
+            captures: 0,
+            closed: false,
+            idempotency_key: Ulid::new().to_string(),
+        }
+    }
+
+    /// Returns the amount captured through the handle, in minor units
+    #[must_use]
+    pub fn captured_amount(&self) -> u64 {
+        self.captured
+    }
+
+    /// Returns the amount that can still be captured, in minor units
+    #[must_use]
+    pub fn remaining_amount(&self) -> u64 {
+        if self.closed {
+            0
+        } else {
+            self.amount.minor_units().saturating_sub(self.captured)
+        }
+    }
+
+    /// Returns whether the authorization has expired at the given time
+    #[must_use]
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_on.is_some_and(|expires_on| expires_on <= now)
+    }
+
+    /// Returns whether the payment was fully or finally captured, or voided
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed || self.remaining_amount() == 0
+    }
+
+    /// Checks that a capture of `amount` minor units (or the remaining
+    /// amount if `None`) can succeed, and returns the amount to capture
+    pub(crate) fn check_capture(
+        &self,
+        amount: Option<u64>,
+        now: DateTime<Utc>,
+    ) -> Result<u64, CaptureFailure> {
+        if self.is_expired(now) {
+            return Err(CaptureFailure::AuthorizationExpired);
+        }
+        let remaining = self.remaining_amount();
+        let amount = amount.unwrap_or(remaining);
+        if amount == 0 || amount > remaining {
+            return Err(CaptureFailure::AmountExceeded);
+        }
+        Ok(amount)
+    }
+
+    /// Returns the idempotency key of the next capture
+    pub(crate) fn capture_key(&self) -> String {
+        format!("{}-capture-{}", self.idempotency_key, self.captures + 1)
+    }
+
+    /// Returns the idempotency key of the void
+    pub(crate) fn void_key(&self) -> String {
+        format!("{}-void", self.idempotency_key)
+    }
+
+    /// Records a capture that was accepted
+    pub(crate) fn record_capture(&mut self, amount: u64, capture_type: Option<CaptureType>) {
+        self.captured += amount;
+        self.captures += 1;
+        if capture_type != Some(CaptureType::NonFinal) {
+            self.closed = true;
+        }
+    }
+
+    /// Records a void that was accepted
+    pub(crate) fn record_void(&mut self) {
+        self.closed = true;
+    }
+}
@@ -0,0 +1,243 @@
+//! Concurrent, resumable submission of payout batches
+//!
+//! Every payout of a batch is sent with an idempotency key derived from the
+//! batch id and the payout's reference, and completed payouts are recorded in
+//! a [`BatchStore`]. Submitting the same batch again, e.g. after a crash,
+//! skips the recorded payouts and safely retries the others.
+
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use crate::{BankPayoutRequest, CardPayoutRequest, CreatePaymentResponse, Error};
+
+/// A payout of a batch
+#[derive(Debug, Clone)]
+pub enum BatchPayout {
+    /// A payout to a card
+    Card(Box<CardPayoutRequest>),
+
+    /// A payout to a bank account
+    Bank(Box<BankPayoutRequest>),
+}
+
+impl BatchPayout {
+    fn amount(&self) -> u64 {
+        match self {
+            BatchPayout::Card(request) => request.amount.minor_units(),
+            BatchPayout::Bank(request) => request.amount.minor_units(),
+        }
+    }
+}
+
+/// A payout batch could not be submitted because it is invalid
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum BatchValidationError {
+    /// The batch has no payouts
+    #[error("the batch is empty")]
+    Empty,
+
+    /// The reference is used by more than one payout of the batch
+    #[error("reference {0} is used more than once")]
+    DuplicateReference(String),
+
+    /// The reference is empty or longer than 50 characters
+    #[error("reference {0:?} must be 1-50 characters")]
+    InvalidReference(String),
+
+    /// The payout with the reference has no amount
+    #[error("payout {0} has no amount")]
+    ZeroAmount(String),
+}
+
+/// A collection of payouts that are submitted together
+#[derive(Debug, Clone)]
+pub struct PayoutBatch {
+    id: String,
+    payouts: Vec<(String, BatchPayout)>,
+}
+
+impl PayoutBatch {
+    /// Creates an empty batch. The id must be unique per batch and stable
+    /// across submissions of the same batch, e.g. `"payouts-2022-w14"`.
+    #[must_use]
+    pub fn new(id: impl Into<String>) -> PayoutBatch {
+        PayoutBatch {
+            id: id.into(),
+            payouts: Vec::new(),
+        }
+    }
+
+    /// Returns the id of the batch
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the payouts of the batch with their references
+    #[must_use]
+    pub fn payouts(&self) -> &[(String, BatchPayout)] {
+        &self.payouts
+    }
+
+    /// Adds a payout to the batch. The reference identifies the payout within
+    /// the batch (1-50 characters).
+    #[must_use]
+    pub fn payout(mut self, reference: impl Into<String>, payout: BatchPayout) -> PayoutBatch {
+        self.payouts.push((reference.into(), payout));
+        self
+    }
+
+    /// Checks that the batch has payouts with unique, valid references and
+    /// non-zero amounts
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found in the batch
+    pub fn validate(&self) -> Result<(), Vec<BatchValidationError>> {
+        if self.payouts.is_empty() {
+            return Err(vec![BatchValidationError::Empty]);
+        }
+
+        let mut errors = Vec::new();
+        let mut references = HashSet::new();
+        for (reference, payout) in &self.payouts {
+            if reference.is_empty() || reference.chars().count() > 50 {
+                errors.push(BatchValidationError::InvalidReference(reference.clone()));
+            }
+            if !references.insert(reference.as_str()) {
+                errors.push(BatchValidationError::DuplicateReference(reference.clone()));
+            }
+            if payout.amount() == 0 {
+                errors.push(BatchValidationError::ZeroAmount(reference.clone()));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Returns the idempotency key the payout with the reference is sent with
+    #[must_use]
+    pub fn idempotency_key(&self, reference: &str) -> String {
+        format!("{}:{}", self.id, reference)
+    }
+}
+
+/// Keeps track of the completed payouts of batches so that a batch can be
+/// resumed after a crash
+///
+/// Implement this trait on top of your own database to resume batches across
+/// processes; [`MemoryBatchStore`] only remembers payouts within a process.
+pub trait BatchStore: Send + Sync {
+    /// Returns the payment id of the payout with the reference, if it was
+    /// completed
+    fn completed(&self, batch_id: &str, reference: &str) -> Option<String>;
+
+    /// Records that the payout with the reference was completed
+    fn record(&self, batch_id: &str, reference: &str, payment_id: &str);
+}
+
+/// An in-memory [`BatchStore`]
+#[derive(Debug, Default)]
+pub struct MemoryBatchStore {
+    completed: Mutex<HashMap<(String, String), String>>,
+}
+
+impl BatchStore for MemoryBatchStore {
+    fn completed(&self, batch_id: &str, reference: &str) -> Option<String> {
+        let completed = match self.completed.lock() {
+            Ok(completed) => completed,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        completed
+            .get(&(batch_id.to_string(), reference.to_string()))
+            .cloned()
+    }
+
+    fn record(&self, batch_id: &str, reference: &str, payment_id: &str) {
+        let mut completed = match self.completed.lock() {
+            Ok(completed) => completed,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        completed.insert(
+            (batch_id.to_string(), reference.to_string()),
+            payment_id.to_string(),
+        );
+    }
+}
+
+/// The outcome of submitting a payout batch
+#[derive(Debug, Default)]
+pub struct BatchReport {
+    /// The payouts that were submitted, with their references
+    pub submitted: Vec<(String, CreatePaymentResponse)>,
+
+    /// The payouts that were completed by an earlier submission, with their
+    /// references and payment ids
+    pub skipped: Vec<(String, String)>,
+
+    /// The payouts that failed, with their references. Submit the batch again
+    /// to retry them.
+    pub failed: Vec<(String, Error)>,
+}
+
+impl BatchReport {
+    /// Returns whether every payout of the batch was completed
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Resolves to the outputs of all futures, in order
+pub(crate) struct JoinAll<F: Future> {
+    futures: Vec<Option<Pin<Box<F>>>>,
+    outputs: Vec<Option<F::Output>>,
+}
+
+impl<F: Future> JoinAll<F> {
+    pub(crate) fn new(futures: impl IntoIterator<Item = F>) -> JoinAll<F> {
+        let futures: Vec<_> = futures
+            .into_iter()
+            .map(|future| Some(Box::pin(future)))
+            .collect();
+        let outputs = futures.iter().map(|_| None).collect();
+        JoinAll { futures, outputs }
+    }
+}
+
+impl<F: Future> Unpin for JoinAll<F> {}
+
+impl<F: Future> Future for JoinAll<F> {
+    type Output = Vec<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        let mut pending = false;
+        for (slot, output) in this.futures.iter_mut().zip(this.outputs.iter_mut()) {
+            if let Some(future) = slot {
+                match future.as_mut().poll(cx) {
+                    Poll::Ready(value) => {
+                        *output = Some(value);
+                        *slot = None;
+                    }
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(this.outputs.iter_mut().filter_map(Option::take).collect())
+        }
+    }
+}
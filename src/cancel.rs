@@ -0,0 +1,57 @@
+//! Cancellation of in-flight requests
+//!
+//! Every future returned by the client can be dropped at any point. Besides
+//! the request itself, a call only updates state shared by the client's
+//! clones: the observed API version, the active failover host and the cached
+//! access tokens. Each is updated at once when a response, transport error or
+//! token was received, so a dropped call never leaves it half-written. A
+//! request that was already sent may still be processed by Checkout though,
+//! so requests that may be cancelled should carry an idempotency key to be
+//! retried safely.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Resolves to the output of `future`, or to `None` if `cancel` completes
+/// first
+pub(crate) struct Cancellable<F, C> {
+    future: Pin<Box<F>>,
+    cancel: Pin<Box<C>>,
+}
+
+impl<F, C> Cancellable<F, C>
+where
+    F: Future,
+    C: Future<Output = ()>,
+{
+    pub(crate) fn new(future: F, cancel: C) -> Cancellable<F, C> {
+        Cancellable {
+            future: Box::pin(future),
+            cancel: Box::pin(cancel),
+        }
+    }
+}
+
+impl<F, C> Future for Cancellable<F, C>
+where
+    F: Future,
+    C: Future<Output = ()>,
+{
+    type Output = Option<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // A response that is ready wins over a cancellation, so that a
+        // processed payment is never reported as cancelled
+        if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+            return Poll::Ready(Some(output));
+        }
+
+        match self.cancel.as_mut().poll(cx) {
+            Poll::Ready(()) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Compensation of captures that can't be completed
+//!
+//! Order management systems commonly capture when an order ships and release
+//! the authorization when the capture fails for a reason that retrying won't
+//! fix. [`Client::try_capture_or_void`](crate::Client::try_capture_or_void)
+//! implements that pattern.
+
+use crate::{ApiError, CapturePaymentResponse, Error, VoidPaymentResponse};
+
+/// The error codes reported when the authorization of a payment has expired
+const AUTHORIZATION_EXPIRED_CODES: &[&str] = &["authorization_expired", "payment_expired"];
+
+/// The error codes reported when the capture amount exceeds the amount that
+/// can still be captured
+const AMOUNT_EXCEEDED_CODES: &[&str] = &[
+    "amount_exceeds_balance",
+    "capture_value_greater_than_authorized",
+    "capture_value_greater_than_remaining_authorized",
+];
+
+/// A class of capture failures that can't be fixed by retrying the capture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFailure {
+    /// The authorization has expired
+    AuthorizationExpired,
+
+    /// The capture amount exceeds the amount that can still be captured
+    AmountExceeded,
+}
+
+impl CaptureFailure {
+    /// Returns the class of a capture error, if it is one that can't be fixed
+    /// by retrying the capture
+    #[must_use]
+    pub fn of(error: &Error) -> Option<CaptureFailure> {
+        match error {
+            Error::Api(error) | Error::InvalidData(error) => CaptureFailure::of_api_error(error),
+            Error::InvalidCapture(failure) => Some(*failure),
+            _ => None,
+        }
+    }
+
+    fn of_api_error(error: &ApiError) -> Option<CaptureFailure> {
+        error.error_codes.iter().find_map(|code| {
+            if AUTHORIZATION_EXPIRED_CODES.contains(&code.as_str()) {
+                Some(CaptureFailure::AuthorizationExpired)
+            } else if AMOUNT_EXCEEDED_CODES.contains(&code.as_str()) {
+                Some(CaptureFailure::AmountExceeded)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// The outcome of [`Client::try_capture_or_void`](crate::Client::try_capture_or_void)
+#[derive(Debug)]
+pub enum CaptureOutcome {
+    /// The capture was accepted
+    Captured(CapturePaymentResponse),
+
+    /// The capture failed and the authorization was voided
+    Voided {
+        /// Why the capture failed
+        failure: CaptureFailure,
+
+        /// The response to the void request
+        void: VoidPaymentResponse,
+    },
+
+    /// The capture failed and the authorization was not voided, either
+    /// because voiding was not requested or because the void failed
+    NotCaptured {
+        /// Why the capture failed
+        failure: CaptureFailure,
+
+        /// The error of the void request if it failed, otherwise the error of
+        /// the capture request
+        error: Error,
+    },
+}
+
+impl CaptureOutcome {
+    /// Returns whether the capture was accepted
+    #[must_use]
+    pub fn is_captured(&self) -> bool {
+        matches!(self, CaptureOutcome::Captured(_))
+    }
+
+    /// Returns why the capture failed, if it did
+    #[must_use]
+    pub fn failure(&self) -> Option<CaptureFailure> {
+        match self {
+            CaptureOutcome::Captured(_) => None,
+            CaptureOutcome::Voided { failure, .. }
+            | CaptureOutcome::NotCaptured { failure, .. } => Some(*failure),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Credential sets for the different Checkout products
+
+use secrecy::SecretString;
+
+use crate::Scope;
+
+/// A Checkout product area, which may require its own credentials and OAuth
+/// scope
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProductArea {
+    /// Payments, instruments, customers, disputes and hosted pages
+    Gateway,
+
+    /// Sub-entity onboarding and payouts of marketplaces
+    Platforms,
+
+    /// Card issuing
+    Issuing,
+
+    /// Standalone 3D Secure authentication sessions, in browsers and apps
+    Sessions,
+
+    /// Financial actions and their fee breakdowns
+    FinancialActions,
+}
+
+impl ProductArea {
+    /// Returns the OAuth scope requested for the product area
+    #[must_use]
+    pub fn scope(self) -> &'static str {
+        match self {
+            ProductArea::Gateway => "gateway",
+            ProductArea::Platforms => "accounts",
+            ProductArea::Issuing => "issuing:card-mgmt",
+            ProductArea::Sessions => "sessions:app sessions:browser",
+            ProductArea::FinancialActions => "financial-actions",
+        }
+    }
+
+    /// Returns the product area whose token grants `scope`
+    #[must_use]
+    pub fn of_scope(scope: &Scope) -> ProductArea {
+        match scope {
+            Scope::Accounts => ProductArea::Platforms,
+            Scope::Other(scope) if scope.starts_with("issuing") => ProductArea::Issuing,
+            Scope::Other(scope) if scope.starts_with("sessions") => ProductArea::Sessions,
+            Scope::Other(scope) if scope.starts_with("financial-actions") => {
+                ProductArea::FinancialActions
+            }
+            _ => ProductArea::Gateway,
+        }
+    }
+
+    /// Returns the product area an API url belongs to
+    #[must_use]
+    pub fn of_url(url: &str) -> ProductArea {
+        let path = url
+            .split("://")
+            .nth(1)
+            .and_then(|rest| rest.find('/').map(|index| &rest[index..]))
+            .unwrap_or("");
+
+        if path.starts_with("/accounts") {
+            ProductArea::Platforms
+        } else if path.starts_with("/issuing") {
+            ProductArea::Issuing
+        } else if path.starts_with("/sessions") {
+            ProductArea::Sessions
+        } else if path.starts_with("/financial-actions") {
+            ProductArea::FinancialActions
+        } else {
+            ProductArea::Gateway
+        }
+    }
+}
+
+/// The OAuth client credentials used to access a product area
+#[derive(Clone, Debug)]
+pub struct Credentials {
+    /// The OAuth client id
+    pub username: SecretString,
+
+    /// The OAuth client secret
+    pub password: SecretString,
+}
+
+impl Credentials {
+    /// Creates a credential set
+    #[must_use]
+    pub fn new(username: SecretString, password: SecretString) -> Credentials {
+        Credentials { username, password }
+    }
+}
@@ -0,0 +1,115 @@
+//! Customers and the instruments stored for them
+//!
+//! See: [Customers](https://api-reference.checkout.com/#tag/Customers)
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CardScheme, ExtraFields, InstrumentAccountHolder, Metadata, PhoneNumber};
+
+/// A customer and their stored instruments
+#[derive(Deserialize, Debug, Clone)]
+pub struct Customer {
+    /// The customer's unique identifier (format: `cus_*`)
+    pub id: String,
+
+    /// The customer's email address
+    pub email: Option<String>,
+
+    /// The customer's name
+    pub name: Option<String>,
+
+    /// The customer's phone number
+    pub phone: Option<PhoneNumber>,
+
+    /// A set of key-value pairs attached to the customer
+    pub metadata: Option<Metadata>,
+
+    /// The identifier of the customer's default instrument (format: `src_*`)
+    pub default: Option<String>,
+
+    /// The customer's stored instruments
+    #[serde(default)]
+    pub instruments: Vec<CustomerInstrument>,
+
+    /// Additional fields returned that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// The full details of a customer, as returned by
+/// [`Client::get_customer`](crate::Client::get_customer)
+pub type CustomerDetails = Customer;
+
+impl Customer {
+    /// Returns the customer's default instrument
+    #[must_use]
+    pub fn default_instrument(&self) -> Option<&CustomerInstrument> {
+        let default = self.default.as_deref()?;
+        self.instruments
+            .iter()
+            .find(|instrument| instrument.id == default)
+    }
+}
+
+/// An instrument stored for a customer
+#[derive(Deserialize, Debug, Clone)]
+pub struct CustomerInstrument {
+    /// The instrument's unique identifier (format: `src_*`)
+    pub id: String,
+
+    /// The instrument type
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// Uniquely identifies the underlying card or account
+    pub fingerprint: Option<String>,
+
+    /// The expiry month of the card (1-2 characters)
+    pub expiry_month: Option<u32>,
+
+    /// The expiry year of the card (4 characters)
+    pub expiry_year: Option<u32>,
+
+    /// The card scheme
+    pub scheme: Option<CardScheme>,
+
+    /// The last four digits of the card number
+    pub last4: Option<String>,
+
+    /// The card issuer's Bank Identification Number (BIN)
+    pub bin: Option<String>,
+
+    /// The instrument's account holder
+    pub account_holder: Option<InstrumentAccountHolder>,
+}
+
+/// Body used in the request to update a customer
+///
+/// Only the provided fields are updated.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UpdateCustomerBody {
+    /// The customer's email address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+
+    /// The customer's name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The customer's phone number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<PhoneNumber>,
+
+    /// A set of key-value pairs to attach to the customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Metadata>,
+
+    /// The identifier of the instrument to make the customer's default
+    /// (format: `src_*`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
@@ -0,0 +1,179 @@
+//! Deprecation and sunset notices of the endpoints the client calls
+//!
+//! Checkout announces that an endpoint will be retired with the
+//! `Deprecation` and `Sunset` response headers. A hook registered with
+//! [`Client::with_deprecation_hook`](crate::Client::with_deprecation_hook)
+//! receives a [`DeprecationNotice`] for every response that carries them,
+//! e.g. to log a warning or increment a metric.
+//!
+//! See: [RFC 8594](https://www.rfc-editor.org/rfc/rfc8594) and
+//! [RFC 9745](https://www.rfc-editor.org/rfc/rfc9745)
+
+use std::{fmt, sync::Arc};
+
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::{header::HeaderMap, Method, Url};
+
+use crate::{audit::CKO_VERSION_HEADER, Endpoint};
+
+/// The header announcing that an endpoint is deprecated
+pub const DEPRECATION_HEADER: &str = "deprecation";
+
+/// The header announcing when an endpoint will stop responding
+pub const SUNSET_HEADER: &str = "sunset";
+
+/// The link relations of the documentation of a deprecation
+const DEPRECATION_LINK_RELATIONS: &[&str] = &["deprecation", "sunset"];
+
+/// An endpoint that responded with a deprecation or sunset notice
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationNotice {
+    /// The endpoint that is deprecated
+    pub endpoint: Endpoint,
+
+    /// The API version reported in the `Cko-Version` header, if any
+    pub api_version: Option<String>,
+
+    /// Whether the `Deprecation` header marks the endpoint as deprecated
+    pub deprecated: bool,
+
+    /// When the endpoint was or will be deprecated, if announced
+    pub deprecated_at: Option<DateTime<Utc>>,
+
+    /// When the endpoint will stop responding, if announced
+    pub sunset_at: Option<DateTime<Utc>>,
+
+    /// The documentation of the deprecation, from the `Link` header
+    pub link: Option<String>,
+}
+
+impl DeprecationNotice {
+    /// Returns the notice of a response, or `None` if the response has
+    /// neither a `Deprecation` nor a `Sunset` header
+    pub(crate) fn from_headers(
+        method: Method,
+        url: &Url,
+        headers: &HeaderMap,
+    ) -> Option<DeprecationNotice> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        let deprecation = header(DEPRECATION_HEADER);
+        let sunset = header(SUNSET_HEADER);
+        if deprecation.is_none() && sunset.is_none() {
+            return None;
+        }
+
+        let deprecated_at = deprecation.and_then(parse_deprecation_date);
+        Some(DeprecationNotice {
+            endpoint: Endpoint::new(method, url),
+            api_version: header(CKO_VERSION_HEADER).map(str::to_string),
+            deprecated: deprecated_at.is_some()
+                || deprecation.is_some_and(|value| value.eq_ignore_ascii_case("true")),
+            deprecated_at,
+            sunset_at: sunset.and_then(parse_http_date),
+            link: headers
+                .get_all(reqwest::header::LINK)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .find_map(deprecation_link),
+        })
+    }
+
+    /// Returns whether the sunset date has passed, after which the endpoint
+    /// may stop responding at any time
+    #[must_use]
+    pub fn is_sunset(&self) -> bool {
+        self.sunset_at.is_some_and(|sunset| sunset <= Utc::now())
+    }
+}
+
+impl fmt::Display for DeprecationNotice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is deprecated", self.endpoint)?;
+        if let Some(sunset) = self.sunset_at {
+            write!(f, " and will stop responding on {}", sunset.to_rfc3339())?;
+        }
+        if let Some(link) = &self.link {
+            write!(f, " (see {link})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the date of a `Deprecation` header, either a Unix timestamp such
+/// as `@1688169599` or, as in earlier drafts, an HTTP date
+fn parse_deprecation_date(value: &str) -> Option<DateTime<Utc>> {
+    match value.strip_prefix('@') {
+        Some(timestamp) => Utc.timestamp_opt(timestamp.parse().ok()?, 0).single(),
+        None => parse_http_date(value),
+    }
+}
+
+/// Parses an HTTP date, e.g. `Sat, 31 Dec 2022 23:59:59 GMT`
+fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Returns the target of the first link of a `Link` header with a
+/// deprecation relation, e.g. `<https://example.com>; rel="deprecation"`
+fn deprecation_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let target = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let is_deprecation = parts.any(|param| {
+            let (name, value) = param.split_once('=').unwrap_or((param, ""));
+            name.trim().eq_ignore_ascii_case("rel")
+                && value
+                    .trim()
+                    .trim_matches('"')
+                    .split_whitespace()
+                    .any(|rel| DEPRECATION_LINK_RELATIONS.contains(&rel))
+        });
+        if is_deprecation {
+            Some(target.to_string())
+        } else {
+            None
+        }
+    })
+}
+
+/// A callback that receives the [`DeprecationNotice`]s of a client's
+/// responses
+///
+/// The callback is called for every response with a notice, so it should
+/// not block:
+///
+/// ```ignore
+/// let client = client.with_deprecation_hook(DeprecationHook::new(|notice| {
+///     log::warn!("{}", notice);
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct DeprecationHook(Arc<dyn Fn(&DeprecationNotice) + Send + Sync>);
+
+impl DeprecationHook {
+    /// Creates a new hook from a callback
+    #[must_use]
+    pub fn new<F>(hook: F) -> DeprecationHook
+    where
+        F: Fn(&DeprecationNotice) + Send + Sync + 'static,
+    {
+        DeprecationHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, notice: &DeprecationNotice) {
+        (self.0)(notice);
+    }
+}
+
+impl fmt::Debug for DeprecationHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DeprecationHook")
+    }
+}
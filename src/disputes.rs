@@ -0,0 +1,302 @@
+//! Disputes and the evidence and files provided to challenge them
+//!
+//! See: [Disputes](https://docs.checkout.com/disputes)
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::types::{links::Links, Address, Amount, Currency, ExtraFields};
+
+pub use crate::types::{DisputeFilter, UploadFileResponse};
+pub use crate::{FileUpload, UploadError, UploadProgress, MAX_FILE_SIZE};
+
+/// The status of a dispute
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisputeStatus {
+    /// Evidence must be provided to challenge the dispute
+    EvidenceRequired,
+
+    /// The provided evidence is being reviewed by the card scheme
+    EvidenceUnderReview,
+
+    /// The dispute was resolved before a chargeback
+    Resolved,
+
+    /// The dispute was closed by the card scheme
+    Closed,
+
+    /// The dispute was decided in your favor
+    Won,
+
+    /// The dispute was decided in the cardholder's favor
+    Lost,
+
+    /// The dispute was canceled by the cardholder or issuer
+    Canceled,
+
+    /// No evidence was provided before the deadline
+    Expired,
+
+    /// You accepted the dispute
+    Accepted,
+
+    /// The dispute was escalated to arbitration by the card scheme
+    ArbitrationUnderReview,
+
+    /// The arbitration was decided in your favor
+    ArbitrationWon,
+
+    /// The arbitration was decided in the cardholder's favor
+    ArbitrationLost,
+}
+
+impl DisputeStatus {
+    /// Returns the status as it is represented by the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DisputeStatus::EvidenceRequired => "evidence_required",
+            DisputeStatus::EvidenceUnderReview => "evidence_under_review",
+            DisputeStatus::Resolved => "resolved",
+            DisputeStatus::Closed => "closed",
+            DisputeStatus::Won => "won",
+            DisputeStatus::Lost => "lost",
+            DisputeStatus::Canceled => "canceled",
+            DisputeStatus::Expired => "expired",
+            DisputeStatus::Accepted => "accepted",
+            DisputeStatus::ArbitrationUnderReview => "arbitration_under_review",
+            DisputeStatus::ArbitrationWon => "arbitration_won",
+            DisputeStatus::ArbitrationLost => "arbitration_lost",
+        }
+    }
+}
+
+/// A summary of a dispute, as returned when listing disputes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DisputeSummary {
+    /// The dispute's unique identifier (format: `dsp_*`)
+    pub id: String,
+
+    /// The reason for the dispute
+    pub category: String,
+
+    /// The current status of the dispute
+    pub status: DisputeStatus,
+
+    /// The disputed amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code of the disputed amount
+    pub currency: Currency,
+
+    /// The reason code provided by the card scheme
+    pub reason_code: Option<String>,
+
+    /// The identifier of the disputed payment (format: `pay_*`)
+    pub payment_id: Option<String>,
+
+    /// Your reference for the disputed payment
+    pub payment_reference: Option<String>,
+
+    /// The deadline to respond to the dispute
+    pub evidence_required_by: Option<DateTime<Utc>>,
+
+    /// The date/time the dispute was received
+    pub received_on: Option<DateTime<Utc>>,
+
+    /// The date/time the dispute was last updated
+    pub last_update: Option<DateTime<Utc>>,
+
+    /// The links related to the dispute
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// Response to list disputes
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetDisputesResponse {
+    /// The number of disputes returned
+    pub limit: u32,
+
+    /// The number of disputes skipped
+    pub skip: u32,
+
+    /// The total number of disputes matching the filter
+    pub total_count: u32,
+
+    /// The disputes
+    #[serde(default)]
+    pub data: Vec<DisputeSummary>,
+}
+
+/// A file sent by the card scheme during a stage of a dispute
+#[derive(Deserialize, Debug, Clone)]
+pub struct SchemeFile {
+    /// The status of the dispute when the file was received
+    pub dispute_status: String,
+
+    /// The identifier of the file, which can be used to download it from the
+    /// Files API (format: `file_*`)
+    pub file: String,
+}
+
+/// Response to get the scheme files of a dispute
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetDisputeSchemeFilesResponse {
+    /// The dispute's unique identifier (format: `dsp_*`)
+    pub id: String,
+
+    /// The scheme files of the dispute
+    pub files: Vec<SchemeFile>,
+
+    /// The links related to the scheme files
+    ///
+    /// - Required: `"self"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// Body used in the request to provide evidence for a dispute
+///
+/// Every field is optional; only the evidence relevant to the dispute's
+/// reason code needs to be provided.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ProvideDisputeEvidenceBody {
+    /// A file containing information about the product or service provided
+    /// (format: `file_*`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_of_delivery_or_service_file: Option<String>,
+
+    /// A brief text description of the proof of delivery or service (<= 500
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proof_of_delivery_or_service_text: Option<String>,
+
+    /// A file containing the invoice or showing the product was not
+    /// subject to the dispute (format: `file_*`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_or_receipt_file: Option<String>,
+
+    /// A brief text description of the invoice or receipt (<= 500
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_or_receipt_text: Option<String>,
+
+    /// Visa Compelling Evidence 3.0 for disputes with reason code 10.4
+    /// (other fraud, card-absent environment)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compelling_evidence: Option<CompellingEvidence>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// Visa Compelling Evidence 3.0 (CE 3.0)
+///
+/// To qualify, provide at least two prior undisputed transactions made with
+/// the same card 120 to 365 days before the disputed transaction, sharing at
+/// least two identifying data elements with it (of which one must be the IP
+/// address or device identifier).
+///
+/// See: [Compelling Evidence 3.0](https://docs.checkout.com/disputes/respond-to-disputes/visa-compelling-evidence-3-0)
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct CompellingEvidence {
+    /// The type of goods or service of the disputed transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchandise_or_service: Option<MerchandiseOrService>,
+
+    /// A description of the goods or service (<= 1000 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchandise_or_service_description: Option<String>,
+
+    /// The date the goods or service were provided (yyyy-mm-dd)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchandise_or_service_provided_date: Option<String>,
+
+    /// The data elements of the disputed transaction
+    pub disputed_transaction: TransactionEvidence,
+
+    /// The prior undisputed transactions (2-5 transactions)
+    pub prior_transactions: Vec<PriorTransaction>,
+}
+
+/// The type of goods or service of a disputed transaction
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MerchandiseOrService {
+    /// Physical goods
+    Merchandise,
+
+    /// Digital goods, e.g. downloads or subscriptions
+    DigitalGoods,
+
+    /// Services
+    Services,
+}
+
+/// A prior undisputed transaction used as compelling evidence
+#[derive(Serialize, Debug, Clone)]
+pub struct PriorTransaction {
+    /// The payment's unique identifier (format: `pay_*`)
+    pub payment_id: String,
+
+    /// The date/time the payment was processed
+    pub processed_on: String,
+
+    /// The data elements of the transaction matching the disputed
+    /// transaction
+    #[serde(flatten)]
+    pub evidence: TransactionEvidence,
+}
+
+/// The identifying data elements of a transaction that are compared between
+/// the disputed and prior transactions
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct TransactionEvidence {
+    /// The customer's IP address (<= 45 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+
+    /// The customer's device identifier or fingerprint (<= 200 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+
+    /// The customer's account or login identifier with the merchant (<= 200
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_account_id: Option<String>,
+
+    /// The shipping address used for the transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_address: Option<Address>,
+}
+
+impl CompellingEvidence {
+    /// Returns whether the evidence meets the minimum CE 3.0 requirements: at
+    /// least two prior transactions, each sharing at least two data elements
+    /// with the disputed transaction, one of which is the IP address or
+    /// device identifier
+    #[must_use]
+    pub fn is_qualifying(&self) -> bool {
+        let disputed = &self.disputed_transaction;
+        let matches = |a: &Option<String>, b: &Option<String>| a.is_some() && a == b;
+
+        self.prior_transactions.len() >= 2
+            && self.prior_transactions.iter().all(|prior| {
+                let prior = &prior.evidence;
+                let ip = matches(&prior.ip_address, &disputed.ip_address);
+                let device = matches(&prior.device_id, &disputed.device_id);
+                let account = matches(&prior.customer_account_id, &disputed.customer_account_id);
+                let shipping = prior.shipping_address.is_some()
+                    && prior.shipping_address == disputed.shipping_address;
+
+                let count = [ip, device, account, shipping]
+                    .iter()
+                    .filter(|matched| **matched)
+                    .count();
+                (ip || device) && count >= 2
+            })
+    }
+}
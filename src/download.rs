@@ -0,0 +1,111 @@
+//! Streaming downloads of large files, e.g. report files
+//!
+//! The body of a download is read in chunks as it arrives instead of being
+//! buffered in memory, so that files of hundreds of megabytes can be written
+//! to disk or forwarded to storage.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use reqwest::Response;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::Error;
+
+/// A file that is being downloaded
+///
+/// Read it chunk by chunk with [`Download::chunk`], as a [`Stream`] of
+/// chunks, or write it to a file with [`Download::download_to`].
+pub struct Download {
+    content_length: Option<u64>,
+    content_type: Option<String>,
+    body: Pin<Box<dyn Stream<Item = reqwest::Result<Bytes>> + Send>>,
+}
+
+impl Download {
+    pub(crate) fn new(response: Response) -> Download {
+        Download {
+            content_length: response.content_length(),
+            content_type: crate::audit::header_value(&response, "content-type"),
+            body: Box::pin(response.bytes_stream()),
+        }
+    }
+
+    /// Returns the size of the file in bytes, if known
+    #[must_use]
+    pub fn content_length(&self) -> Option<u64> {
+        self.content_length
+    }
+
+    /// Returns the media type of the file, e.g. `"text/csv"`, if known
+    #[must_use]
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// Returns the next chunk of the file, or `None` once it was read
+    /// completely
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails during the download
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, Error> {
+        NextChunk(self).await.transpose()
+    }
+
+    /// Writes the file to `writer`, returning the number of bytes written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails during the download or the
+    /// file cannot be written
+    pub async fn download_to<W>(mut self, writer: &mut W) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut written = 0;
+        while let Some(chunk) = self.chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+}
+
+impl Stream for Download {
+    type Item = Result<Bytes, Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.body
+            .as_mut()
+            .poll_next(cx)
+            .map(|chunk| chunk.map(|chunk| chunk.map_err(Error::from)))
+    }
+}
+
+impl fmt::Debug for Download {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Download")
+            .field("content_length", &self.content_length)
+            .field("content_type", &self.content_type)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Resolves to the next chunk of a download
+struct NextChunk<'a>(&'a mut Download);
+
+impl Future for NextChunk<'_> {
+    type Output = Option<Result<Bytes, Error>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.0).poll_next(cx)
+    }
+}
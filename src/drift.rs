@@ -0,0 +1,315 @@
+//! Detection of drift between Checkout's published API spec and the
+//! endpoints and request fields modeled by this library
+//!
+//! Print a report of the gaps with
+//! `cargo run --example schema_drift -- <spec url or file>`.
+//!
+//! The modeled request fields are taken from serialized sample requests, so a
+//! field is only known once it is part of a request type.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use chrono::Utc;
+
+use crate::{
+    Amount, BillingDescriptor, CapturePaymentBody, CaptureType, CreatePaymentRequest, Currency,
+    CustomerDescriptor, Metadata, Money, PaymentAmount, PaymentProcessingDescriptor,
+    PaymentRecipient, PaymentRequestDestination, PaymentRequestSource, PaymentType, PayoutSender,
+    ProcessingChannelId, RefundPaymentBody, RiskRequest, ShippingDescriptor, StoreDescriptor,
+    ThreeDsRequest, VoidPaymentBody,
+};
+
+/// The endpoints implemented by [`Client`](crate::Client), as `(method, path)`
+pub const COVERED_ENDPOINTS: &[(&str, &str)] = &[
+    ("POST", "/payments"),
+    ("GET", "/payments/{id}"),
+    ("GET", "/payments/{id}/actions"),
+    ("POST", "/payments/{id}/captures"),
+    ("POST", "/payments/{id}/refunds"),
+    ("POST", "/payments/{id}/voids"),
+    ("POST", "/instruments"),
+    ("PATCH", "/instruments/{id}"),
+    ("DELETE", "/instruments/{id}"),
+    ("GET", "/customers/{id}"),
+    ("PATCH", "/customers/{id}"),
+    ("GET", "/disputes"),
+    ("POST", "/disputes/{id}/evidence"),
+    ("PUT", "/disputes/{id}/evidence"),
+    ("GET", "/disputes/{id}/schemefiles"),
+    ("POST", "/files"),
+    ("POST", "/hosted-payments"),
+    ("POST", "/payment-links"),
+    ("POST", "/metadata/card"),
+    ("POST", "/sessions"),
+    ("GET", "/reports"),
+    ("GET", "/reports/{id}/files/{fileId}"),
+    ("GET", "/reporting/statements"),
+    ("GET", "/financial-actions"),
+];
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "patch"];
+
+/// The differences between the spec and this library
+#[derive(Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct DriftReport {
+    /// The version of the spec, from `info.version`
+    pub spec_version: Option<String>,
+
+    /// Endpoints of the spec that are not implemented by the client
+    pub missing_endpoints: Vec<String>,
+
+    /// Endpoints implemented by the client that are not in the spec
+    pub removed_endpoints: Vec<String>,
+
+    /// Request body fields of the spec that are not modeled, by endpoint
+    pub missing_fields: BTreeMap<String, Vec<String>>,
+
+    /// Modeled request body fields that are not in the spec, by endpoint
+    pub removed_fields: BTreeMap<String, Vec<String>>,
+}
+
+impl DriftReport {
+    /// Returns whether the spec no longer has endpoints or fields this
+    /// library sends, which likely breaks requests
+    #[must_use]
+    pub fn is_breaking(&self) -> bool {
+        !self.removed_endpoints.is_empty() || !self.removed_fields.is_empty()
+    }
+}
+
+/// Compares an `OpenAPI` 3 spec with the endpoints and request fields modeled
+/// by this library
+#[must_use]
+pub fn schema_drift(spec: &Value) -> DriftReport {
+    let mut report = DriftReport {
+        spec_version: spec
+            .pointer("/info/version")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        ..DriftReport::default()
+    };
+
+    let mut spec_endpoints = BTreeMap::new();
+    if let Some(paths) = spec.get("paths").and_then(Value::as_object) {
+        for (path, item) in paths {
+            for method in METHODS {
+                if let Some(operation) = item.get(*method) {
+                    let key = endpoint_key(&method.to_ascii_uppercase(), path);
+                    spec_endpoints.insert(
+                        key,
+                        (
+                            format!("{} {}", method.to_ascii_uppercase(), path),
+                            operation,
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    let covered: BTreeSet<String> = COVERED_ENDPOINTS
+        .iter()
+        .map(|(method, path)| endpoint_key(method, path))
+        .collect();
+
+    for (key, (endpoint, _)) in &spec_endpoints {
+        if !covered.contains(key) {
+            report.missing_endpoints.push(endpoint.clone());
+        }
+    }
+    for (method, path) in COVERED_ENDPOINTS {
+        if !spec_endpoints.contains_key(&endpoint_key(method, path)) {
+            report.removed_endpoints.push(format!("{method} {path}"));
+        }
+    }
+
+    for (method, path, sample) in modeled_requests() {
+        let Some((_, operation)) = spec_endpoints.get(&endpoint_key(method, path)) else {
+            continue;
+        };
+        let Some(schema) = operation.pointer("/requestBody/content/application~1json/schema")
+        else {
+            continue;
+        };
+
+        let mut spec_fields = BTreeSet::new();
+        collect_properties(spec, schema, &mut spec_fields, 0);
+        let modeled: BTreeSet<String> = sample
+            .as_object()
+            .map(|body| body.keys().cloned().collect())
+            .unwrap_or_default();
+
+        let endpoint = format!("{method} {path}");
+        let missing: Vec<String> = spec_fields.difference(&modeled).cloned().collect();
+        if !missing.is_empty() {
+            report.missing_fields.insert(endpoint.clone(), missing);
+        }
+        let removed: Vec<String> = modeled.difference(&spec_fields).cloned().collect();
+        if !removed.is_empty() {
+            report.removed_fields.insert(endpoint, removed);
+        }
+    }
+
+    report
+}
+
+/// Identifies an endpoint regardless of the names of its path parameters
+fn endpoint_key(method: &str, path: &str) -> String {
+    let mut key = format!("{method} ");
+    let mut in_parameter = false;
+    for c in path.chars() {
+        match c {
+            '{' => {
+                in_parameter = true;
+                key.push_str("{}");
+            }
+            '}' => in_parameter = false,
+            c if !in_parameter => key.push(c),
+            _ => {}
+        }
+    }
+    key
+}
+
+/// Collects the property names of a schema, following references and
+/// compositions
+fn collect_properties(spec: &Value, schema: &Value, fields: &mut BTreeSet<String>, depth: usize) {
+    if depth > 8 {
+        return;
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        if let Some(target) = reference
+            .strip_prefix('#')
+            .and_then(|pointer| spec.pointer(pointer))
+        {
+            collect_properties(spec, target, fields, depth + 1);
+        }
+        return;
+    }
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        fields.extend(properties.keys().cloned());
+    }
+    for composition in &["allOf", "oneOf", "anyOf"] {
+        if let Some(schemas) = schema.get(*composition).and_then(Value::as_array) {
+            for schema in schemas {
+                collect_properties(spec, schema, fields, depth + 1);
+            }
+        }
+    }
+}
+
+/// Returns sample request bodies with every modeled field present. The
+/// bodies are built without `..` so that a new field can't be left out.
+fn modeled_requests() -> Vec<(&'static str, &'static str, Value)> {
+    let money = Money {
+        amount: Amount::from_minor_units(100),
+        currency: Currency::GBP,
+    };
+    let metadata = Metadata::from([("drift".to_string(), "sample".to_string())]);
+
+    let payment = CreatePaymentRequest {
+        source: Some(PaymentRequestSource::Id {
+            id: "src_drift".to_string(),
+            cvv: None,
+        }),
+        destination: Some(PaymentRequestDestination::Card {
+            number: String::new(),
+            expiry_month: String::new(),
+            expiry_year: String::new(),
+            first_name: String::new(),
+            last_name: String::new(),
+            name: None,
+            billing_address: None,
+            phone: None,
+        }),
+        amount: PaymentAmount::Charge(money),
+        payment_type: PaymentType::Regular,
+        merchant_initiated: false,
+        reference: Some(String::new()),
+        description: Some(String::new()),
+        capture: Some(true),
+        capture_on: Some(Utc::now()),
+        customer: Some(CustomerDescriptor {
+            id: None,
+            email: None,
+            name: None,
+        }),
+        billing_descriptor: Some(BillingDescriptor {
+            name: String::new(),
+            city: String::new(),
+        }),
+        shipping: Some(ShippingDescriptor {
+            address: None,
+            phone: None,
+        }),
+        three_ds: Some(ThreeDsRequest {
+            enabled: None,
+            attempt_n3d: None,
+            sci: None,
+            cryptogram: None,
+            xid: None,
+            version: None,
+            exemption: None,
+        }),
+        previous_payment_id: Some(String::new()),
+        risk: Some(RiskRequest {
+            enabled: true,
+            device_session_id: None,
+        }),
+        success_url: Some(String::new()),
+        failure_url: Some(String::new()),
+        payment_ip: Some([127, 0, 0, 1].into()),
+        sender: Some(PayoutSender::Corporate {
+            company_name: String::new(),
+            address: None,
+            reference: None,
+        }),
+        recipient: Some(PaymentRecipient {
+            dob: None,
+            account_number: None,
+            zip: None,
+            first_name: None,
+            last_name: None,
+        }),
+        processing_channel_id: ProcessingChannelId::new("pc_drift").ok(),
+        processing: Some(PaymentProcessingDescriptor::default()),
+        store: Some(StoreDescriptor::default()),
+        items: Some(Vec::new()),
+        metadata: Some(metadata.clone()),
+        extra: None,
+    };
+
+    let capture = CapturePaymentBody {
+        amount: Some(money),
+        capture_type: Some(CaptureType::Final),
+        reference: Some(String::new()),
+        metadata: Some(metadata.clone()),
+        extra: None,
+    };
+    let refund = RefundPaymentBody {
+        amount: Some(money),
+        reference: Some(String::new()),
+        metadata: Some(metadata.clone()),
+        extra: None,
+    };
+    let void = VoidPaymentBody {
+        reference: Some(String::new()),
+        metadata: Some(metadata),
+    };
+
+    vec![
+        ("POST", "/payments", to_value(&payment)),
+        ("POST", "/payments/{id}/captures", to_value(&capture)),
+        ("POST", "/payments/{id}/refunds", to_value(&refund)),
+        ("POST", "/payments/{id}/voids", to_value(&void)),
+    ]
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
@@ -0,0 +1,219 @@
+//! Lifecycle events of the payments made through a client
+//!
+//! A hook registered with [`Client::with_event_hook`](crate::Client::with_event_hook)
+//! receives a [`PaymentEvent`] whenever a call changes the state of a payment,
+//! e.g. to append it to an event store. Events are derived from the calls and
+//! their responses: a capture or refund that was accepted by Checkout may
+//! still fail later, which is reported by webhooks.
+
+use std::{fmt, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    CapturePaymentResponse, CreatePaymentResponse, Currency, PaymentStatus, RefundPaymentResponse,
+    VoidPaymentResponse,
+};
+
+/// A change of a payment's state caused by a call of the client
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum PaymentEvent {
+    /// A payment or payout was requested and created
+    PaymentCreated {
+        /// When the response was received
+        occurred_at: DateTime<Utc>,
+
+        /// The payment's unique identifier (format: `pay_*`)
+        payment_id: String,
+
+        /// The status of the payment
+        status: PaymentStatus,
+
+        /// Whether the payment was approved, or `None` if it is pending
+        approved: Option<bool>,
+
+        /// The amount of the payment in minor units, if it was processed
+        amount: Option<u64>,
+
+        /// The currency of the payment, if it was processed
+        currency: Option<Currency>,
+
+        /// Your reference for the payment
+        reference: Option<String>,
+    },
+
+    /// A capture of a payment was accepted
+    CaptureRequested {
+        /// When the response was received
+        occurred_at: DateTime<Utc>,
+
+        /// The unique identifier of the payment
+        payment_id: String,
+
+        /// The unique identifier of the capture action (format: `act_*`)
+        action_id: String,
+
+        /// The amount to capture in minor units, or `None` for the full
+        /// amount
+        amount: Option<u64>,
+
+        /// Your reference for the capture
+        reference: Option<String>,
+    },
+
+    /// A refund of a payment was accepted
+    RefundRequested {
+        /// When the response was received
+        occurred_at: DateTime<Utc>,
+
+        /// The unique identifier of the payment
+        payment_id: String,
+
+        /// The unique identifier of the refund action (format: `act_*`)
+        action_id: String,
+
+        /// The amount to refund in minor units, or `None` for the full
+        /// captured amount
+        amount: Option<u64>,
+
+        /// Your reference for the refund
+        reference: Option<String>,
+    },
+
+    /// A void of a payment was accepted
+    VoidSucceeded {
+        /// When the response was received
+        occurred_at: DateTime<Utc>,
+
+        /// The unique identifier of the payment
+        payment_id: String,
+
+        /// The unique identifier of the void action (format: `act_*`)
+        action_id: String,
+
+        /// Your reference for the void
+        reference: Option<String>,
+    },
+}
+
+impl PaymentEvent {
+    pub(crate) fn payment_created(response: &CreatePaymentResponse) -> PaymentEvent {
+        let (status, approved, amount, currency, reference) = match response {
+            CreatePaymentResponse::Processed(payment) => (
+                payment.status,
+                Some(payment.approved),
+                Some(payment.amount.minor_units()),
+                Some(payment.currency),
+                payment.reference.clone(),
+            ),
+            CreatePaymentResponse::Pending(payment) => {
+                (payment.status, None, None, None, payment.reference.clone())
+            }
+        };
+        PaymentEvent::PaymentCreated {
+            occurred_at: Utc::now(),
+            payment_id: response.payment_id().to_string(),
+            status,
+            approved,
+            amount,
+            currency,
+            reference,
+        }
+    }
+
+    pub(crate) fn capture_requested(
+        payment_id: &str,
+        amount: Option<u64>,
+        response: &CapturePaymentResponse,
+    ) -> PaymentEvent {
+        PaymentEvent::CaptureRequested {
+            occurred_at: Utc::now(),
+            payment_id: payment_id.to_string(),
+            action_id: response.action_id.clone(),
+            amount,
+            reference: response.reference.clone(),
+        }
+    }
+
+    pub(crate) fn refund_requested(
+        payment_id: &str,
+        amount: Option<u64>,
+        response: &RefundPaymentResponse,
+    ) -> PaymentEvent {
+        PaymentEvent::RefundRequested {
+            occurred_at: Utc::now(),
+            payment_id: payment_id.to_string(),
+            action_id: response.action_id.clone(),
+            amount,
+            reference: response.reference.clone(),
+        }
+    }
+
+    pub(crate) fn void_succeeded(payment_id: &str, response: &VoidPaymentResponse) -> PaymentEvent {
+        PaymentEvent::VoidSucceeded {
+            occurred_at: Utc::now(),
+            payment_id: payment_id.to_string(),
+            action_id: response.action_id.clone(),
+            reference: response.reference.clone(),
+        }
+    }
+
+    /// Returns the unique identifier of the payment the event belongs to
+    #[must_use]
+    pub fn payment_id(&self) -> &str {
+        match self {
+            PaymentEvent::PaymentCreated { payment_id, .. }
+            | PaymentEvent::CaptureRequested { payment_id, .. }
+            | PaymentEvent::RefundRequested { payment_id, .. }
+            | PaymentEvent::VoidSucceeded { payment_id, .. } => payment_id,
+        }
+    }
+
+    /// Returns when the event occurred
+    #[must_use]
+    pub fn occurred_at(&self) -> DateTime<Utc> {
+        match self {
+            PaymentEvent::PaymentCreated { occurred_at, .. }
+            | PaymentEvent::CaptureRequested { occurred_at, .. }
+            | PaymentEvent::RefundRequested { occurred_at, .. }
+            | PaymentEvent::VoidSucceeded { occurred_at, .. } => *occurred_at,
+        }
+    }
+}
+
+/// A callback that receives the [`PaymentEvent`]s of a client
+///
+/// The callback is called on the task making the request, so it should not
+/// block. To process events elsewhere, send them to a channel:
+///
+/// ```ignore
+/// let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+/// let client = client.with_event_hook(EventHook::new(move |event| {
+///     let _ = sender.send(event.clone());
+/// }));
+/// ```
+#[derive(Clone)]
+pub struct EventHook(Arc<dyn Fn(&PaymentEvent) + Send + Sync>);
+
+impl EventHook {
+    /// Creates a new hook from a callback
+    #[must_use]
+    pub fn new<F>(hook: F) -> EventHook
+    where
+        F: Fn(&PaymentEvent) + Send + Sync + 'static,
+    {
+        EventHook(Arc::new(hook))
+    }
+
+    pub(crate) fn call(&self, event: &PaymentEvent) {
+        (self.0)(event);
+    }
+}
+
+impl fmt::Debug for EventHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EventHook")
+    }
+}
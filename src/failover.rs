@@ -0,0 +1,131 @@
+//! Failover between a prioritized list of API hosts for accounts enrolled in
+//! Checkout's multi-region availability setups.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How long requests are sent to a fallback host before the primary host is
+/// tried again, unless set with [`ApiHosts::with_primary_retry_interval`]
+// `Duration::from_mins` is too recent for the toolchains the crate supports
+#[allow(clippy::duration_suboptimal_units)]
+pub const DEFAULT_PRIMARY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A prioritized list of API base URLs. The first host is the primary.
+///
+/// Requests are sent to the currently active host. When a host cannot be
+/// reached the next host is activated and the request is sent to it. Once
+/// the primary retry interval has passed, requests are sent to the primary
+/// host again, and [`Client::check_hosts`](crate::Client::check_hosts) falls
+/// back to the highest priority host that is healthy right away.
+///
+/// Only the API hosts fail over. Access tokens are always requested from the
+/// [`access_url`](crate::Environment::access_url) of the client's
+/// environment.
+#[derive(Clone, Debug)]
+pub struct ApiHosts {
+    hosts: Vec<String>,
+    active: Arc<AtomicUsize>,
+    failed_over_at: Arc<Mutex<Option<Instant>>>,
+    primary_retry_interval: Duration,
+}
+
+impl ApiHosts {
+    /// Creates a new list of hosts, ordered from highest to lowest priority
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hosts` is empty
+    #[must_use]
+    pub fn new(hosts: Vec<String>) -> ApiHosts {
+        assert!(!hosts.is_empty(), "at least one API host is required");
+        ApiHosts {
+            hosts: hosts
+                .into_iter()
+                .map(|host| host.trim_end_matches('/').to_string())
+                .collect(),
+            active: Arc::new(AtomicUsize::new(0)),
+            failed_over_at: Arc::default(),
+            primary_retry_interval: DEFAULT_PRIMARY_RETRY_INTERVAL,
+        }
+    }
+
+    /// Sets how long requests are sent to a fallback host before the primary
+    /// host is tried again
+    #[must_use]
+    pub fn with_primary_retry_interval(mut self, interval: Duration) -> ApiHosts {
+        self.primary_retry_interval = interval;
+        self
+    }
+
+    /// Returns the hosts, ordered from highest to lowest priority
+    #[must_use]
+    pub fn hosts(&self) -> &[String] {
+        &self.hosts
+    }
+
+    /// Returns the base URL of the currently active host, falling back to
+    /// the primary host once the primary retry interval has passed
+    #[must_use]
+    pub fn active(&self) -> &str {
+        if let Ok(mut failed_over_at) = self.failed_over_at.lock() {
+            let retry_primary = failed_over_at.is_some_and(|failed_over_at| {
+                failed_over_at.elapsed() >= self.primary_retry_interval
+            });
+            if retry_primary {
+                *failed_over_at = None;
+                self.active.store(0, Ordering::SeqCst);
+            }
+        }
+        &self.hosts[self.active.load(Ordering::SeqCst)]
+    }
+
+    /// Returns whether the primary host is the active host
+    #[must_use]
+    pub fn is_primary_active(&self) -> bool {
+        self.active.load(Ordering::SeqCst) == 0
+    }
+
+    /// Activates the next host after `failed`, wrapping around to the primary.
+    /// Does nothing if another request already moved away from `failed`.
+    pub(crate) fn fail_over(&self, failed: &str) {
+        let current = self.active.load(Ordering::SeqCst);
+        if self.hosts[current] == failed {
+            let next = (current + 1) % self.hosts.len();
+            if self
+                .active
+                .compare_exchange(current, next, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+            {
+                self.record_activation(next);
+            }
+        }
+    }
+
+    pub(crate) fn activate(&self, index: usize) {
+        self.active.store(index, Ordering::SeqCst);
+        self.record_activation(index);
+    }
+
+    /// Returns the host that serves `url`, if any
+    pub(crate) fn host_of(&self, url: &str) -> Option<&str> {
+        self.hosts
+            .iter()
+            .find(|host| url.starts_with(host.as_str()))
+            .map(String::as_str)
+    }
+
+    fn record_activation(&self, index: usize) {
+        if let Ok(mut failed_over_at) = self.failed_over_at.lock() {
+            *failed_over_at = if index == 0 {
+                None
+            } else {
+                Some(Instant::now())
+            };
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! JSON payloads of the Checkout API and a round-trip check, for crates that
+//! embed this library's types in their own APIs or storage
+//!
+//! Enable the `fixtures` feature in the `[dev-dependencies]` of your crate and
+//! check that your serialization layer keeps every field Checkout sends:
+//!
+//! ```ignore
+//! use checkout::fixtures::{self, assert_roundtrip};
+//!
+//! #[test]
+//! fn stored_payments_are_compatible() {
+//!     assert_roundtrip::<StoredPayment>(fixtures::PAYMENT_PROCESSED);
+//! }
+//! ```
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// A payment that was processed immediately, as returned by
+/// `POST /payments` and `GET /payments/{id}`
+pub const PAYMENT_PROCESSED: &str = include_str!("payment_processed.json");
+
+/// A payment that requires a 3D Secure redirect, as returned by
+/// `POST /payments`
+pub const PENDING_PAYMENT: &str = include_str!("pending_payment.json");
+
+/// A payment that was declined by the issuer, as returned by `POST /payments`
+/// and `GET /payments/{id}`
+pub const PAYMENT_DECLINED: &str = include_str!("payment_declined.json");
+
+/// A capture action, as returned by `GET /payments/{id}/actions`
+pub const PAYMENT_ACTION: &str = include_str!("payment_action.json");
+
+/// The actions of a captured payment, as returned by
+/// `GET /payments/{id}/actions`
+pub const PAYMENT_ACTIONS: &str = include_str!("payment_actions.json");
+
+/// A payout that is being processed, as returned by `POST /payments`
+pub const PAYOUT: &str = include_str!("payout.json");
+
+/// A `payment_captured` webhook notification of a multi-capture
+pub const CAPTURE_WEBHOOK: &str = include_str!("capture_webhook.json");
+
+/// A dispute, as listed by `GET /disputes`
+pub const DISPUTE: &str = include_str!("dispute.json");
+
+/// All fixtures by name
+pub const FIXTURES: &[(&str, &str)] = &[
+    ("payment_processed", PAYMENT_PROCESSED),
+    ("pending_payment", PENDING_PAYMENT),
+    ("payment_declined", PAYMENT_DECLINED),
+    ("payment_action", PAYMENT_ACTION),
+    ("payment_actions", PAYMENT_ACTIONS),
+    ("payout", PAYOUT),
+    ("capture_webhook", CAPTURE_WEBHOOK),
+    ("dispute", DISPUTE),
+];
+
+/// Deserializes `fixture` into `T`, serializes it again and checks that no
+/// field of the fixture was lost or changed, and that serializing is stable
+/// across a second round trip. Fields that `T` adds, e.g. `null` for absent
+/// optional fields, are allowed.
+///
+/// # Panics
+///
+/// Panics with the paths of the fields that differ, or if `fixture` can't be
+/// deserialized into `T`
+pub fn assert_roundtrip<T>(fixture: &str)
+where
+    T: Serialize + DeserializeOwned,
+{
+    let expected: Value = serde_json::from_str(fixture).expect("fixture is not valid JSON");
+    let value: T = serde_json::from_value(expected.clone())
+        .unwrap_or_else(|err| panic!("fixture can't be deserialized: {}", err));
+    let serialized = serde_json::to_value(&value).expect("value can't be serialized");
+
+    let mut differences = Vec::new();
+    diff(&expected, &serialized, "$", &mut differences);
+    assert!(
+        differences.is_empty(),
+        "fields were lost or changed in the round trip: {}",
+        differences.join(", ")
+    );
+
+    let again: T = serde_json::from_value(serialized.clone())
+        .unwrap_or_else(|err| panic!("serialized value can't be deserialized: {}", err));
+    assert_eq!(
+        serde_json::to_value(&again).expect("value can't be serialized"),
+        serialized,
+        "serialization is not stable across round trips"
+    );
+}
+
+/// Collects the paths of the values of `expected` that are missing from or
+/// different in `actual`
+fn diff(expected: &Value, actual: &Value, path: &str, differences: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let path = format!("{path}.{key}");
+                match actual.get(key) {
+                    Some(actual) => diff(value, actual, &path, differences),
+                    None if value.is_null() => {}
+                    None => differences.push(path),
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                diff(expected, actual, &format!("{path}[{index}]"), differences);
+            }
+        }
+        (expected, actual) if expected == actual => {}
+        _ => differences.push(path.to_string()),
+    }
+}
@@ -0,0 +1,53 @@
+//! Health checks of the credentials and the API, e.g. for readiness probes
+
+use std::time::Duration;
+
+use crate::Error;
+
+/// The result of one step of a health check
+#[derive(Debug)]
+pub struct HealthCheck {
+    /// How long the step took
+    pub latency: Duration,
+
+    /// Why the step failed, if it did
+    pub error: Option<Error>,
+}
+
+impl HealthCheck {
+    pub(crate) fn new(latency: Duration, error: Option<Error>) -> HealthCheck {
+        HealthCheck { latency, error }
+    }
+
+    /// Returns whether the step succeeded
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The result of [`Client::health_check`](crate::Client::health_check)
+#[derive(Debug)]
+pub struct HealthReport {
+    /// Whether an access token could be requested with the credentials
+    pub credentials: HealthCheck,
+
+    /// Whether the API could be reached with the access token. Skipped if
+    /// no access token could be requested.
+    pub api: Option<HealthCheck>,
+}
+
+impl HealthReport {
+    /// Returns whether payments can be expected to succeed: the credentials
+    /// were accepted and the API was reachable
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        self.credentials.is_healthy() && self.api.as_ref().is_some_and(HealthCheck::is_healthy)
+    }
+
+    /// Returns the total time the health check took
+    #[must_use]
+    pub fn latency(&self) -> Duration {
+        self.credentials.latency + self.api.as_ref().map_or(Duration::ZERO, |api| api.latency)
+    }
+}
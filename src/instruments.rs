@@ -0,0 +1,148 @@
+//! Payment instruments stored for later payments and payouts
+//!
+//! See: [Instruments](https://api-reference.checkout.com/#tag/Instruments)
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    links::Links, AccountHolder, Address, BankAccountType, Currency, ExtraFields, PhoneNumber,
+};
+
+pub use crate::types::CreateInstrumentBody;
+
+/// Body used in the request to update an instrument
+///
+/// Only the provided fields are updated.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct UpdateInstrumentBody {
+    /// The new expiry month of the card (1-2 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_month: Option<u32>,
+
+    /// The new expiry year of the card (4 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_year: Option<u32>,
+
+    /// The new name of the cardholder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The new details of the instrument's account holder
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_holder: Option<InstrumentAccountHolder>,
+
+    /// The customer the instrument belongs to and whether it is their default
+    /// instrument
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<InstrumentCustomer>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// The account holder of an instrument
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct InstrumentAccountHolder {
+    /// The account holder's billing address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_address: Option<Address>,
+
+    /// The account holder's phone number
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<PhoneNumber>,
+}
+
+/// The customer an instrument belongs to
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstrumentCustomer {
+    /// The customer's unique identifier (format: `cus_*`)
+    pub id: String,
+
+    /// Whether the instrument is the customer's default instrument
+    #[serde(default)]
+    pub default: bool,
+}
+
+/// Response to update an instrument
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpdateInstrumentResponse {
+    /// The instrument type
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// The fingerprint of the updated instrument, which changes when the card
+    /// details change
+    pub fingerprint: String,
+}
+
+/// Request body to store bank account details as an instrument
+///
+/// The returned instrument id can be used as the destination of bank payouts
+/// with [`BankPayoutDestination::Id`], so the account details only have to be
+/// sent once.
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename = "bank_account")]
+pub struct CreateBankAccountInstrumentBody {
+    /// The type of the account
+    pub account_type: Option<BankAccountType>,
+
+    /// The account number
+    pub account_number: Option<String>,
+
+    /// The code that identifies the bank (e.g. a sort code or routing number)
+    pub bank_code: Option<String>,
+
+    /// The code that identifies the bank's branch
+    pub branch_code: Option<String>,
+
+    /// The International Bank Account Number
+    pub iban: Option<String>,
+
+    /// The Basic Bank Account Number
+    pub bban: Option<String>,
+
+    /// The 8 or 11 character SWIFT/BIC code of the bank
+    pub swift_bic: Option<String>,
+
+    /// The two-letter ISO country code of the account (2 characters)
+    pub country: String,
+
+    /// The three-letter ISO currency code of the account
+    pub currency: Currency,
+
+    /// The holder of the account
+    pub account_holder: AccountHolder,
+
+    /// The customer to store the instrument for
+    pub customer: Option<InstrumentCustomer>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// Response to create an instrument
+#[derive(Deserialize, Debug, Clone)]
+pub struct CreateInstrumentResponse {
+    /// The instrument's unique identifier (format: `src_*`)
+    pub id: String,
+
+    /// The instrument type
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// A token that can uniquely identify the instrument's details across
+    /// all Checkout customers
+    pub fingerprint: Option<String>,
+
+    /// The customer the instrument was stored for
+    pub customer: Option<InstrumentCustomer>,
+
+    /// The last four characters of the account number or IBAN
+    pub last4: Option<String>,
+
+    /// The links related to the instrument
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
@@ -2,20 +2,93 @@
 //!
 //! Documentation: <https://docs.checkout.com>
 //! API Reference: <https://api-reference.checkout.com>
+//!
+//! Types are grouped by product in [`payments`], [`payouts`], [`customers`],
+//! [`instruments`], [`disputes`], [`platforms`] and [`webhooks`], and the
+//! most common ones are in the [`prelude`]. Every type is also available at
+//! the crate root.
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs, clippy::all, clippy::pedantic)]
 
-use std::{convert::TryFrom, fmt, str::FromStr};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    future::Future,
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
-use reqwest::{Client as ReqwestClient, Error as ReqwestError, Response, StatusCode};
+use reqwest::{
+    header::{HeaderValue, AUTHORIZATION},
+    Client as ReqwestClient, Error as ReqwestError, Method, RequestBuilder, Response, StatusCode,
+};
 use secrecy::{ExposeSecret, SecretString};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use ulid::Ulid;
 
+pub(crate) mod audit;
+pub(crate) mod authorization;
+pub(crate) mod batch;
+pub(crate) mod cancel;
+pub(crate) mod compensation;
+pub(crate) mod credentials;
+pub mod customers;
+pub(crate) mod deprecation;
+pub mod disputes;
+pub(crate) mod download;
+pub mod drift;
+pub(crate) mod events;
+pub(crate) mod failover;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub(crate) mod health;
+pub mod instruments;
+pub(crate) mod options;
+pub(crate) mod pages;
+pub mod payments;
+pub mod payouts;
+pub mod platforms;
+pub mod prelude;
+pub(crate) mod recorder;
+pub(crate) mod registry;
+pub(crate) mod response;
+pub(crate) mod routing;
+pub mod sandbox;
 pub(crate) mod types;
+pub(crate) mod uploads;
+pub mod webhooks;
 
+pub use audit::*;
+pub use authorization::*;
+pub use batch::*;
+pub use compensation::*;
+pub use credentials::*;
+pub use deprecation::*;
+pub use download::*;
+pub use events::*;
+pub use failover::*;
+pub use health::*;
+pub use options::*;
+pub use pages::*;
+pub use recorder::*;
+pub use registry::*;
+pub use response::*;
+pub use routing::*;
 pub use types::*;
+pub use uploads::*;
+pub use webhooks::*;
+
+/// How long before their expiry cached access tokens are renewed
+// `Duration::from_mins` is too recent for the toolchains the crate supports
+#[allow(clippy::duration_suboptimal_units)]
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// The time to wait before the first retry of a request
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
 
 /// An error that was reported by the Checkout API
 #[derive(Deserialize, Debug)]
@@ -28,41 +101,340 @@ pub struct ApiError {
 
     /// A list of errors
     pub error_codes: Vec<String>,
+
+    #[serde(skip)]
+    endpoint: Option<Endpoint>,
+}
+
+impl ApiError {
+    /// Creates an error as reported by the API, e.g. to test error handling
+    #[must_use]
+    pub fn new(request_id: String, error_type: String, error_codes: Vec<String>) -> ApiError {
+        ApiError {
+            request_id,
+            error_type,
+            error_codes,
+            endpoint: None,
+        }
+    }
+
+    /// Returns the endpoint that reported the error, if known
+    #[must_use]
+    pub fn endpoint(&self) -> Option<&Endpoint> {
+        self.endpoint.as_ref()
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error_type)?;
+        if !self.error_codes.is_empty() {
+            write!(f, " ({})", self.error_codes.join(", "))?;
+        }
+        if let Some(endpoint) = &self.endpoint {
+            write!(f, " from {endpoint}")?;
+        }
+        write!(f, " [request {}]", self.request_id)
+    }
+}
+
+/// The HTTP method and path of a request, to give errors context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    /// The HTTP method of the request
+    pub method: Method,
+
+    /// The path of the request, without the host and query
+    pub path: String,
+}
+
+impl Endpoint {
+    fn new(method: Method, url: &reqwest::Url) -> Endpoint {
+        Endpoint {
+            method,
+            path: url.path().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method, self.path)
+    }
 }
 
 /// Encapsulates any error that can occur when sending a request to the
 /// Checkout API
 #[derive(thiserror::Error, Debug)]
-#[error("{0:?}")]
 pub enum Error {
     /// An error that was reported by the Checkout API
+    #[error("API error: {0}")]
     Api(ApiError),
 
     /// Not authorized
-    #[error("Unauthorized")]
+    #[error("unauthorized")]
     Unauthorized,
 
     /// Invalid data was sent
+    #[error("invalid data: {0}")]
     InvalidData(ApiError),
 
     /// To many requests or duplicate request detected
-    #[error("TooManyRequests")]
+    #[error("too many requests")]
     TooManyRequests,
 
-    /// To many requests or duplicate request detected
-    #[error("Unknown({0:?}, {1:?})")]
+    /// A response with an unexpected status, and its body
+    #[error("unexpected response ({0}): {1:?}")]
     Unknown(StatusCode, String),
 
     /// An error that ocurred during transport
+    #[error("transport error")]
     Transport(#[from] ReqwestError),
+
+    /// A file could not be uploaded because it is invalid, it was not sent
+    #[error("invalid upload: {0}")]
+    InvalidUpload(#[from] UploadError),
+
+    /// A downloaded file could not be written
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// The body of a successful response could not be decoded
+    #[error("invalid response body from {endpoint}")]
+    Decode {
+        /// The endpoint that returned the body
+        endpoint: Endpoint,
+
+        /// Why the body could not be decoded
+        source: serde_json::Error,
+    },
+
+    /// The instrument is in use, e.g. by an active payment plan, and can't be
+    /// changed
+    #[error("instrument in use: {0}")]
+    InstrumentInUse(ApiError),
+
+    /// The query parameters of a request could not be encoded
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+
+    /// The request succeeded but the payment was declined
+    #[error(transparent)]
+    Declined(DeclineError),
+
+    /// The request was cancelled before a response was received. It may
+    /// still have been processed, contains the idempotency key to retry it
+    /// with, if one was sent
+    #[error("request cancelled (idempotency key: {0:?})")]
+    Cancelled(Option<String>),
+
+    /// A payout batch is invalid and none of its payouts were submitted
+    #[error("invalid payout batch: {0:?}")]
+    InvalidBatch(Vec<BatchValidationError>),
+
+    /// An Account Funding Transaction is missing data card schemes require,
+    /// it was not sent
+    #[error("invalid AFT: {0:?}")]
+    InvalidAft(Vec<AftValidationError>),
+
+    /// A payment's dLocal processing information is incomplete, it was not
+    /// sent
+    #[error("invalid dLocal payment: {0:?}")]
+    InvalidDLocal(Vec<DLocalValidationError>),
+
+    /// No client is registered under the key in a [`ClientRegistry`]
+    #[error("no client registered for {0:?}")]
+    UnknownClient(String),
+
+    /// No processing channel of a [`ChannelRouter`] accepts the payment, it
+    /// was not sent
+    #[error("no processing channel accepts the payment")]
+    NoEligibleChannel,
+
+    /// A sandbox helper was used with a client that is not connected to the
+    /// sandbox, nothing was sent
+    #[error("only available in the sandbox")]
+    SandboxOnly,
+
+    /// A payment that was requested as an authorization was processed but
+    /// not authorized, e.g. because it requires a 3D Secure redirect
+    #[error("payment {} was not authorized", .0.payment_id())]
+    NotAuthorized(Box<CreatePaymentResponse>),
+
+    /// A capture of an [`AuthorizationHandle`] can't succeed, it was not sent
+    #[error("invalid capture: {0:?}")]
+    InvalidCapture(CaptureFailure),
+
+    /// A request that was sent with a generated idempotency key failed
+    /// without a definite answer, because its retries were exhausted on
+    /// 5xx responses or transport errors. It may still have been processed,
+    /// so send it again with the key to find out without processing it twice.
+    #[error("{source} (idempotency key: {idempotency_key})")]
+    Unconfirmed {
+        /// The idempotency key that was sent with every attempt
+        idempotency_key: String,
+
+        /// The error of the last attempt
+        source: Box<Error>,
+    },
+
+    /// A refund of [`Client::refund_remaining`] failed after earlier refunds
+    /// of the remaining amount were made
+    #[error("{source} after {} refunds", refunds.len())]
+    PartiallyRefunded {
+        /// The refunds that were made before the failure
+        refunds: Vec<RefundPaymentResponse>,
+
+        /// The error of the refund that failed
+        source: Box<Error>,
+    },
+
+    /// The amount of a capture or refund is in another currency than the
+    /// payment, or exceeds the amount that can still be captured or
+    /// refunded, it was not sent
+    #[error("amount {requested:?} does not match the payment's {payment:?}")]
+    AmountMismatch {
+        /// The amount of the payment that can still be captured or refunded,
+        /// in the payment's currency
+        payment: Money,
+
+        /// The amount and currency of the capture or refund
+        requested: Money,
+    },
+}
+
+impl Error {
+    /// Returns the idempotency key to send the failed request again with,
+    /// if it may have been processed
+    #[must_use]
+    pub fn idempotency_key(&self) -> Option<&str> {
+        match self {
+            Error::Unconfirmed {
+                idempotency_key, ..
+            } => Some(idempotency_key),
+            Error::Cancelled(idempotency_key) => idempotency_key.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// A payment that was processed but not approved
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("payment {payment_id} declined ({response_code}): {response_summary:?}")]
+pub struct DeclineError {
+    /// The payment's unique identifier (format: `pay_*`)
+    pub payment_id: String,
+
+    /// The Gateway response code
+    pub response_code: String,
+
+    /// The Gateway response summary
+    pub response_summary: Option<String>,
+
+    /// The kind of decline, derived from the response code
+    pub kind: DeclineKind,
+
+    merchant_advice: Option<MerchantAdviceCode>,
+}
+
+impl DeclineError {
+    /// Returns the issuer's advice on retrying the payment, if any
+    #[must_use]
+    pub fn merchant_advice(&self) -> Option<&MerchantAdviceCode> {
+        self.merchant_advice.as_ref()
+    }
+
+    /// Returns whether retrying the payment may succeed
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        self.kind == DeclineKind::Soft
+    }
+
+    /// Returns whether and when to retry the payment, e.g. to schedule the
+    /// retries of a declined recurring payment
+    #[must_use]
+    pub fn retry_advice(&self) -> RetryAdvice {
+        RetryAdvice::from_decline(
+            &self.response_code,
+            self.response_summary.as_deref(),
+            self.merchant_advice.as_ref(),
+        )
+        .unwrap_or(RetryAdvice::DoNotRetry)
+    }
+}
+
+/// The kind of a declined payment
+///
+/// See: [Response codes](https://docs.checkout.com/resources/codes/api-response-codes)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeclineKind {
+    /// `20xxx` - The issuer declined the payment, but it may succeed if
+    /// retried later
+    Soft,
+
+    /// `30xxx` - The issuer declined the payment and it should not be retried
+    Hard,
+
+    /// `40xxx` - The payment was blocked by a risk rule
+    Risk,
+
+    /// Any other response code
+    Other,
+}
+
+impl DeclineKind {
+    /// Classifies a Gateway response code, see [`ResponseCode`]
+    #[must_use]
+    pub fn from_response_code(response_code: &str) -> DeclineKind {
+        DeclineKind::from(&ResponseCode::from(response_code))
+    }
+}
+
+impl From<&ResponseCode> for DeclineKind {
+    fn from(response_code: &ResponseCode) -> DeclineKind {
+        match response_code {
+            ResponseCode::SoftDecline(_) => DeclineKind::Soft,
+            ResponseCode::HardDecline(_) => DeclineKind::Hard,
+            ResponseCode::RiskDecline(_) => DeclineKind::Risk,
+            ResponseCode::Approved(_) | ResponseCode::Other(_) => DeclineKind::Other,
+        }
+    }
 }
 
 /// Could not parse an environment, contains the original string.
 #[derive(Debug)]
 pub struct ParseEnvironmentError(pub String);
 
+/// Credentials look like they belong to a different environment than the one
+/// they are used with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialMismatchError {
+    /// The environment the client was created for
+    pub environment: Environment,
+
+    /// The environment the credentials appear to belong to
+    pub credentials: Environment,
+}
+
+impl Environment {
+    /// Guesses the environment of a key or client id from its format, e.g.
+    /// `sk_sbox_*` is a sandbox key while `sk_*` is a production key. Returns
+    /// `None` if the format does not reveal the environment.
+    #[must_use]
+    pub fn of_credential(credential: &str) -> Option<Environment> {
+        let credential = credential.trim();
+        if credential.contains("_sbox_") || credential.contains("_test_") {
+            Some(Environment::Sandbox)
+        } else if credential.starts_with("sk_") || credential.starts_with("pk_") {
+            Some(Environment::Production)
+        } else {
+            None
+        }
+    }
+}
+
 /// API environments to differentiate between testing environments and live.
-#[derive(PartialEq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 #[allow(missing_docs)]
 pub enum Environment {
     Production,
@@ -134,9 +506,22 @@ impl Environment {
 #[derive(Clone, Debug)]
 pub struct Client {
     http_client: ReqwestClient,
+    custom_http_client: bool,
+    access_http_client: Option<ReqwestClient>,
     environment: Environment,
     username: SecretString,
     password: SecretString,
+    credentials: HashMap<ProductArea, Credentials>,
+    environment_credentials: HashMap<Environment, Credentials>,
+    audit_hook: Option<AuditHook>,
+    recorder: Option<Recorder>,
+    event_hook: Option<EventHook>,
+    deprecation_hook: Option<DeprecationHook>,
+    api_hosts: Option<ApiHosts>,
+    request_options: RequestOptions,
+    api_version: Option<String>,
+    observed_api_version: Arc<RwLock<Option<String>>>,
+    tokens: Arc<RwLock<HashMap<ProductArea, OAuthTokenResponse>>>,
 }
 
 impl Client {
@@ -145,10 +530,125 @@ impl Client {
     pub fn new(username: SecretString, password: SecretString, environment: Environment) -> Client {
         Client {
             http_client: ReqwestClient::new(),
+            custom_http_client: false,
+            access_http_client: None,
             environment,
             username,
             password,
+            credentials: HashMap::new(),
+            environment_credentials: HashMap::new(),
+            audit_hook: None,
+            recorder: None,
+            event_hook: None,
+            deprecation_hook: None,
+            api_hosts: None,
+            request_options: RequestOptions::default(),
+            api_version: None,
+            observed_api_version: Arc::default(),
+            tokens: Arc::default(),
+        }
+    }
+
+    /// Sends requests with `http_client`, e.g. to share its connection pool
+    /// with other clients or to configure proxies and TLS
+    ///
+    /// Token requests to the access host use the same client, unless one was
+    /// set with [`Client::with_access_http_client`].
+    #[must_use]
+    pub fn with_http_client(mut self, http_client: ReqwestClient) -> Client {
+        self.http_client = http_client;
+        self.custom_http_client = true;
+        self
+    }
+
+    /// Sends requests with the shared `http_client` of a
+    /// [`ClientRegistry`], unless the client has an HTTP client of its own
+    pub(crate) fn share_http_client(mut self, http_client: &ReqwestClient) -> Client {
+        if !self.custom_http_client {
+            self.http_client = http_client.clone();
+        }
+        self
+    }
+
+    /// Sends requests with an HTTP client built from `options`, e.g. to keep
+    /// more connections alive for traffic spikes
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built
+    pub fn with_connection_options(mut self, options: &ConnectionOptions) -> Result<Client, Error> {
+        self.http_client = options.build_http_client()?;
+        self.custom_http_client = true;
+        Ok(self)
+    }
+
+    /// Sends the token requests to the access host (`/connect/token`) with
+    /// `http_client`, e.g. when auth traffic leaves through a different
+    /// egress proxy or needs a different timeout than API traffic
+    #[must_use]
+    pub fn with_access_http_client(mut self, http_client: ReqwestClient) -> Client {
+        self.access_http_client = Some(http_client);
+        self
+    }
+
+    /// Registers a hook that receives a sanitized [`RequestSummary`] of every
+    /// outbound request, e.g. to write PCI/AML audit trails
+    #[must_use]
+    pub fn with_audit_hook(mut self, hook: AuditHook) -> Client {
+        self.audit_hook = Some(hook);
+        self
+    }
+
+    /// Records the redacted requests of the client and their responses with
+    /// `recorder`, e.g. to hand them to Checkout support when an error occurs
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Recorder) -> Client {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Registers a hook that receives a [`PaymentEvent`] whenever a call of
+    /// the client creates, captures, refunds or voids a payment
+    #[must_use]
+    pub fn with_event_hook(mut self, hook: EventHook) -> Client {
+        self.event_hook = Some(hook);
+        self
+    }
+
+    /// Registers a hook that receives a [`DeprecationNotice`] whenever a
+    /// response announces that its endpoint is deprecated or will be retired
+    #[must_use]
+    pub fn with_deprecation_hook(mut self, hook: DeprecationHook) -> Client {
+        self.deprecation_hook = Some(hook);
+        self
+    }
+
+    /// Creates a new client after checking that the credentials don't look
+    /// like they belong to the other environment, e.g. a sandbox key used with
+    /// [`Environment::Production`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either credential appears to belong to a different
+    /// environment
+    pub fn new_checked(
+        username: SecretString,
+        password: SecretString,
+        environment: Environment,
+    ) -> Result<Client, CredentialMismatchError> {
+        for credential in &[&username, &password] {
+            match Environment::of_credential(credential.expose_secret()) {
+                Some(credentials) if credentials != environment => {
+                    return Err(CredentialMismatchError {
+                        environment,
+                        credentials,
+                    })
+                }
+                _ => {}
+            }
         }
+
+        Ok(Client::new(username, password, environment))
     }
 
     /// Creates a new `Client` from the following environment variables:
@@ -169,294 +669,4501 @@ impl Client {
         ))
     }
 
-    async fn authorize(&self) -> Result<String, Error> {
+    /// Uses a separate credential set for the endpoints of a product area,
+    /// e.g. platforms credentials for `/accounts`. The client's credentials
+    /// are used for product areas without their own credential set.
+    #[must_use]
+    pub fn with_credentials(mut self, area: ProductArea, credentials: Credentials) -> Client {
+        self.credentials.insert(area, credentials);
+        self.tokens = Arc::default();
+        self
+    }
+
+    /// Uses `credentials` for the requests sent to `environment` by a client
+    /// returned from [`Client::with_environment`]
+    #[must_use]
+    pub fn with_environment_credentials(
+        mut self,
+        environment: Environment,
+        credentials: Credentials,
+    ) -> Client {
+        self.environment_credentials
+            .insert(environment, credentials);
+        self
+    }
+
+    /// Returns a copy of the client that sends its requests to `environment`,
+    /// e.g. to look up the same payment in sandbox and production
+    ///
+    /// The copy shares the client's connection pool and options. It uses the
+    /// credentials set with [`Client::with_environment_credentials`] for
+    /// `environment`, if any, in place of the client's credentials, including
+    /// the ones of product areas. The API hosts set with
+    /// [`Client::with_api_hosts`] only apply to the client's own environment.
+    #[must_use]
+    pub fn with_environment(&self, environment: Environment) -> Client {
+        let mut client = self.clone();
+        if environment == self.environment {
+            return client;
+        }
+
+        client.environment = environment;
+        client.api_hosts = None;
+        client.tokens = Arc::default();
+        if let Some(credentials) = self.environment_credentials.get(&environment) {
+            client.username = credentials.username.clone();
+            client.password = credentials.password.clone();
+            client.credentials.clear();
+        }
+        client
+    }
+
+    /// Returns a token for the product area of the url, using the area's
+    /// credentials if any. Tokens are cached until shortly before they
+    /// expire.
+    async fn authorize(&self, url: &str) -> Result<SecretString, Error> {
+        let token = self.cached_token(ProductArea::of_url(url)).await?;
+        Ok(token.access_token)
+    }
+
+    async fn cached_token(&self, area: ProductArea) -> Result<OAuthTokenResponse, Error> {
+        if let Ok(tokens) = self.tokens.read() {
+            if let Some(token) = tokens.get(&area) {
+                if !token.expires_within(TOKEN_EXPIRY_MARGIN) {
+                    return Ok(token.clone());
+                }
+            }
+        }
+
+        let token = self.request_token(area).await?;
+        if let Ok(mut tokens) = self.tokens.write() {
+            tokens.insert(area, token.clone());
+        }
+        Ok(token)
+    }
+
+    /// Fetches and caches the tokens for `scopes`, e.g. at startup so that
+    /// the first payment after a deploy doesn't wait for a token
+    ///
+    /// Tokens are requested per product area, see [`ProductArea::of_scope`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unauthorized`] if the credentials of a product area
+    /// were rejected
+    pub async fn warm_up(&self, scopes: &[Scope]) -> Result<(), Error> {
+        let mut areas: Vec<ProductArea> = Vec::new();
+        for area in scopes.iter().map(ProductArea::of_scope) {
+            if !areas.contains(&area) {
+                areas.push(area);
+            }
+        }
+
+        for area in areas {
+            self.cached_token(area).await?;
+        }
+        Ok(())
+    }
+
+    /// Request an access token
+    ///
+    /// Requests a token for a product area with the area's credentials, e.g.
+    /// to check with [`OAuthTokenResponse::has_scope`] that the credentials
+    /// can access an endpoint before calling it. A new token is requested on
+    /// every call, unlike the cached tokens the client's requests use.
+    ///
+    /// [`POST /connect/token`](https://api-reference.checkout.com/#section/Authentication)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unauthorized`] if the credentials were rejected
+    pub async fn request_token(&self, area: ProductArea) -> Result<OAuthTokenResponse, Error> {
+        let (username, password) = match self.credentials.get(&area) {
+            Some(credentials) => (&credentials.username, &credentials.password),
+            None => (&self.username, &self.password),
+        };
+
         let url = format!("{}/connect/token", self.environment.access_url());
         let body = OAuthTokenRequest {
             grant_type: "client_credentials".to_string(),
-            scope: "gateway".to_string(),
+            scope: area.scope().to_string(),
         };
 
         let response = self
-            .http_client
+            .access_http_client
+            .as_ref()
+            .unwrap_or(&self.http_client)
             .post(&url)
-            .basic_auth(
-                self.username.expose_secret(),
-                Some(self.password.expose_secret()),
-            )
+            .basic_auth(username.expose_secret(), Some(password.expose_secret()))
             .form(&body)
             .send()
             .await?;
 
         let status = response.status();
         match status {
-            StatusCode::OK => {
-                let body: OAuthTokenResponse = response.json().await?;
-                Ok(body.access_token)
-            }
+            StatusCode::OK => decode_response(Method::POST, response).await,
             _ => Err(Error::Unauthorized),
         }
     }
 
-    async fn send_get_request<R>(&self, url: &str) -> Result<R, Error>
-    where
-        R: DeserializeOwned,
-    {
-        let token = self.authorize().await?;
-
-        let response = self.http_client.get(url).bearer_auth(token).send().await?;
-
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(Error::Api(response.json().await?))
-        }
-    }
+    /// Checks that payments can be made, e.g. for a readiness probe
+    ///
+    /// Requests an access token for payments to validate the credentials,
+    /// then requests an unknown payment with it to validate that the API is
+    /// reachable and accepts the token. Each step is timed.
+    pub async fn health_check(&self) -> HealthReport {
+        let started = Instant::now();
+        let token = self.request_token(ProductArea::Gateway).await;
+        let credentials_latency = started.elapsed();
 
-    async fn send_post_request<B, R>(&self, url: &str, body: &B) -> Result<R, Error>
-    where
-        B: Serialize,
-        R: DeserializeOwned,
-    {
-        let token = self.authorize().await?;
+        let token = match token {
+            Ok(token) => token.access_token,
+            Err(err) => {
+                return HealthReport {
+                    credentials: HealthCheck::new(credentials_latency, Some(err)),
+                    api: None,
+                }
+            }
+        };
 
+        let url = format!("{}/payments/pay_healthcheck", self.api_url());
+        let started = Instant::now();
         let response = self
             .http_client
-            .post(url)
-            .bearer_auth(token)
-            .json(body)
+            .get(&url)
+            .bearer_auth(token.expose_secret())
             .send()
-            .await?;
+            .await;
+        let api_latency = started.elapsed();
 
-        if response.status().is_success() {
-            Ok(response.json().await?)
-        } else {
-            Err(Error::Api(response.json().await?))
+        // The payment doesn't exist, so any response but a rejected token or
+        // a server error shows that payments can be made
+        let error = match response {
+            Ok(response) => match response.status() {
+                StatusCode::UNAUTHORIZED => Some(Error::Unauthorized),
+                status if status.is_server_error() => {
+                    Some(error_from_response(Method::GET, response, Error::Api).await)
+                }
+                _ => None,
+            },
+            Err(err) => {
+                self.fail_over(&url, &err);
+                Some(Error::Transport(err))
+            }
+        };
+
+        HealthReport {
+            credentials: HealthCheck::new(credentials_latency, None),
+            api: Some(HealthCheck::new(api_latency, error)),
         }
     }
 
-    async fn send_post_request_2<B>(&self, url: &str, body: &B) -> Result<Response, Error>
-    where
-        B: Serialize,
-    {
-        let token = self.authorize().await?;
+    /// Applies additional headers, an idempotency key or a timeout to every
+    /// request of the client. To send options with a single request, use
+    /// the `*_with_options` variant of the call instead, e.g.
+    /// [`Client::create_payment_with_options`].
+    #[must_use]
+    pub fn with_request_options(mut self, options: RequestOptions) -> Client {
+        self.request_options = options;
+        self
+    }
 
-        self.http_client
-            .post(url)
-            .bearer_auth(token)
-            .json(body)
-            .send()
-            .await
-            .map_err(Error::from)
+    /// Returns the options sent with every request of the client, e.g. to
+    /// extend them for a single request
+    #[must_use]
+    pub fn request_options(&self) -> &RequestOptions {
+        &self.request_options
     }
 
-    /// Request a payment or payout
+    /// Pins the version of the API by sending it in the `Cko-Version` header
+    /// of every request
+    #[must_use]
+    pub fn with_api_version(mut self, version: String) -> Client {
+        self.api_version = Some(version);
+        self
+    }
+
+    /// Returns the API version reported in the `Cko-Version` header of the
+    /// most recent response, if any, e.g. to detect version drift
+    #[must_use]
+    pub fn observed_api_version(&self) -> Option<String> {
+        self.observed_api_version
+            .read()
+            .ok()
+            .and_then(|version| version.clone())
+    }
+
+    /// Sends requests to a prioritized list of API hosts instead of the
+    /// environment's API url. A request to a host that cannot be reached is
+    /// sent to the next host, see [`ApiHosts`].
+    #[must_use]
+    pub fn with_api_hosts(mut self, hosts: ApiHosts) -> Client {
+        self.api_hosts = Some(hosts);
+        self
+    }
+
+    /// Checks the configured API hosts in priority order and activates the
+    /// first one that is healthy, falling back to the primary host whenever
+    /// it is available again. Returns the base URL of the active host.
     ///
-    /// To accept payments from cards, digital wallets and many alternative
-    /// payment methods, specify the source.type field, along with the
-    /// source-specific data.
+    /// Without configured hosts this checks the environment's API url.
     ///
-    /// To pay out to a card, specify the destination of your payout using the
-    /// destination.type field, along with the destination-specific data.
+    /// # Errors
     ///
-    /// To verify the success of the payment, check the approved field in the
-    /// response.
+    /// Returns the error of the last host checked if no host is healthy
     ///
-    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
-    pub async fn create_payment(
-        &self,
-        request: &CreatePaymentRequest,
-    ) -> Result<CreatePaymentResponse, Error> {
-        let url = format!("{}/payments", self.environment.api_url());
-        let response = self.send_post_request_2(&url, request).await?;
+    /// # Panics
+    ///
+    /// Panics if no host is checked, which [`ApiHosts::new`] rules out
+    pub async fn check_hosts(&self) -> Result<String, Error> {
+        let hosts = match &self.api_hosts {
+            Some(api_hosts) => api_hosts.hosts().to_vec(),
+            None => vec![self.environment.api_url().to_string()],
+        };
 
-        let status = response.status();
-        match status {
-            StatusCode::CREATED => {
-                let body = response.json().await?;
-                Ok(CreatePaymentResponse::Processed(body))
-            }
-            StatusCode::ACCEPTED => {
-                let body = response.json().await?;
-                Ok(CreatePaymentResponse::Pending(body))
-            }
-            StatusCode::UNAUTHORIZED => Err(Error::Unauthorized),
-            StatusCode::UNPROCESSABLE_ENTITY => {
-                let body = response.json().await?;
-                Err(Error::InvalidData(body))
+        let mut last_error = None;
+        for (index, host) in hosts.into_iter().enumerate() {
+            match self.http_client.get(&host).send().await {
+                Ok(response) if !response.status().is_server_error() => {
+                    if let Some(api_hosts) = &self.api_hosts {
+                        api_hosts.activate(index);
+                    }
+                    return Ok(host);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    last_error = Some(match response.text().await {
+                        Ok(body) => Error::Unknown(status, body),
+                        Err(err) => Error::Transport(err),
+                    });
+                }
+                Err(err) => last_error = Some(Error::Transport(err)),
             }
-            StatusCode::TOO_MANY_REQUESTS => Err(Error::TooManyRequests),
-            code => {
-                let body = response.text().await?;
-                Err(Error::Unknown(code, body))
+        }
+
+        Err(last_error.expect("at least one host is checked"))
+    }
+
+    fn api_url(&self) -> String {
+        match &self.api_hosts {
+            Some(api_hosts) => api_hosts.active().to_string(),
+            None => self.environment.api_url().to_string(),
+        }
+    }
+
+    fn fail_over(&self, url: &str, err: &ReqwestError) {
+        if let Some(api_hosts) = &self.api_hosts {
+            if err.is_connect() || err.is_timeout() {
+                if let Some(host) = api_hosts.host_of(url) {
+                    api_hosts.fail_over(host);
+                }
             }
         }
     }
 
-    /// Get payment details
-    ///
-    /// Returns the details of the payment with the specified identifier
-    /// string.
-    ///
-    /// If the payment method requires a redirection to a third party (e.g., 3D
-    /// Secure), the redirect URL back to your site will include a
-    /// `cko-session-id` query parameter containing a payment session ID that
-    /// can be used to obtain the details of the payment
-    ///
-    /// [`GET /payments/{id}`](https://api-reference.checkout.com/#operation/getPaymentDetails)
-    pub async fn get_payment_details(
+    /// Sends a request to `url` to the active host instead, after its host
+    /// failed over. Returns the request and its new URL, or `None` if the
+    /// host didn't change.
+    fn redirect_to_active_host(
         &self,
-        payment_id: String,
-    ) -> Result<GetPaymentDetailsResponse, Error> {
-        let url = format!("{}/payments/{}", self.environment.api_url(), payment_id);
-        self.send_get_request(&url).await
+        request: RequestBuilder,
+        url: &str,
+    ) -> Option<(RequestBuilder, String)> {
+        let api_hosts = self.api_hosts.as_ref()?;
+        let failed = api_hosts.host_of(url)?;
+        let active = api_hosts.active();
+        if active == failed {
+            return None;
+        }
+
+        let redirected = format!("{}{}", active, &url[failed.len()..]);
+        let (client, request) = request.build_split();
+        let mut request = request.ok()?;
+        *request.url_mut() = redirected.parse().ok()?;
+        Some((RequestBuilder::from_parts(client, request), redirected))
+    }
+
+    fn audit(
+        &self,
+        mut summary: RequestSummary,
+        response: Option<&Response>,
+        request_body: Option<serde_json::Value>,
+        response_body: Option<serde_json::Value>,
+    ) {
+        if let Some(response) = response {
+            summary.record_response(response);
+        }
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&summary, request_body, response_body);
+        }
+        if let Some(hook) = &self.audit_hook {
+            hook.call(&summary);
+        }
+    }
+
+    /// Reads the body of a response for the recorder, if the client has one
+    /// and the body is small enough JSON
+    async fn buffer_for_recorder(
+        &self,
+        response: Result<Response, ReqwestError>,
+    ) -> (Result<Response, ReqwestError>, Option<serde_json::Value>) {
+        match response {
+            Ok(response) if self.recorder.is_some() && recorder::is_recordable(&response) => {
+                match recorder::buffer_response(response).await {
+                    Ok((response, body)) => (Ok(response), body),
+                    Err(err) => (Err(err), None),
+                }
+            }
+            response => (response, None),
+        }
+    }
+
+    fn emit(&self, event: impl FnOnce() -> PaymentEvent) {
+        if let Some(hook) = &self.event_hook {
+            hook.call(&event());
+        }
+    }
+
+    async fn send(
+        &self,
+        request: RequestBuilder,
+        summary: RequestSummary,
+    ) -> Result<Response, Error> {
+        Ok(self
+            .send_measured(request, summary, None, &self.request_options)
+            .await?
+            .body)
+    }
+
+    async fn send_measured(
+        &self,
+        request: RequestBuilder,
+        mut summary: RequestSummary,
+        request_body: Option<serde_json::Value>,
+        options: &RequestOptions,
+    ) -> Result<ApiResponse<Response>, Error> {
+        let retries = options.retries;
+        let (mut request, generated_key) = self.prepare(request, &mut summary, options);
+        let mut url = summary.endpoint.clone();
+        let mut reauthorized = false;
+        let mut redirects = self
+            .api_hosts
+            .as_ref()
+            .map_or(0, |api_hosts| api_hosts.hosts().len() - 1);
+        let started = Instant::now();
+        summary.attempts = 1;
+
+        loop {
+            let retry = if summary.attempts <= retries {
+                request.try_clone()
+            } else {
+                None
+            };
+            let redirect = if redirects > 0 {
+                request.try_clone()
+            } else {
+                None
+            };
+            // The token may expire or be revoked between fetching and using
+            // it, so a rejected request is sent once more with a new token
+            let reauthorize = if reauthorized {
+                None
+            } else {
+                request.try_clone()
+            };
+
+            let sent = Instant::now();
+            let response = request.send().await;
+            let latency = sent.elapsed();
+            let (response, response_body) = self.buffer_for_recorder(response).await;
+            summary.latency = response.as_ref().ok().map(|_| latency);
+
+            if let Ok(response) = &response {
+                self.observe_headers(&summary.method, response);
+            }
+            if let Err(err) = &response {
+                self.fail_over(&url, err);
+            }
+
+            // A host that could not be connected to never received the
+            // request, so it is sent to the next host right away
+            let unreachable = matches!(&response, Err(err) if err.is_connect());
+            if let Some(next) = redirect.filter(|_| unreachable) {
+                if let Some((next, next_url)) = self.redirect_to_active_host(next, &url) {
+                    self.audit(summary.clone(), None, request_body.clone(), None);
+                    redirects -= 1;
+                    summary.redirects += 1;
+                    url = next_url;
+                    summary.endpoint = url.clone();
+                    request = next;
+                    continue;
+                }
+            }
+
+            let unauthorized = matches!(
+                &response,
+                Ok(response) if response.status() == StatusCode::UNAUTHORIZED
+            );
+            if let Some(next) = reauthorize.filter(|_| unauthorized) {
+                self.audit(
+                    summary.clone(),
+                    response.as_ref().ok(),
+                    request_body.clone(),
+                    response_body,
+                );
+                request = self.reauthorize(next, &url).await?;
+                reauthorized = true;
+                continue;
+            }
+
+            let retryable = match &response {
+                Ok(response) => is_retryable_status(response.status()),
+                Err(err) => err.is_connect() || err.is_timeout(),
+            };
+            if let Some(next) = retry.filter(|_| retryable) {
+                self.audit(
+                    summary.clone(),
+                    response.as_ref().ok(),
+                    request_body.clone(),
+                    response_body,
+                );
+                tokio::time::sleep(retry_backoff(summary.attempts)).await;
+                summary.attempts += 1;
+                request = next;
+                continue;
+            }
+
+            let total_latency = started.elapsed();
+            let attempts = summary.attempts;
+            let redirects = summary.redirects;
+            let method = summary.method.clone();
+            let idempotency_key = summary.idempotency_key.clone();
+            self.audit(summary, response.as_ref().ok(), request_body, response_body);
+
+            let response = confirm_outcome(method, response, generated_key).await?;
+            return Ok(ApiResponse {
+                request_id: audit::header_value(&response, CKO_REQUEST_ID_HEADER),
+                status: response.status(),
+                latency,
+                total_latency,
+                attempts,
+                redirects,
+                idempotency_key,
+                body: response,
+            });
+        }
+    }
+
+    /// Applies the options and API version to a request, generating an
+    /// idempotency key if it may be retried. Returns the generated key.
+    fn prepare(
+        &self,
+        request: RequestBuilder,
+        summary: &mut RequestSummary,
+        options: &RequestOptions,
+    ) -> (RequestBuilder, Option<String>) {
+        let mut request = options.apply(request, summary);
+        if let Some(version) = &self.api_version {
+            request = request.header(CKO_VERSION_HEADER, version.as_str());
+        }
+        if options.retries == 0
+            || summary.idempotency_key.is_some()
+            || summary.method != Method::POST
+        {
+            return (request, None);
+        }
+        // Generated once, so that every attempt is the same logical request
+        // and a retry can't create a second payment
+        let key = Ulid::new().to_string();
+        let request = request.header(CKO_IDEMPOTENCY_KEY_HEADER, key.as_str());
+        summary.idempotency_key = Some(key.clone());
+        (request, Some(key))
+    }
+
+    /// Records the API version of a response and reports its deprecation
+    /// headers to the deprecation hook
+    fn observe_headers(&self, method: &Method, response: &Response) {
+        if let Some(version) = audit::header_value(response, CKO_VERSION_HEADER) {
+            if let Ok(mut observed) = self.observed_api_version.write() {
+                *observed = Some(version);
+            }
+        }
+        if let Some(hook) = &self.deprecation_hook {
+            let notice =
+                DeprecationNotice::from_headers(method.clone(), response.url(), response.headers());
+            if let Some(notice) = notice {
+                hook.call(&notice);
+            }
+        }
+    }
+
+    /// Replaces the token of a request that was rejected with a new one
+    async fn reauthorize(
+        &self,
+        request: RequestBuilder,
+        url: &str,
+    ) -> Result<RequestBuilder, Error> {
+        let (client, request) = request.build_split();
+        let mut request = request?;
+        let area = ProductArea::of_url(url);
+        if let Some(rejected) = request.headers_mut().remove(AUTHORIZATION) {
+            self.invalidate_token(area, &rejected);
+        }
+        let token = self.cached_token(area).await?;
+        Ok(RequestBuilder::from_parts(client, request)
+            .bearer_auth(token.access_token.expose_secret()))
+    }
+
+    /// Removes the cached token of the product area if it is the rejected
+    /// one. A token that was already replaced, e.g. by a concurrent request
+    /// that was rejected too, is kept.
+    fn invalidate_token(&self, area: ProductArea, rejected: &HeaderValue) {
+        if let Ok(mut tokens) = self.tokens.write() {
+            let is_rejected = tokens.get(&area).is_some_and(|token| {
+                let bearer = format!("Bearer {}", token.access_token.expose_secret());
+                rejected.as_bytes() == bearer.as_bytes()
+            });
+            if is_rejected {
+                tokens.remove(&area);
+            }
+        }
+    }
+
+    async fn send_get_request<R>(&self, url: &str) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+    {
+        Ok(self.send_get_request_measured(url).await?.body)
+    }
+
+    async fn send_get_request_measured<R>(&self, url: &str) -> Result<ApiResponse<R>, Error>
+    where
+        R: DeserializeOwned,
+    {
+        let token = self.authorize(url).await?;
+
+        let summary = RequestSummary::new(Method::GET, url, None);
+        let request = self.http_client.get(url).bearer_auth(token.expose_secret());
+        let (response, measured) = self
+            .send_measured(request, summary, None, &self.request_options)
+            .await?
+            .split();
+
+        if response.status().is_success() {
+            let body = decode_response(Method::GET, response).await?;
+            Ok(measured.map(|()| body))
+        } else {
+            Err(error_from_response(Method::GET, response, Error::Api).await)
+        }
+    }
+
+    async fn send_get_request_with_query<Q, R>(&self, url: &str, query: &Q) -> Result<R, Error>
+    where
+        Q: Serialize,
+        R: DeserializeOwned,
+    {
+        let query = serde_urlencoded::to_string(query)
+            .map_err(|err| Error::InvalidQuery(err.to_string()))?;
+        if query.is_empty() {
+            self.send_get_request(url).await
+        } else {
+            self.send_get_request(&format!("{url}?{query}")).await
+        }
+    }
+
+    async fn send_delete_request(&self, url: &str) -> Result<(), Error> {
+        let token = self.authorize(url).await?;
+
+        let summary = RequestSummary::new(Method::DELETE, url, None);
+        let request = self
+            .http_client
+            .delete(url)
+            .bearer_auth(token.expose_secret());
+        let response = self.send(request, summary).await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(Method::DELETE, response, Error::Api).await)
+        }
+    }
+
+    async fn send_post_request<B, R>(&self, url: &str, body: &B) -> Result<R, Error>
+    where
+        B: Serialize + Auditable,
+        R: DeserializeOwned,
+    {
+        self.send_post_request_with_options(url, body, &self.request_options)
+            .await
+    }
+
+    async fn send_post_request_with_options<B, R>(
+        &self,
+        url: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<R, Error>
+    where
+        B: Serialize + Auditable,
+        R: DeserializeOwned,
+    {
+        let response = self
+            .send_request_with_body_measured(Method::POST, url, body, options)
+            .await?
+            .body;
+
+        if response.status().is_success() {
+            Ok(decode_response(Method::POST, response).await?)
+        } else {
+            Err(error_from_response(Method::POST, response, Error::Api).await)
+        }
+    }
+
+    async fn send_post_request_2<B>(&self, url: &str, body: &B) -> Result<Response, Error>
+    where
+        B: Serialize + Auditable,
+    {
+        self.send_request_with_body(Method::POST, url, body).await
+    }
+
+    async fn send_put_request<B>(&self, url: &str, body: &B) -> Result<(), Error>
+    where
+        B: Serialize + Auditable,
+    {
+        let response = self.send_request_with_body(Method::PUT, url, body).await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(Method::PUT, response, Error::Api).await)
+        }
+    }
+
+    async fn send_request_with_body<B>(
+        &self,
+        method: Method,
+        url: &str,
+        body: &B,
+    ) -> Result<Response, Error>
+    where
+        B: Serialize + Auditable,
+    {
+        Ok(self
+            .send_request_with_body_measured(method, url, body, &self.request_options)
+            .await?
+            .body)
+    }
+
+    async fn send_request_with_body_measured<B>(
+        &self,
+        method: Method,
+        url: &str,
+        body: &B,
+        options: &RequestOptions,
+    ) -> Result<ApiResponse<Response>, Error>
+    where
+        B: Serialize + Auditable,
+    {
+        let token = self.authorize(url).await?;
+
+        let summary = RequestSummary::new(method.clone(), url, Some(body));
+        let request = self
+            .http_client
+            .request(method, url)
+            .bearer_auth(token.expose_secret())
+            .json(body);
+        let recorded = self
+            .recorder
+            .as_ref()
+            .and_then(|_| recorder::redacted_body(body));
+        self.send_measured(request, summary, recorded, options)
+            .await
+    }
+
+    /// Request a payment or payout
+    ///
+    /// To accept payments from cards, digital wallets and many alternative
+    /// payment methods, specify the source.type field, along with the
+    /// source-specific data.
+    ///
+    /// To pay out to a card, specify the destination of your payout using the
+    /// destination.type field, along with the destination-specific data.
+    ///
+    /// To verify the success of the payment, check the approved field in the
+    /// response.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDLocal`] without sending the request if its
+    /// dLocal processing information is incomplete, see
+    /// [`CreatePaymentRequest::validate_dlocal`]
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_payment(
+        &self,
+        request: &CreatePaymentRequest,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.create_payment_with_options(request, &self.request_options)
+            .await
+    }
+
+    /// Request a payment or payout with options for this request only
+    ///
+    /// Same as [`Client::create_payment`], except that `options` are sent
+    /// instead of the client's, e.g. an idempotency key for this payment:
+    ///
+    /// ```ignore
+    /// let options = client.request_options().clone().idempotency_key(order_id);
+    /// let response = client.create_payment_with_options(&request, &options).await?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDLocal`] without sending the request if its
+    /// dLocal processing information is incomplete, see
+    /// [`CreatePaymentRequest::validate_dlocal`]
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_payment_with_options(
+        &self,
+        request: &CreatePaymentRequest,
+        options: &RequestOptions,
+    ) -> Result<CreatePaymentResponse, Error> {
+        request.validate_dlocal().map_err(Error::InvalidDLocal)?;
+        self.send_payment_request(request, options).await
+    }
+
+    /// Request a payment from borrowed data
+    ///
+    /// Like [`Client::create_payment`], but serializes the request's strings
+    /// by reference instead of requiring owned copies of them, for
+    /// high-throughput payment creation. See [`PaymentRequestRef`].
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_payment_ref(
+        &self,
+        request: &PaymentRequestRef<'_>,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.send_payment_request(request, &self.request_options)
+            .await
+    }
+
+    /// Request an Account Funding Transaction
+    ///
+    /// Pulls funds from the source to fund another account, after checking
+    /// that the sender and recipient data required for the merchant category
+    /// is present.
+    ///
+    /// See: [Account Funding Transactions](https://docs.checkout.com/payments/manage-payments/account-funding-transactions)
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAft`] without sending the request if it is
+    /// missing required data
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_aft(&self, request: &AftRequest) -> Result<CreatePaymentResponse, Error> {
+        let request = request
+            .clone()
+            .into_payment_request()
+            .map_err(Error::InvalidAft)?;
+        self.send_payment_request(&request, &self.request_options)
+            .await
+    }
+
+    /// Pay out to a card
+    ///
+    /// Sends funds to a card. Only payout-relevant fields can be set, so a
+    /// payout can't accidentally be combined with a payment source.
+    ///
+    /// See: [Card Payouts](https://docs.checkout.com/card-payouts)
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_card_payout(
+        &self,
+        request: &CardPayoutRequest,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.create_card_payout_with_options(request, &self.request_options)
+            .await
+    }
+
+    /// Pay out to a card with options for this request only
+    ///
+    /// Same as [`Client::create_card_payout`], except that `options` are
+    /// sent instead of the client's.
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_card_payout_with_options(
+        &self,
+        request: &CardPayoutRequest,
+        options: &RequestOptions,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.send_payment_request(request, options).await
+    }
+
+    /// Pay out to a bank account
+    ///
+    /// Sends funds from one of your currency accounts to a bank account.
+    ///
+    /// See: [Bank Payouts](https://docs.checkout.com/bank-payouts)
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_bank_payout(
+        &self,
+        request: &BankPayoutRequest,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.create_bank_payout_with_options(request, &self.request_options)
+            .await
+    }
+
+    /// Pay out to a bank account with options for this request only
+    ///
+    /// Same as [`Client::create_bank_payout`], except that `options` are
+    /// sent instead of the client's.
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_bank_payout_with_options(
+        &self,
+        request: &BankPayoutRequest,
+        options: &RequestOptions,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.send_payment_request(request, options).await
+    }
+
+    /// Submit a payout batch
+    ///
+    /// Validates the batch and submits its payouts, up to `concurrency` at a
+    /// time. Payouts recorded as completed in the store are skipped, and every
+    /// payout is sent with an idempotency key derived from the batch, so a
+    /// batch can safely be submitted again to retry its failed payouts or to
+    /// resume it after a crash.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBatch`] if the batch is invalid. Failures of
+    /// individual payouts are reported in the [`BatchReport`].
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn submit_payout_batch(
+        &self,
+        batch: &PayoutBatch,
+        store: &dyn BatchStore,
+        concurrency: usize,
+    ) -> Result<BatchReport, Error> {
+        batch.validate().map_err(Error::InvalidBatch)?;
+
+        let mut report = BatchReport::default();
+        let mut pending = Vec::new();
+        for (reference, payout) in batch.payouts() {
+            match store.completed(batch.id(), reference) {
+                Some(payment_id) => report.skipped.push((reference.clone(), payment_id)),
+                None => pending.push((reference, payout)),
+            }
+        }
+
+        for chunk in pending.chunks(concurrency.max(1)) {
+            let submissions = chunk.iter().map(|(reference, payout)| async move {
+                let options = self
+                    .request_options
+                    .clone()
+                    .idempotency_key(batch.idempotency_key(reference));
+                let response = match payout {
+                    BatchPayout::Card(request) => {
+                        self.create_card_payout_with_options(request, &options)
+                            .await
+                    }
+                    BatchPayout::Bank(request) => {
+                        self.create_bank_payout_with_options(request, &options)
+                            .await
+                    }
+                };
+                ((*reference).clone(), response)
+            });
+
+            for (reference, response) in JoinAll::new(submissions).await {
+                match response {
+                    Ok(response) => {
+                        store.record(batch.id(), &reference, response.payment_id());
+                        report.submitted.push((reference, response));
+                    }
+                    Err(err) => report.failed.push((reference, err)),
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn send_payment_request<B>(
+        &self,
+        request: &B,
+        options: &RequestOptions,
+    ) -> Result<CreatePaymentResponse, Error>
+    where
+        B: Serialize + Auditable,
+    {
+        Ok(self
+            .send_payment_request_measured(request, options)
+            .await?
+            .body)
+    }
+
+    async fn send_payment_request_measured<B>(
+        &self,
+        request: &B,
+        options: &RequestOptions,
+    ) -> Result<ApiResponse<CreatePaymentResponse>, Error>
+    where
+        B: Serialize + Auditable,
+    {
+        let url = format!("{}/payments", self.api_url());
+        let measured = self
+            .send_request_with_body_measured(Method::POST, &url, request, options)
+            .await?;
+        let (response, measured) = measured.split();
+
+        let status = response.status();
+        let body = match status {
+            StatusCode::CREATED => {
+                CreatePaymentResponse::Processed(decode_response(Method::POST, response).await?)
+            }
+            StatusCode::ACCEPTED => {
+                CreatePaymentResponse::Pending(decode_response(Method::POST, response).await?)
+            }
+            StatusCode::UNAUTHORIZED => return Err(Error::Unauthorized),
+            StatusCode::UNPROCESSABLE_ENTITY => {
+                return Err(error_from_response(Method::POST, response, Error::InvalidData).await)
+            }
+            StatusCode::TOO_MANY_REQUESTS => return Err(Error::TooManyRequests),
+            code => {
+                let body = response.text().await?;
+                return Err(Error::Unknown(code, body));
+            }
+        };
+        self.emit(|| PaymentEvent::payment_created(&body));
+        Ok(measured.map(|()| body))
+    }
+
+    /// Request a payment through the processing channel selected by `router`
+    ///
+    /// Sets the request's processing channel to the channel the router
+    /// selects for `context`, unless the request already names a channel,
+    /// and records the channel in the router's usage.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NoEligibleChannel`] without sending the request if no
+    /// channel accepts the payment
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_routed_payment(
+        &self,
+        router: &ChannelRouter,
+        request: &CreatePaymentRequest,
+        context: &RoutingContext,
+    ) -> Result<RoutedPayment, Error> {
+        let channel_id = match &request.processing_channel_id {
+            Some(channel_id) => channel_id.clone(),
+            None => router
+                .select(context)
+                .ok_or(Error::NoEligibleChannel)?
+                .id()
+                .clone(),
+        };
+
+        let mut request = request.clone();
+        request.processing_channel_id = Some(channel_id.clone());
+        let response = self.create_payment(&request).await?;
+        router.record(&channel_id);
+        Ok(RoutedPayment {
+            channel_id,
+            response,
+        })
+    }
+
+    /// Request a payment, giving up when `cancel` completes
+    ///
+    /// Useful when the shopper navigates away while the payment is in flight,
+    /// e.g. with `token.cancelled()` of a `tokio_util` cancellation token. If
+    /// the payment was processed before the cancellation, its response is
+    /// returned. Otherwise [`Error::Cancelled`] is returned with the
+    /// idempotency key the payment was sent with: the payment may still be
+    /// processed by Checkout, so retry it with the key to find out. A key is
+    /// generated if the client's [`RequestOptions`] don't set one.
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_payment_with_cancellation<C>(
+        &self,
+        request: &CreatePaymentRequest,
+        cancel: C,
+    ) -> Result<CreatePaymentResponse, Error>
+    where
+        C: Future<Output = ()>,
+    {
+        // The key is chosen before sending, so that it is known whenever the
+        // request is cancelled
+        let mut options = self.request_options.clone();
+        if options.idempotency_key.is_none() {
+            options = options.idempotency_key(Ulid::new().to_string());
+        }
+        let payment = self.create_payment_with_options(request, &options);
+        match cancel::Cancellable::new(payment, cancel).await {
+            Some(response) => response,
+            None => Err(Error::Cancelled(options.idempotency_key)),
+        }
+    }
+
+    /// Request a payment, measuring the request
+    ///
+    /// Same as [`Client::create_payment`], except that the response carries
+    /// the request id, status, latency and number of attempts of the request.
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn create_payment_measured(
+        &self,
+        request: &CreatePaymentRequest,
+    ) -> Result<ApiResponse<CreatePaymentResponse>, Error> {
+        request.validate_dlocal().map_err(Error::InvalidDLocal)?;
+        self.send_payment_request_measured(request, &self.request_options)
+            .await
+    }
+
+    /// Request a payment, treating declines as errors
+    ///
+    /// Same as [`Client::create_payment`], except that a payment that was
+    /// processed but not approved is returned as [`Error::Declined`].
+    pub async fn create_payment_strict(
+        &self,
+        request: &CreatePaymentRequest,
+    ) -> Result<CreatePaymentResponse, Error> {
+        self.create_payment(request)
+            .await?
+            .ensure_approved()
+            .map_err(Error::Declined)
+    }
+
+    /// Charge stored payment details
+    ///
+    /// Requests a merchant-initiated payment (MIT) against a stored instrument
+    /// or a customer's default instrument. Persist the
+    /// [`scheme_id`](CreatePaymentResponse::scheme_id) of the response and
+    /// pass it as the `previous_payment_id` of the next charge in the series.
+    pub async fn charge_stored_instrument(
+        &self,
+        source: StoredPaymentSource,
+        money: Money,
+        options: StoredChargeOptions,
+    ) -> Result<CreatePaymentResponse, Error> {
+        let request = CreatePaymentRequest::merchant_initiated(source, money, options);
+        self.create_payment(&request).await
+    }
+
+    /// Get payment details
+    ///
+    /// Returns the details of the payment with the specified identifier
+    /// string.
+    ///
+    /// If the payment method requires a redirection to a third party (e.g., 3D
+    /// Secure), the redirect URL back to your site will include a
+    /// `cko-session-id` query parameter containing a payment session ID that
+    /// can be used to obtain the details of the payment
+    ///
+    /// [`GET /payments/{id}`](https://api-reference.checkout.com/#operation/getPaymentDetails)
+    pub async fn get_payment_details(
+        &self,
+        payment_id: String,
+    ) -> Result<GetPaymentDetailsResponse, Error> {
+        let url = format!("{}/payments/{}", self.api_url(), payment_id);
+        self.send_get_request(&url).await
+    }
+
+    /// Get payment details, measuring the request
+    ///
+    /// Same as [`Client::get_payment_details`], except that the response
+    /// carries the request id, status, latency and number of attempts of the
+    /// request.
+    ///
+    /// [`GET /payments/{id}`](https://api-reference.checkout.com/#operation/getPaymentDetails)
+    pub async fn get_payment_details_measured(
+        &self,
+        payment_id: String,
+    ) -> Result<ApiResponse<GetPaymentDetailsResponse>, Error> {
+        let url = format!("{}/payments/{}", self.api_url(), payment_id);
+        self.send_get_request_measured(&url).await
+    }
+
+    /// Get payment actions
+    ///
+    /// Returns all the actions associated with a payment ordered by processing
+    /// date in descending order (latest first). Use [`PaymentActions`] to
+    /// extract the captures and refunds.
+    ///
+    /// The endpoint can't be paged, so all actions are returned in one
+    /// response however many partial captures and refunds the payment has.
+    ///
+    /// [`GET /payments/{id}/actions`](https://api-reference.checkout.com/#operation/getPaymentActions)
+    pub async fn get_payment_actions(
+        &self,
+        payment_id: String,
+    ) -> Result<GetPaymentActionsResponse, Error> {
+        let url = format!("{}/payments/{}/actions", self.api_url(), payment_id);
+        self.send_get_request(&url).await
+    }
+
+    /// Get filtered payment actions
+    ///
+    /// Returns the actions of a payment that match the filter, ordered by
+    /// processing date in descending order (latest first). The endpoint
+    /// doesn't support filters, so all actions are fetched and filtered here.
+    ///
+    /// [`GET /payments/{id}/actions`](https://api-reference.checkout.com/#operation/getPaymentActions)
+    pub async fn get_payment_actions_filtered(
+        &self,
+        payment_id: String,
+        filter: &ActionFilter,
+    ) -> Result<GetPaymentActionsResponse, Error> {
+        let actions = self.get_payment_actions(payment_id).await?;
+        Ok(filter.apply(actions))
+    }
+
+    /// Get a payment's timeline
+    ///
+    /// Combines the payment's details with its actions, ordered by processing
+    /// date in ascending order (oldest first), along with the running
+    /// authorized, captured and refunded balances after each action.
+    pub async fn payment_timeline(&self, payment_id: String) -> Result<PaymentTimeline, Error> {
+        let payment = self.get_payment_details(payment_id.clone()).await?;
+        let actions = self.get_payment_actions(payment_id).await?;
+        Ok(PaymentTimeline::new(payment, actions))
+    }
+
+    /// Capture a payment
+    ///
+    /// Captures a payment if supported by the payment method.
+    ///
+    /// For card payments, capture requests are processed asynchronously. You
+    /// can use webhooks to be notified if the capture is successful.
+    ///
+    /// [`POST /payments/{id}/captures`](https://api-reference.checkout.com/#operation/captureAPayment)
+    pub async fn capture_payment(
+        &self,
+        payment_id: String,
+        body: &CapturePaymentBody,
+    ) -> Result<CapturePaymentResponse, Error> {
+        self.capture_payment_with_options(payment_id, body, &self.request_options)
+            .await
+    }
+
+    /// Capture a payment with options for this request only
+    ///
+    /// Same as [`Client::capture_payment`], except that `options` are sent
+    /// instead of the client's, e.g. an idempotency key for this capture.
+    ///
+    /// [`POST /payments/{id}/captures`](https://api-reference.checkout.com/#operation/captureAPayment)
+    pub async fn capture_payment_with_options(
+        &self,
+        payment_id: String,
+        body: &CapturePaymentBody,
+        options: &RequestOptions,
+    ) -> Result<CapturePaymentResponse, Error> {
+        let url = format!("{}/payments/{}/captures", self.api_url(), payment_id);
+        let response = self
+            .send_post_request_with_options(&url, body, options)
+            .await?;
+        let amount = body.amount.map(|money| money.amount.minor_units());
+        self.emit(|| PaymentEvent::capture_requested(&payment_id, amount, &response));
+        Ok(response)
+    }
+
+    /// Capture a payment after checking the amount against the payment
+    ///
+    /// Fetches the payment's details and captures it like
+    /// [`Client::capture_payment`] if the amount of `body` is in the
+    /// payment's currency and does not exceed the amount that is still
+    /// available to capture. Without balances in the details, the amount
+    /// captured so far is taken from the payment's actions.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AmountMismatch`] without sending the capture if the
+    /// amount does not match the payment
+    ///
+    /// [`POST /payments/{id}/captures`](https://api-reference.checkout.com/#operation/captureAPayment)
+    pub async fn capture_payment_checked(
+        &self,
+        payment_id: String,
+        body: &CapturePaymentBody,
+    ) -> Result<CapturePaymentResponse, Error> {
+        let capturable = self.capturable(payment_id.clone()).await?;
+        check_action_amount(capturable, body.amount)?;
+        self.capture_payment(payment_id, body).await
+    }
+
+    async fn capturable(&self, payment_id: String) -> Result<Money, Error> {
+        let payment = self.get_payment_details(payment_id.clone()).await?;
+        let amount = if let Some(balances) = payment.balances {
+            balances.available_to_capture.minor_units()
+        } else {
+            let actions = self.get_payment_actions(payment_id).await?;
+            let captured = timeline_entries(actions)
+                .last()
+                .map_or(0, |entry| entry.captured);
+            payment.amount.minor_units().saturating_sub(captured)
+        };
+        Ok(Money {
+            amount: Amount::from_minor_units(amount),
+            currency: payment.currency,
+        })
+    }
+
+    /// Capture a payment, voiding it if the capture can't succeed
+    ///
+    /// Captures the payment like [`Client::capture_payment`]. If the capture
+    /// fails with one of the [`CaptureFailure`] classes and `void_on_failure`
+    /// is set, the authorization is voided so that the shopper's funds are
+    /// released. Other errors are returned as is.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the capture request if it failed for a reason
+    /// other than a [`CaptureFailure`].
+    pub async fn try_capture_or_void(
+        &self,
+        payment_id: String,
+        body: &CapturePaymentBody,
+        void_on_failure: bool,
+    ) -> Result<CaptureOutcome, Error> {
+        let error = match self.capture_payment(payment_id.clone(), body).await {
+            Ok(capture) => return Ok(CaptureOutcome::Captured(capture)),
+            Err(error) => error,
+        };
+
+        let Some(failure) = CaptureFailure::of(&error) else {
+            return Err(error);
+        };
+
+        if !void_on_failure {
+            return Ok(CaptureOutcome::NotCaptured { failure, error });
+        }
+
+        let void = VoidPaymentBody {
+            reference: body.reference.clone(),
+            metadata: body.metadata.clone(),
+        };
+        Ok(match self.void_payment(payment_id, &void).await {
+            Ok(void) => CaptureOutcome::Voided { failure, void },
+            Err(error) => CaptureOutcome::NotCaptured { failure, error },
+        })
+    }
+
+    /// Authorize a payment to capture or void it later
+    ///
+    /// Requests the payment without capturing it and returns a handle to
+    /// store, e.g. with the order, and to capture or void the payment with
+    /// later. See [`AuthorizationHandle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Declined`] if the payment was declined, or
+    /// [`Error::NotAuthorized`] if it was not authorized, e.g. because it
+    /// requires a 3D Secure redirect. Once such a payment is authorized,
+    /// create its handle with [`AuthorizationHandle::from_details`].
+    ///
+    /// [`POST /payments`](https://api-reference.checkout.com/#operation/requestAPaymentOrPayout)
+    pub async fn authorize_payment(
+        &self,
+        request: &CreatePaymentRequest,
+    ) -> Result<AuthorizationHandle, Error> {
+        let request = CreatePaymentRequest {
+            capture: Some(false),
+            ..request.clone()
+        };
+        let response = self
+            .create_payment(&request)
+            .await?
+            .ensure_approved()
+            .map_err(Error::Declined)?;
+
+        let handle = match &response {
+            CreatePaymentResponse::Processed(payment) => AuthorizationHandle::new(payment),
+            CreatePaymentResponse::Pending(_) => None,
+        };
+        handle.ok_or_else(|| Error::NotAuthorized(Box::new(response)))
+    }
+
+    /// Capture an authorized payment
+    ///
+    /// Captures the amount of `body`, or the remaining amount of the
+    /// authorization if it is `None`, with an idempotency key derived from
+    /// the handle, and records the capture in the handle. Store the handle
+    /// again afterwards. A capture of type [`CaptureType::NonFinal`] leaves
+    /// the rest of the authorization to be captured later.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidCapture`] without sending the capture if the
+    /// authorization has expired or the amount exceeds the remaining amount
+    ///
+    /// [`POST /payments/{id}/captures`](https://api-reference.checkout.com/#operation/captureAPayment)
+    pub async fn capture_authorization(
+        &self,
+        handle: &mut AuthorizationHandle,
+        body: &CapturePaymentBody,
+    ) -> Result<CapturePaymentResponse, Error> {
+        let authorized = Money {
+            amount: handle.amount,
+            currency: handle.currency,
+        };
+        let requested = match body.amount {
+            Some(requested) if requested.currency != handle.currency => {
+                return Err(Error::AmountMismatch {
+                    payment: authorized,
+                    requested,
+                })
+            }
+            requested => requested.map(|money| money.amount.minor_units()),
+        };
+        let amount = handle
+            .check_capture(requested, chrono::Utc::now())
+            .map_err(Error::InvalidCapture)?;
+        let body = CapturePaymentBody {
+            amount: Some(Money {
+                amount: Amount::from_minor_units(amount),
+                currency: handle.currency,
+            }),
+            ..body.clone()
+        };
+
+        let options = self
+            .request_options
+            .clone()
+            .idempotency_key(handle.capture_key());
+        let response = self
+            .capture_payment_with_options(handle.payment_id.to_string(), &body, &options)
+            .await?;
+        handle.record_capture(amount, body.capture_type);
+        Ok(response)
+    }
+
+    /// Void an authorized payment
+    ///
+    /// Releases the authorization, or its remaining amount after non-final
+    /// captures, with an idempotency key derived from the handle, and
+    /// records the void in the handle. Store the handle again afterwards.
+    ///
+    /// [`POST /payments/{id}/voids`](https://api-reference.checkout.com/#operation/voidAPayment)
+    pub async fn void_authorization(
+        &self,
+        handle: &mut AuthorizationHandle,
+        body: &VoidPaymentBody,
+    ) -> Result<VoidPaymentResponse, Error> {
+        let options = self
+            .request_options
+            .clone()
+            .idempotency_key(handle.void_key());
+        let response = self
+            .void_payment_with_options(handle.payment_id.to_string(), body, &options)
+            .await?;
+        handle.record_void();
+        Ok(response)
+    }
+
+    /// Refund a payment
+    ///
+    /// Refunds a payment if supported by the payment method.
+    ///
+    /// For card payments, refund requests are processed asynchronously. You
+    /// can use webhooks to be notified if the refund is successful.
+    ///
+    /// [`POST /payments/{id}/refunds`](https://api-reference.checkout.com/#operation/refundAPayment)
+    pub async fn refund_payment(
+        &self,
+        payment_id: String,
+        body: &RefundPaymentBody,
+    ) -> Result<RefundPaymentResponse, Error> {
+        self.refund_payment_with_options(payment_id, body, &self.request_options)
+            .await
+    }
+
+    /// Refund a payment with options for this request only
+    ///
+    /// Same as [`Client::refund_payment`], except that `options` are sent
+    /// instead of the client's, e.g. an idempotency key for this refund.
+    ///
+    /// [`POST /payments/{id}/refunds`](https://api-reference.checkout.com/#operation/refundAPayment)
+    pub async fn refund_payment_with_options(
+        &self,
+        payment_id: String,
+        body: &RefundPaymentBody,
+        options: &RequestOptions,
+    ) -> Result<RefundPaymentResponse, Error> {
+        let url = format!("{}/payments/{}/refunds", self.api_url(), payment_id);
+        let response = self
+            .send_post_request_with_options(&url, body, options)
+            .await?;
+        let amount = body.amount.map(|money| money.amount.minor_units());
+        self.emit(|| PaymentEvent::refund_requested(&payment_id, amount, &response));
+        Ok(response)
+    }
+
+    /// Refund a payment after checking the amount against the payment
+    ///
+    /// Fetches the payment's details and refunds it like
+    /// [`Client::refund_payment`] if the amount of `body` is in the
+    /// payment's currency and does not exceed the amount that is still
+    /// available to refund, see [`Client::refundable_amount`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AmountMismatch`] without sending the refund if the
+    /// amount does not match the payment
+    ///
+    /// [`POST /payments/{id}/refunds`](https://api-reference.checkout.com/#operation/refundAPayment)
+    pub async fn refund_payment_checked(
+        &self,
+        payment_id: String,
+        body: &RefundPaymentBody,
+    ) -> Result<RefundPaymentResponse, Error> {
+        let refundable = self.refundable(payment_id.clone()).await?;
+        check_action_amount(refundable, body.amount)?;
+        self.refund_payment(payment_id, body).await
+    }
+
+    /// Get the refundable amount of a payment
+    ///
+    /// Returns the amount, in the payment currency's minor units, that has
+    /// been captured and can still be refunded. Uses the payment's balances
+    /// when available and otherwise computes it from the payment's actions.
+    pub async fn refundable_amount(&self, payment_id: String) -> Result<u64, Error> {
+        let refundable = self.refundable(payment_id).await?;
+        Ok(refundable.amount.minor_units())
+    }
+
+    async fn refundable(&self, payment_id: String) -> Result<Money, Error> {
+        let payment = self.get_payment_details(payment_id.clone()).await?;
+        let amount = if let Some(balances) = payment.balances {
+            balances.available_to_refund.minor_units()
+        } else {
+            let actions = self.get_payment_actions(payment_id).await?;
+            timeline_entries(actions)
+                .last()
+                .map_or(0, TimelineEntry::net_captured)
+        };
+        Ok(Money {
+            amount: Amount::from_minor_units(amount),
+            currency: payment.currency,
+        })
+    }
+
+    /// Refund the remaining amount of a payment
+    ///
+    /// Refunds whatever can still be refunded, so that a payment is never
+    /// over-refunded. When `max_per_refund` is set, the remaining amount is
+    /// split into several partial refunds of at most that amount. Returns no
+    /// refunds when nothing is left to refund.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::PartiallyRefunded`] with the refunds that were made
+    /// if a refund fails after the first one, so that they are not made
+    /// again. Errors before the first refund are returned as is.
+    pub async fn refund_remaining(
+        &self,
+        payment_id: String,
+        max_per_refund: Option<u64>,
+        reference: Option<String>,
+    ) -> Result<Vec<RefundPaymentResponse>, Error> {
+        let refundable = self.refundable(payment_id.clone()).await?;
+        let mut remaining = refundable.amount.minor_units();
+
+        let mut refunds = Vec::new();
+        while remaining > 0 {
+            let amount = max_per_refund.map_or(remaining, |max| remaining.min(max.max(1)));
+            let body = RefundPaymentBody {
+                amount: Some(Money {
+                    amount: Amount::from_minor_units(amount),
+                    currency: refundable.currency,
+                }),
+                reference: reference.clone(),
+                metadata: None,
+                extra: None,
+            };
+            match self.refund_payment(payment_id.clone(), &body).await {
+                Ok(refund) => refunds.push(refund),
+                Err(err) if refunds.is_empty() => return Err(err),
+                Err(err) => {
+                    return Err(Error::PartiallyRefunded {
+                        refunds,
+                        source: Box::new(err),
+                    })
+                }
+            }
+            remaining -= amount;
+        }
+
+        Ok(refunds)
+    }
+
+    /// Void a payment
+    ///
+    /// Voids a payment if supported by the payment method.
+    ///
+    /// For card payments, void requests are processed asynchronously. You can
+    /// use webhooks to be notified if the void is successful.
+    ///
+    /// [`POST /payments/{id}/voids`](https://api-reference.checkout.com/#operation/voidAPayment)
+    pub async fn void_payment(
+        &self,
+        payment_id: String,
+        body: &VoidPaymentBody,
+    ) -> Result<VoidPaymentResponse, Error> {
+        self.void_payment_with_options(payment_id, body, &self.request_options)
+            .await
+    }
+
+    /// Void a payment with options for this request only
+    ///
+    /// Same as [`Client::void_payment`], except that `options` are sent
+    /// instead of the client's, e.g. an idempotency key for this void.
+    ///
+    /// [`POST /payments/{id}/voids`](https://api-reference.checkout.com/#operation/voidAPayment)
+    pub async fn void_payment_with_options(
+        &self,
+        payment_id: String,
+        body: &VoidPaymentBody,
+        options: &RequestOptions,
+    ) -> Result<VoidPaymentResponse, Error> {
+        let url = format!("{}/payments/{}/voids", self.api_url(), payment_id);
+        let response = self
+            .send_post_request_with_options(&url, body, options)
+            .await?;
+        self.emit(|| PaymentEvent::void_succeeded(&payment_id, &response));
+        Ok(response)
+    }
+
+    /// Get card metadata
+    ///
+    /// Returns details about a card, such as its scheme, type and issuer, and
+    /// for the `card_payouts` format its payout eligibility.
+    ///
+    /// [`POST /metadata/card`](https://api-reference.checkout.com/#operation/requestCardMetadata)
+    pub async fn get_card_metadata(
+        &self,
+        request: &CardMetadataRequest,
+    ) -> Result<CardMetadataResponse, Error> {
+        let url = format!("{}/metadata/card", self.api_url());
+        self.send_post_request(&url, request).await
+    }
+
+    /// Check whether a card can receive payouts
+    ///
+    /// Looks up the card's payout eligibility before attempting a payout, e.g.
+    /// to tell a seller up-front whether an instant (fast funds) payout is
+    /// possible. The eligibility is unknown for all kinds of payouts if the
+    /// card metadata does not include it.
+    ///
+    /// [`POST /metadata/card`](https://api-reference.checkout.com/#operation/requestCardMetadata)
+    pub async fn check_payout_eligibility(
+        &self,
+        source: CardMetadataSource,
+    ) -> Result<CardPayoutsEligibility, Error> {
+        let request = CardMetadataRequest {
+            source,
+            format: Some(CardMetadataFormat::CardPayouts),
+        };
+        let metadata = self.get_card_metadata(&request).await?;
+        Ok(metadata.card_payouts.unwrap_or_default())
+    }
+
+    /// Store a bank account
+    ///
+    /// Stores bank account details as an instrument, e.g. to pay out to the
+    /// account with just the instrument id later.
+    ///
+    /// [`POST /instruments`](https://api-reference.checkout.com/#operation/createAnInstrument)
+    pub async fn create_bank_account_instrument(
+        &self,
+        body: &CreateBankAccountInstrumentBody,
+    ) -> Result<CreateInstrumentResponse, Error> {
+        let url = format!("{}/instruments", self.api_url());
+        self.send_post_request(&url, body).await
+    }
+
+    /// Update an instrument
+    ///
+    /// Updates the expiry date, account holder details or customer of a
+    /// stored instrument. Only the fields provided in the body are changed.
+    ///
+    /// [`PATCH /instruments/{id}`](https://api-reference.checkout.com/#operation/updateAnInstrument)
+    pub async fn update_instrument(
+        &self,
+        instrument_id: String,
+        body: &UpdateInstrumentBody,
+    ) -> Result<UpdateInstrumentResponse, Error> {
+        let url = format!("{}/instruments/{}", self.api_url(), instrument_id);
+        let response = self
+            .send_request_with_body(Method::PATCH, &url, body)
+            .await?;
+
+        match response.status() {
+            status if status.is_success() => Ok(decode_response(Method::PATCH, response).await?),
+            StatusCode::CONFLICT => {
+                Err(error_from_response(Method::PATCH, response, Error::InstrumentInUse).await)
+            }
+            _ => Err(error_from_response(Method::PATCH, response, Error::Api).await),
+        }
+    }
+
+    /// Delete an instrument
+    ///
+    /// Deletes a stored instrument, detaching it from its customer. Checkout
+    /// has no way to detach an instrument without deleting it.
+    ///
+    /// [`DELETE /instruments/{id}`](https://api-reference.checkout.com/#operation/deleteAnInstrument)
+    pub async fn delete_instrument(&self, instrument_id: String) -> Result<(), Error> {
+        let url = format!("{}/instruments/{}", self.api_url(), instrument_id);
+        self.send_delete_request(&url).await
+    }
+
+    /// Get customer details
+    ///
+    /// Returns the details of a customer and their stored instruments.
+    ///
+    /// [`GET /customers/{id}`](https://api-reference.checkout.com/#operation/getCustomerDetails)
+    pub async fn get_customer(&self, customer_id: String) -> Result<Customer, Error> {
+        let url = format!("{}/customers/{}", self.api_url(), customer_id);
+        self.send_get_request(&url).await
+    }
+
+    /// Update customer details
+    ///
+    /// Updates the details of a customer. Only the fields provided in the body
+    /// are changed.
+    ///
+    /// [`PATCH /customers/{id}`](https://api-reference.checkout.com/#operation/updateCustomerDetails)
+    pub async fn update_customer(
+        &self,
+        customer_id: String,
+        body: &UpdateCustomerBody,
+    ) -> Result<(), Error> {
+        let url = format!("{}/customers/{}", self.api_url(), customer_id);
+        let response = self
+            .send_request_with_body(Method::PATCH, &url, body)
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(Method::PATCH, response, Error::Api).await)
+        }
+    }
+
+    /// Attach an existing instrument to a customer, optionally making it the
+    /// customer's default instrument
+    pub async fn attach_instrument(
+        &self,
+        customer_id: String,
+        instrument_id: String,
+        default: bool,
+    ) -> Result<UpdateInstrumentResponse, Error> {
+        let body = UpdateInstrumentBody {
+            customer: Some(InstrumentCustomer {
+                id: customer_id,
+                default,
+            }),
+            ..UpdateInstrumentBody::default()
+        };
+        self.update_instrument(instrument_id, &body).await
+    }
+
+    /// Set a customer's default instrument
+    pub async fn set_default_instrument(
+        &self,
+        customer_id: String,
+        instrument_id: String,
+    ) -> Result<(), Error> {
+        let body = UpdateCustomerBody {
+            default: Some(instrument_id),
+            ..UpdateCustomerBody::default()
+        };
+        self.update_customer(customer_id, &body).await
+    }
+
+    /// Create a Hosted Payments Page session
+    ///
+    /// Creates a session for a payment page hosted by Checkout. Redirect the
+    /// customer to the `redirect` link of the response to complete the
+    /// payment.
+    ///
+    /// [`POST /hosted-payments`](https://api-reference.checkout.com/#operation/createAHostedPaymentsSession)
+    pub async fn create_hosted_payment(
+        &self,
+        request: &CreateHostedPaymentRequest,
+    ) -> Result<CreateHostedPaymentResponse, Error> {
+        let url = format!("{}/hosted-payments", self.api_url());
+        self.send_post_request(&url, request).await
+    }
+
+    /// Request a session
+    ///
+    /// Creates a standalone 3D Secure authentication session. For in-app
+    /// authentication, pass the values of the 3DS2 SDK as
+    /// [`ChannelData::App`] and hand the returned session's secret to the
+    /// SDK to complete the challenge.
+    ///
+    /// [`POST /sessions`](https://api-reference.checkout.com/#operation/requestSession)
+    pub async fn create_session(&self, request: &CreateSessionRequest) -> Result<Session, Error> {
+        let url = format!("{}/sessions", self.api_url());
+        self.send_post_request(&url, request).await
+    }
+
+    /// Create a Payment Link
+    ///
+    /// Creates a link to a payment page hosted by Checkout that can be shared
+    /// with the customer, e.g. by email or chat.
+    ///
+    /// [`POST /payment-links`](https://api-reference.checkout.com/#operation/createAPaymentLinkSession)
+    pub async fn create_payment_link(
+        &self,
+        request: &CreatePaymentLinkRequest,
+    ) -> Result<CreatePaymentLinkResponse, Error> {
+        let url = format!("{}/payment-links", self.api_url());
+        self.send_post_request(&url, request).await
+    }
+
+    /// Get disputes
+    ///
+    /// Returns a list of all disputes against your business, most recently
+    /// updated first, matching the filter.
+    ///
+    /// [`GET /disputes`](https://api-reference.checkout.com/#operation/getDisputes)
+    pub async fn get_disputes(&self, filter: &DisputeFilter) -> Result<GetDisputesResponse, Error> {
+        let url = format!("{}/disputes", self.api_url());
+        self.send_get_request_with_query(&url, filter).await
+    }
+
+    /// Get reports
+    ///
+    /// Returns the first page of the reports matching the filter. Use
+    /// [`Client::next_page`], [`Client::fetch_all_pages`] or
+    /// [`Client::pages`] to get the following pages.
+    ///
+    /// [`GET /reports`](https://api-reference.checkout.com/#operation/getAllReports)
+    pub async fn get_reports(&self, filter: &ReportFilter) -> Result<GetReportsResponse, Error> {
+        let url = format!("{}/reports", self.api_url());
+        self.send_get_request_with_query(&url, filter).await
+    }
+
+    /// Get payout statements
+    ///
+    /// Returns the first page of the statements of your payouts matching the
+    /// filter, for reconciliation. Use [`Client::next_page`],
+    /// [`Client::fetch_all_pages`] or [`Client::pages`] to get the following
+    /// pages.
+    ///
+    /// [`GET /reporting/statements`](https://api-reference.checkout.com/#operation/getStatements)
+    pub async fn get_payout_statements(
+        &self,
+        filter: &PayoutStatementFilter,
+    ) -> Result<GetPayoutStatementsResponse, Error> {
+        let url = format!("{}/reporting/statements", self.api_url());
+        self.send_get_request_with_query(&url, filter).await
+    }
+
+    /// Get the financial actions of a payment or action
+    ///
+    /// Returns how the payment's captures, refunds and chargebacks were
+    /// settled, with the scheme fees, interchange and Checkout fees deducted
+    /// from each, see [`FinancialAction::holding_currency_total`]. Use
+    /// [`Client::next_page`], [`Client::fetch_all_pages`] or
+    /// [`Client::pages`] to get the following pages.
+    ///
+    /// [`GET /financial-actions`](https://api-reference.checkout.com/#operation/getFinancialActions)
+    pub async fn get_financial_actions(
+        &self,
+        filter: &FinancialActionFilter,
+    ) -> Result<GetFinancialActionsResponse, Error> {
+        let url = format!("{}/financial-actions", self.api_url());
+        self.send_get_request_with_query(&url, filter).await
+    }
+
+    /// Download a report file
+    ///
+    /// Streams the file instead of buffering it in memory, see [`Download`].
+    ///
+    /// [`GET /reports/{id}/files/{fileId}`](https://api-reference.checkout.com/#operation/getReportFile)
+    pub async fn download_report_file(
+        &self,
+        report_id: String,
+        file_id: String,
+    ) -> Result<Download, Error> {
+        let url = format!("{}/reports/{}/files/{}", self.api_url(), report_id, file_id);
+        let token = self.authorize(&url).await?;
+
+        let summary = RequestSummary::new(Method::GET, &url, None);
+        let request = self
+            .http_client
+            .get(&url)
+            .bearer_auth(token.expose_secret());
+        let response = self.send(request, summary).await?;
+
+        if response.status().is_success() {
+            Ok(Download::new(response))
+        } else {
+            Err(error_from_response(Method::GET, response, Error::Api).await)
+        }
+    }
+
+    /// Get the next page of a paginated list
+    ///
+    /// Follows the `next` link of the page. Returns `None` if the page is the
+    /// last one. Call it repeatedly to process the pages one at a time, or
+    /// use [`Client::pages`].
+    pub async fn next_page<P>(&self, page: &P) -> Result<Option<P>, Error>
+    where
+        P: Paginated,
+    {
+        match page.next_page_url() {
+            Some(url) => Ok(Some(self.send_get_request(url).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the items of all pages of a paginated list
+    ///
+    /// Follows the `next` links starting from the first page and accumulates
+    /// the items of every page.
+    pub async fn fetch_all_pages<P>(&self, first_page: P) -> Result<Vec<P::Item>, Error>
+    where
+        P: Paginated,
+    {
+        let mut items = Vec::new();
+        let mut page = Some(first_page);
+        while let Some(current) = page {
+            page = self.next_page(&current).await?;
+            items.extend(current.into_items());
+        }
+        Ok(items)
+    }
+
+    /// Stream the pages of a paginated list
+    ///
+    /// Yields `first_page` and then follows the `next` links, fetching each
+    /// page only once the previous one was consumed. See [`Pages`].
+    pub fn pages<'a, P>(&'a self, first_page: P) -> Pages<'a, P>
+    where
+        P: Paginated + Send + 'a,
+    {
+        Pages::new(self, first_page)
+    }
+
+    /// Upload a file
+    ///
+    /// Uploads a file, e.g. dispute evidence, after checking it against the
+    /// size and type limits of the Files API. See [`FileUpload`].
+    ///
+    /// [`POST /files`](https://api-reference.checkout.com/#operation/uploadAFile)
+    pub async fn upload_file(&self, upload: FileUpload) -> Result<UploadFileResponse, Error> {
+        let form = upload.into_form()?;
+
+        let url = format!("{}/files", self.api_url());
+        let token = self.authorize(&url).await?;
+
+        let summary = RequestSummary::new(Method::POST, &url, None);
+        let request = self
+            .http_client
+            .post(&url)
+            .bearer_auth(token.expose_secret())
+            .multipart(form);
+        let response = self.send(request, summary).await?;
+
+        if response.status().is_success() {
+            decode_response(Method::POST, response).await
+        } else {
+            Err(error_from_response(Method::POST, response, Error::Api).await)
+        }
+    }
+
+    /// Get dispute scheme files
+    ///
+    /// Returns all of the scheme files of a dispute, e.g. the documents sent
+    /// by the card scheme at each stage of the dispute.
+    ///
+    /// [`GET /disputes/{id}/schemefiles`](https://api-reference.checkout.com/#operation/getDisputeSchemeFiles)
+    pub async fn get_dispute_scheme_files(
+        &self,
+        dispute_id: String,
+    ) -> Result<GetDisputeSchemeFilesResponse, Error> {
+        let url = format!("{}/disputes/{}/schemefiles", self.api_url(), dispute_id);
+        self.send_get_request(&url).await
+    }
+
+    /// Provide dispute evidence
+    ///
+    /// Adds supporting evidence to a dispute, including Visa Compelling
+    /// Evidence 3.0 prior-transaction evidence. The evidence is not sent to
+    /// the card scheme until it is submitted with
+    /// [`Client::submit_dispute_evidence`].
+    ///
+    /// [`PUT /disputes/{id}/evidence`](https://api-reference.checkout.com/#operation/provideDisputeEvidence)
+    pub async fn provide_dispute_evidence(
+        &self,
+        dispute_id: String,
+        body: &ProvideDisputeEvidenceBody,
+    ) -> Result<(), Error> {
+        let url = format!("{}/disputes/{}/evidence", self.api_url(), dispute_id);
+        self.send_put_request(&url, body).await
+    }
+
+    /// Submit dispute evidence
+    ///
+    /// Submits the evidence previously provided for a dispute to the card
+    /// scheme. Once submitted, the evidence can no longer be changed.
+    ///
+    /// [`POST /disputes/{id}/evidence`](https://api-reference.checkout.com/#operation/submitDisputeEvidence)
+    pub async fn submit_dispute_evidence(&self, dispute_id: String) -> Result<(), Error> {
+        let url = format!("{}/disputes/{}/evidence", self.api_url(), dispute_id);
+        let response = self
+            .send_post_request_2(&url, &types::requests::EmptyBody {})
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(error_from_response(Method::POST, response, Error::Api).await)
+        }
+    }
+}
+
+/// Decodes the JSON body of a successful response
+async fn decode_response<R>(method: Method, response: Response) -> Result<R, Error>
+where
+    R: DeserializeOwned,
+{
+    let endpoint = Endpoint::new(method, response.url());
+    let body = response.bytes().await?;
+    serde_json::from_slice(&body).map_err(|source| Error::Decode { endpoint, source })
+}
+
+/// Reads the body of an unsuccessful response as the [`ApiError`] it
+/// describes. Bodies that are not an [`ApiError`], such as HTML error pages of
+/// intermediaries or empty bodies, become an [`Error::Unknown`] instead of a
+/// misleading decoding error.
+async fn error_from_response(
+    method: Method,
+    response: Response,
+    error: fn(ApiError) -> Error,
+) -> Error {
+    let endpoint = Endpoint::new(method, response.url());
+    let status = response.status();
+    match response.bytes().await {
+        Ok(body) => error_from_body(status, &body, error, Some(endpoint)),
+        Err(err) => Error::Transport(err),
+    }
+}
+
+/// Returns the final response of a request. The caller only knows a
+/// generated idempotency key from the response, so a failure that leaves the
+/// outcome open carries the key instead.
+async fn confirm_outcome(
+    method: Method,
+    response: Result<Response, ReqwestError>,
+    generated_key: Option<String>,
+) -> Result<Response, Error> {
+    match (response, generated_key) {
+        (Ok(response), Some(key)) if response.status().is_server_error() => {
+            let error = error_from_response(method, response, Error::Api).await;
+            Err(Error::Unconfirmed {
+                idempotency_key: key,
+                source: Box::new(error),
+            })
+        }
+        (Err(err), Some(key)) => Err(Error::Unconfirmed {
+            idempotency_key: key,
+            source: Box::new(Error::Transport(err)),
+        }),
+        (response, _) => Ok(response?),
+    }
+}
+
+/// Returns whether a response with the status may succeed when the request
+/// is sent again: rate limiting and temporary failures of the API
+fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Returns the time to wait before sending a request again after `attempts`
+/// attempts, doubling with every attempt
+fn retry_backoff(attempts: u32) -> Duration {
+    RETRY_BACKOFF * 2_u32.pow(attempts.saturating_sub(1).min(6))
+}
+
+fn error_from_body(
+    status: StatusCode,
+    body: &[u8],
+    error: fn(ApiError) -> Error,
+    endpoint: Option<Endpoint>,
+) -> Error {
+    match serde_json::from_slice::<ApiError>(body) {
+        Ok(mut api_error) => {
+            api_error.endpoint = endpoint;
+            error(api_error)
+        }
+        Err(_) => Error::Unknown(status, String::from_utf8_lossy(body).into_owned()),
+    }
+}
+
+/// Checks that the amount of a capture or refund is in the payment's
+/// currency and does not exceed the `available` amount of the payment
+fn check_action_amount(available: Money, requested: Option<Money>) -> Result<(), Error> {
+    match requested {
+        Some(requested)
+            if requested.currency != available.currency
+                || requested.amount.minor_units() > available.amount.minor_units() =>
+        {
+            Err(Error::AmountMismatch {
+                payment: available,
+                requested,
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bigdecimal::BigDecimal;
+    use once_cell::sync::OnceCell;
+
+    use super::*;
+
+    fn client() -> &'static Client {
+        static INSTANCE: OnceCell<Client> = OnceCell::new();
+        INSTANCE.get_or_init(|| {
+            let dotenv_var = |key: &str| SecretString::new(dotenv::var(key).expect(key));
+            Client::new(
+                dotenv_var("CKO_USERNAME"),
+                dotenv_var("CKO_PASSWORD"),
+                Environment::Sandbox,
+            )
+        })
+    }
+
+    fn create_payment(
+        number: String,
+        month: u32,
+        year: u32,
+        cvv: Option<String>,
+        amount: BigDecimal,
+    ) -> CreatePaymentRequest {
+        // The Checkout sandbox uses certain card numbers, expiration dates,
+        // cvvs, and amounts to trigger failure cases.
+        //
+        // https://docs.checkout.com/testing
+
+        CreatePaymentRequest {
+            source: Some(PaymentRequestSource::Card {
+                number,
+                expiry_month: month,
+                expiry_year: year,
+                name: None,
+                cvv: cvv.map(Cvv::from),
+                stored: None,
+                billing_address: None,
+                phone: None,
+            }),
+            destination: None,
+            amount: PaymentAmount::Charge(Money::new(Currency::USD, amount).unwrap()),
+            payment_type: PaymentType::Regular,
+            merchant_initiated: false,
+            reference: None,
+            description: None,
+            capture: None,
+            capture_on: None,
+            customer: None,
+            billing_descriptor: None,
+            shipping: None,
+            three_ds: None,
+            previous_payment_id: None,
+            risk: None,
+            success_url: None,
+            failure_url: None,
+            payment_ip: None,
+            sender: None,
+            recipient: None,
+            processing_channel_id: None,
+            processing: None,
+            store: None,
+            items: None,
+            metadata: None,
+            extra: None,
+        }
+    }
+
+    /// A local server that answers each connection with the next of its
+    /// responses and records the requests it received, lowercased
+    struct MockServer {
+        url: String,
+        requests: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl MockServer {
+        fn start(responses: Vec<(u16, &'static str)>) -> MockServer {
+            use std::io::{Read, Write};
+
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+            let recorded = Arc::clone(&requests);
+            std::thread::spawn(move || {
+                for (status, body) in responses {
+                    let (mut stream, _) = listener.accept().unwrap();
+                    let mut request = Vec::new();
+                    let mut buffer = [0; 4096];
+                    loop {
+                        let read = stream.read(&mut buffer).unwrap();
+                        request.extend_from_slice(&buffer[..read]);
+                        let text = String::from_utf8_lossy(&request).to_lowercase();
+                        if let Some(end) = text.find("\r\n\r\n") {
+                            let length = text
+                                .lines()
+                                .find_map(|line| line.strip_prefix("content-length: "))
+                                .map_or(0, |length| length.trim().parse().unwrap());
+                            if read == 0 || request.len() >= end + 4 + length {
+                                break;
+                            }
+                        }
+                    }
+                    recorded
+                        .lock()
+                        .unwrap()
+                        .push(String::from_utf8_lossy(&request).to_lowercase());
+                    let content_type = if body.starts_with('{') || body.starts_with('[') {
+                        "application/json"
+                    } else {
+                        "text/csv"
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {status} Mock\r\ncontent-type: {content_type}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            });
+            MockServer { url, requests }
+        }
+
+        fn requests(&self) -> Vec<String> {
+            self.requests.lock().unwrap().clone()
+        }
+
+        /// Returns a client that sends its requests to the server, with
+        /// cached tokens for every product area
+        fn client(&self) -> Client {
+            let client = Client::new(
+                SecretString::new("ack_sandbox".to_string()),
+                SecretString::new("secret".to_string()),
+                Environment::Sandbox,
+            )
+            .with_api_hosts(ApiHosts::new(vec![self.url.clone()]));
+            let token: OAuthTokenResponse = serde_json::from_str(
+                r#"{"access_token":"eyJmock","expires_in":3600,"token_type":"Bearer","scope":"gateway"}"#,
+            )
+            .unwrap();
+            let areas = [
+                ProductArea::Gateway,
+                ProductArea::Platforms,
+                ProductArea::Issuing,
+                ProductArea::Sessions,
+                ProductArea::FinancialActions,
+            ];
+            for area in areas {
+                client.tokens.write().unwrap().insert(area, token.clone());
+            }
+            client
+        }
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_return_the_generated_idempotency_key() {
+        let unavailable =
+            r#"{"request_id":"req_1","error_type":"service_unavailable","error_codes":[]}"#;
+        let server = MockServer::start(vec![(503, unavailable), (503, unavailable)]);
+        let client = server
+            .client()
+            .with_request_options(RequestOptions::default().retries(1));
+        let request = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2030,
+            None,
+            BigDecimal::from(10),
+        );
+
+        let err = client.create_payment(&request).await.unwrap_err();
+        let key = err.idempotency_key().unwrap().to_string();
+        assert!(
+            matches!(&err, Error::Unconfirmed { source, .. } if matches!(**source, Error::Api(_)))
+        );
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        for request in requests {
+            assert!(request.contains(&format!("cko-idempotency-key: {}", key.to_lowercase())));
+        }
+
+        // Hosts that can't be reached leave the outcome open too
+        let unreachable = MockServer::start(Vec::new()).client();
+        let err = unreachable
+            .with_request_options(RequestOptions::default().retries(1))
+            .create_payment(&request)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::Unconfirmed { source, .. } if matches!(**source, Error::Transport(_)))
+        );
+
+        // Without retries nothing is generated
+        let server = MockServer::start(vec![(503, unavailable)]);
+        let err = server.client().create_payment(&request).await.unwrap_err();
+        assert_eq!(err.idempotency_key(), None);
+    }
+
+    #[tokio::test]
+    async fn options_are_sent_with_a_single_request() {
+        let server = MockServer::start(vec![
+            (202, r#"{"action_id":"act_1"}"#),
+            (202, r#"{"action_id":"act_2"}"#),
+        ]);
+        let client = server.client();
+        let body = VoidPaymentBody {
+            reference: None,
+            metadata: None,
+        };
+
+        let options = client.request_options().clone().idempotency_key("void-1");
+        client
+            .void_payment_with_options("pay_1".to_string(), &body, &options)
+            .await
+            .unwrap();
+        client
+            .void_payment("pay_1".to_string(), &body)
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert!(requests[0].contains("cko-idempotency-key: void-1"));
+        assert!(!requests[1].contains("cko-idempotency-key"));
+    }
+
+    #[test]
+    fn audit_masks_card_number() {
+        let payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2025,
+            Some("100".to_string()),
+            BigDecimal::try_from(20.00).unwrap(),
+        );
+
+        let summary = RequestSummary::new(Method::POST, "/payments", Some(&payment));
+
+        assert_eq!(summary.amount, Some(2000));
+        assert_eq!(summary.currency.as_deref(), Some("USD"));
+        assert_eq!(
+            summary.masked_source.as_deref(),
+            Some("card:424242******4242")
+        );
+    }
+
+    #[test]
+    fn payment_status_round_trips_through_strings() {
+        for status in &[
+            PaymentStatus::CardVerified,
+            PaymentStatus::PartiallyCaptured,
+            PaymentStatus::Refunded,
+        ] {
+            assert_eq!(status.to_string().parse::<PaymentStatus>(), Ok(*status));
+        }
+        assert_eq!(PaymentType::Moto.to_string(), "MOTO");
+        assert_eq!("Unscheduled".parse(), Ok(PaymentType::Unscheduled));
+        assert!(PaymentType::Installment.is_card_on_file());
+        assert!(!PaymentType::Moto.is_card_on_file());
+        assert!("Partially captured".parse::<PaymentStatus>().is_err());
+    }
+
+    #[test]
+    fn webhook_signature_verification() {
+        use hmac::{Hmac, Mac};
+
+        let secret = "whsec_test";
+        let body = br#"{"id":"evt_1","type":"payment_approved","created_on":"2022-04-01T12:00:00Z","data":{}}"#;
+        let mut mac = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let webhook_secret = WebhookSecret::new(SecretString::new(secret.to_string()));
+        assert_eq!(webhook_secret.sign(body), signature);
+        assert!(webhook_secret.verify(body, &signature));
+        assert!(!webhook_secret.verify(body, "not hex"));
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokens"));
+
+        let verifier = WebhookVerifier::new(SecretString::new(secret.to_string()));
+        let event = verifier
+            .verify::<serde_json::Value>(body, Some(&signature))
+            .unwrap();
+        assert_eq!(event.ty, "payment_approved");
+
+        assert!(matches!(
+            verifier.verify::<serde_json::Value>(b"{}", Some(&signature)),
+            Err(WebhookError::InvalidSignature)
+        ));
+        assert!(matches!(
+            verifier.verify::<serde_json::Value>(body, None),
+            Err(WebhookError::MissingSignature)
+        ));
+    }
+
+    #[test]
+    fn decline_reasons_are_normalized() {
+        assert_eq!(
+            DeclineReason::from_response("20051", Some("Insufficient Funds")),
+            Some(DeclineReason::InsufficientFunds)
+        );
+        assert_eq!(
+            DeclineReason::from_response("20099", Some("Declined - Stolen card")),
+            Some(DeclineReason::StolenCard)
+        );
+        assert_eq!(
+            DeclineReason::from_response("40101", Some("Risk Blocked Transaction")),
+            Some(DeclineReason::RiskBlocked)
+        );
+        assert_eq!(
+            DeclineReason::from_response("20099", None),
+            Some(DeclineReason::Other)
+        );
+        assert_eq!(
+            DeclineReason::from_response("10000", Some("Approved")),
+            None
+        );
+        assert_eq!(DeclineReason::DoNotHonor.to_string(), "do_not_honor");
+    }
+
+    #[test]
+    fn flagged_payments_require_review() {
+        let event: RiskWebhookEvent = serde_json::from_str(
+            r#"{
+                "id": "evt_1",
+                "type": "payment_flagged",
+                "created_on": "2022-04-01T12:00:00Z",
+                "data": {
+                    "id": "pay_123",
+                    "amount": 1000,
+                    "currency": "USD",
+                    "risk": { "flagged": true }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(event.risk_event_type(), Some(RiskEventType::PaymentFlagged));
+        assert_eq!(event.payment_id(), &PaymentId("pay_123".to_string()));
+        assert!(event.requires_review());
+        assert!(event.data.triggered_rules().is_empty());
+    }
+
+    #[test]
+    fn stable_hash_ignores_volatile_fields() {
+        let payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2025,
+            Some("100".to_string()),
+            BigDecimal::try_from(20.00).unwrap(),
+        );
+
+        let mut scheduled = payment.clone();
+        scheduled.capture_on = Some(chrono::Utc::now());
+        scheduled.source = Some(PaymentRequestSource::Card {
+            number: "4242424242424242".to_string(),
+            expiry_month: 6,
+            expiry_year: 2025,
+            name: None,
+            cvv: Some(Cvv::new("200")),
+            stored: None,
+            billing_address: None,
+            phone: None,
+        });
+        assert_eq!(payment.stable_hash(), scheduled.stable_hash());
+
+        let mut larger = payment.clone();
+        larger.amount = PaymentAmount::Charge(
+            Money::new(Currency::USD, BigDecimal::try_from(21.00).unwrap()).unwrap(),
+        );
+        assert_ne!(payment.stable_hash(), larger.stable_hash());
+
+        let shipped_to = |number: &str| {
+            let mut shipped = payment.clone();
+            shipped.shipping = Some(ShippingDescriptor {
+                address: None,
+                phone: Some(PhoneNumber {
+                    country_code: "+44".to_string(),
+                    number: number.to_string(),
+                }),
+            });
+            shipped.stable_hash()
+        };
+        assert_ne!(shipped_to("447700900123"), shipped_to("447700800123"));
+    }
+
+    #[test]
+    fn references_are_validated() {
+        assert!(Reference::new("order-1234/a_b.c").is_ok());
+        assert!(Reference::new("").is_err());
+        assert!(Reference::new("order 1234").is_err());
+        assert!(Reference::new("x".repeat(51)).is_err());
+
+        let reference = Reference::generate("order-").unwrap();
+        assert!(reference.as_str().starts_with("order-"));
+        assert_eq!(reference.as_str().len(), 32);
+        assert!(Reference::generate(&"x".repeat(25)).is_err());
+    }
+
+    #[test]
+    fn statement_preview_truncates_per_scheme() {
+        let descriptor = BillingDescriptor {
+            name: "Telcoin Marketplace Store Ltd".to_string(),
+            city: "San Francisco CA".to_string(),
+        };
+
+        assert_eq!(
+            descriptor.statement_preview(&CardScheme::Visa),
+            "TELCOIN MARKETPLACE STORESAN FRANCISCO"
+        );
+        assert_eq!(
+            descriptor.statement_preview(&CardScheme::Amex),
+            "TELCOIN MARKETPLACE SAN FRANCISCO"
+        );
+    }
+
+    #[test]
+    fn unknown_processed_sources_are_kept() {
+        let source: PaymentProcessedSource =
+            serde_json::from_str(r#"{"type":"qpay","description":"Order 1"}"#).unwrap();
+        match source {
+            PaymentProcessedSource::Other { ty, raw } => {
+                assert_eq!(ty, "qpay");
+                assert_eq!(raw["description"], "Order 1");
+            }
+            other => panic!("source is not other: {:?}", other),
+        }
+
+        let source: PaymentProcessedSource =
+            serde_json::from_str(r#"{"type":"ideal","bic":"INGBNL2A"}"#).unwrap();
+        assert!(matches!(source, PaymentProcessedSource::Ideal { .. }));
+    }
+
+    #[test]
+    fn capture_failures_are_classified() {
+        let error = |code: &str| {
+            Error::Api(ApiError::new(
+                "0HL80RJLS76I7".to_string(),
+                "request_invalid".to_string(),
+                vec![code.to_string()],
+            ))
+        };
+
+        assert_eq!(
+            CaptureFailure::of(&error("authorization_expired")),
+            Some(CaptureFailure::AuthorizationExpired)
+        );
+        assert_eq!(
+            CaptureFailure::of(&error("amount_exceeds_balance")),
+            Some(CaptureFailure::AmountExceeded)
+        );
+        assert_eq!(CaptureFailure::of(&error("reference_invalid")), None);
+        assert_eq!(CaptureFailure::of(&Error::TooManyRequests), None);
+    }
+
+    #[test]
+    fn error_bodies_that_are_not_json_are_unknown() {
+        let html = b"<html><body><h1>502 Bad Gateway</h1></body></html>";
+        match error_from_body(StatusCode::BAD_GATEWAY, html, Error::Api, None) {
+            Error::Unknown(status, body) => {
+                assert_eq!(status, StatusCode::BAD_GATEWAY);
+                assert!(body.contains("502 Bad Gateway"));
+            }
+            other => panic!("error is not unknown: {:?}", other),
+        }
+
+        assert!(matches!(
+            error_from_body(StatusCode::BAD_GATEWAY, b"", Error::Api, None),
+            Error::Unknown(StatusCode::BAD_GATEWAY, body) if body.is_empty()
+        ));
+
+        let json = br#"{"request_id":"0HL80RJLS76I7","error_type":"request_invalid","error_codes":["amount_invalid"]}"#;
+        assert!(matches!(
+            error_from_body(StatusCode::UNPROCESSABLE_ENTITY, json, Error::InvalidData, None),
+            Error::InvalidData(error) if error.error_codes == ["amount_invalid"]
+        ));
+    }
+
+    #[test]
+    fn errors_display_their_endpoint() {
+        let error = Error::Api(ApiError {
+            request_id: "0HL80RJLS76I7".to_string(),
+            error_type: "request_invalid".to_string(),
+            error_codes: vec!["amount_invalid".to_string()],
+            endpoint: Some(Endpoint {
+                method: Method::POST,
+                path: "/payments/pay_123/captures".to_string(),
+            }),
+        });
+
+        assert_eq!(
+            error.to_string(),
+            "API error: request_invalid (amount_invalid) from POST /payments/pay_123/captures \
+             [request 0HL80RJLS76I7]"
+        );
+        assert!(matches!(
+            &error,
+            Error::Api(api_error) if api_error.endpoint().unwrap().method == Method::POST
+        ));
+    }
+
+    #[test]
+    fn token_scopes_and_expiry_are_parsed() {
+        let token: OAuthTokenResponse = serde_json::from_str(
+            r#"{"access_token":"eyJ","expires_in":3600,"token_type":"Bearer","scope":"gateway disputes:view"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            token.scope,
+            [Scope::Gateway, Scope::Other("disputes:view".to_string())]
+        );
+        assert!(token.has_scope(&Scope::Gateway));
+        assert!(token.has_scope(&Scope::Other("gateway:payment".to_string())));
+        assert!(!token.has_scope(&Scope::Disputes));
+        assert!(!token.has_scope(&Scope::Other("gatewayx".to_string())));
+
+        let expires_in = token.expires_at() - Instant::now();
+        assert!(expires_in <= Duration::from_secs(3600));
+        assert!(expires_in > Duration::from_secs(3500));
+        assert!(token.expires_within(Duration::from_secs(3600)));
+        assert!(!token.expires_within(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn uploads_are_validated() {
+        let pdf = FileUpload::new("receipt.pdf", &b"%PDF-1.4"[..]);
+        assert_eq!(pdf.content_type(), Ok("application/pdf"));
+        assert_eq!(pdf.validate(), Ok(()));
+
+        let png = FileUpload::new("evidence", &b"\x89PNG\r\n"[..]);
+        assert_eq!(png.content_type(), Ok("image/png"));
+
+        assert_eq!(
+            FileUpload::new("receipt.pdf", &b"%PDF-1.4"[..])
+                .max_size(4)
+                .validate(),
+            Err(UploadError::TooLarge { size: 8, max: 4 })
+        );
+        assert!(matches!(
+            FileUpload::new("notes.txt", &b"notes"[..]).validate(),
+            Err(UploadError::UnsupportedType(_))
+        ));
+        assert_eq!(
+            FileUpload::new("empty.pdf", Vec::new()).validate(),
+            Err(UploadError::Empty)
+        );
+    }
+
+    #[test]
+    fn borrowed_requests_serialize_like_owned_ones() {
+        let number = "4242424242424242".to_string();
+        let metadata: Metadata = vec![("order".to_string(), "1234".to_string())]
+            .into_iter()
+            .collect();
+        let money = Money::new(Currency::USD, BigDecimal::try_from(20.00).unwrap()).unwrap();
+        let cvv = Cvv::new("987");
+
+        let request = PaymentRequestRef::new(
+            PaymentSourceRef::Card {
+                number: &number,
+                expiry_month: 6,
+                expiry_year: 2025,
+                name: None,
+                cvv: Some(&cvv),
+                stored: None,
+            },
+            money,
+        )
+        .reference("ORD-1234")
+        .metadata(&metadata);
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["source"]["type"], "card");
+        assert_eq!(json["source"]["number"], "4242424242424242");
+        assert_eq!(json["source"]["cvv"], "987");
+        assert!(!format!("{:?}", request).contains("987"));
+        assert_eq!(json["amount"], 2000);
+        assert_eq!(json["currency"], "USD");
+        assert_eq!(json["payment_type"], "Regular");
+        assert_eq!(json["reference"], "ORD-1234");
+        assert_eq!(json["metadata"]["order"], "1234");
+        assert!(json.get("description").is_none());
+    }
+
+    #[test]
+    fn secrets_are_redacted() {
+        let source = PaymentRequestSource::Id {
+            id: "src_123".to_string(),
+            cvv: Some(Cvv::new("100")),
+        };
+        assert!(!format!("{:?}", source).contains("100"));
+        assert_eq!(serde_json::to_value(&source).unwrap()["cvv"], "100");
+
+        let token: OAuthTokenResponse = serde_json::from_str(
+            r#"{"access_token":"eyJsecret","expires_in":3600,"token_type":"Bearer","scope":"gateway"}"#,
+        )
+        .unwrap();
+        assert!(!format!("{:?}", token).contains("eyJsecret"));
+    }
+
+    #[test]
+    fn recorded_bodies_are_redacted() {
+        let request = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2025,
+            Some("100".to_string()),
+            BigDecimal::try_from(20.00).unwrap(),
+        );
+
+        let body = recorder::redacted_body(&request).unwrap();
+        assert_eq!(body["source"]["number"], "424242******4242");
+        assert_eq!(body["source"]["cvv"], "[REDACTED]");
+        assert_eq!(body["amount"], 2000);
+
+        let recorder = Recorder::new(1, std::time::Duration::from_secs(60));
+        let summary = RequestSummary::new(Method::POST, "https://api.checkout.com/payments", None);
+        recorder.record(&summary, Some(body.clone()), None);
+        recorder.record(&summary, Some(body), None);
+        assert_eq!(recorder.exchanges().len(), 1);
+        assert!(recorder.dump_json().contains("[REDACTED]"));
+
+        let customer =
+            serde_json::json!({ "phone": { "country_code": "+44", "number": "2071234567" } });
+        let body = recorder::redacted_body(&customer).unwrap();
+        assert_eq!(body["phone"]["number"], "2071234567");
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_are_redacted() {
+        let server = MockServer::start(vec![(
+            201,
+            r#"{"id":"pay_1","approved":true,"status":"Authorized","response_code":"10000",
+                "source":{"type":"card","number":"4242424242424242","expiry_month":6,"expiry_year":2025}}"#,
+        )]);
+        let recorder = Recorder::new(10, std::time::Duration::from_secs(60));
+        let client = server.client().with_recorder(recorder.clone());
+
+        let response: serde_json::Value = client
+            .send_get_request(&format!("{}/payments/pay_1", client.api_url()))
+            .await
+            .unwrap();
+        assert_eq!(response["source"]["number"], "4242424242424242");
+
+        let exchanges = recorder.exchanges();
+        let recorded = exchanges[0].response_body.as_ref().unwrap();
+        assert_eq!(recorded["id"], "pay_1");
+        assert_eq!(recorded["source"]["number"], "424242******4242");
+    }
+
+    #[tokio::test]
+    async fn downloads_are_not_recorded() {
+        let server = MockServer::start(vec![(200, "id,amount\npay_1,1000\n")]);
+        let recorder = Recorder::new(10, std::time::Duration::from_secs(60));
+        let client = server.client().with_recorder(recorder.clone());
+
+        let download = client
+            .download_report_file("rpt_1".to_string(), "file_1".to_string())
+            .await
+            .unwrap();
+        assert_eq!(download.content_type(), Some("text/csv"));
+        let mut file = Vec::new();
+        assert_eq!(download.download_to(&mut file).await.unwrap(), 21);
+        assert_eq!(file, b"id,amount\npay_1,1000\n");
+
+        let exchanges = recorder.exchanges();
+        assert_eq!(exchanges[0].status, Some(200));
+        assert!(exchanges[0].response_body.is_none());
+    }
+
+    /// The currencies documented at
+    /// https://docs.checkout.com/resources/codes/currency-codes, as of
+    /// [`CURRENCY_LIST_VERSION`]
+    const DOCUMENTED_CURRENCIES: &[&str] = &[
+        "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+        "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD",
+        "CDF", "CHF", "CLF", "CLP", "CNY", "COP", "CRC", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD",
+        "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS", "GIP", "GMD", "GNF", "GTQ",
+        "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD", "JOD",
+        "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR",
+        "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR",
+        "MWK", "MXN", "MYR", "MZN", "NAD", "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN",
+        "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD", "RUB", "RWF", "SAR", "SBD", "SCR",
+        "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "STN", "SVC", "SYP", "SZL", "THB", "TJS",
+        "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS", "VES",
+        "VND", "VUV", "WST", "XAF", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWG",
+    ];
+
+    #[test]
+    #[allow(deprecated)]
+    fn currencies_match_the_documented_list() {
+        for code in DOCUMENTED_CURRENCIES {
+            let currency: Currency = serde_json::from_value(serde_json::json!(code))
+                .unwrap_or_else(|_| panic!("{} is not a currency", code));
+            assert!(currency.is_active(), "{} is not active", code);
+        }
+
+        for retired in &[Currency::EEK, Currency::MRO, Currency::STD, Currency::HRK] {
+            assert!(!retired.is_active());
+            let replacement = retired.replacement().unwrap();
+            assert!(DOCUMENTED_CURRENCIES.contains(&format!("{:?}", replacement).as_str()));
+        }
+    }
+
+    #[test]
+    fn afts_require_scheme_data() {
+        let source = PaymentRequestSource::Token {
+            token: "tok_123".to_string(),
+        };
+        let money = Money::new(Currency::GBP, BigDecimal::try_from(50.00).unwrap()).unwrap();
+        let sender = PayoutSender::Individual {
+            first_name: "Jane".to_string(),
+            last_name: "Doe".to_string(),
+            address: Some(Address {
+                address_line1: Some("1 Main Street".to_string()),
+                address_line2: None,
+                city: Some("London".to_string()),
+                state: None,
+                zip: Some("W1T 4TJ".to_string()),
+                country: Some("GB".to_string()),
+            }),
+            reference: None,
+        };
+        let recipient = PaymentRecipient {
+            dob: None,
+            account_number: Some("4242424242".to_string()),
+            zip: None,
+            first_name: Some("John".to_string()),
+            last_name: Some("Doe".to_string()),
+        };
+
+        let aft = AftRequest::new(
+            source,
+            money,
+            AftCategory::from_mcc(4829),
+            sender,
+            recipient,
+        );
+        assert_eq!(
+            aft.validate(),
+            Err(vec![AftValidationError::MissingPurpose])
+        );
+
+        let request = aft
+            .purpose("family_support")
+            .into_payment_request()
+            .unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["processing"]["aft"], true);
+        assert_eq!(json["processing"]["merchant_category_code"], "4829");
+        assert_eq!(json["sender"]["type"], "individual");
+        assert_eq!(json["recipient"]["account_number"], "4242424242");
+
+        let mut recipient = request.recipient.unwrap();
+        recipient.dob = Some("1985-05-15".to_string());
+        let institution = AftRequest::new(
+            PaymentRequestSource::Customer {
+                id: "cus_123".to_string(),
+            },
+            money,
+            AftCategory::FinancialInstitution,
+            PayoutSender::Corporate {
+                company_name: "Acme".to_string(),
+                address: None,
+                reference: None,
+            },
+            recipient,
+        );
+        assert_eq!(
+            institution.validate(),
+            Err(vec![
+                AftValidationError::MissingSenderAddress,
+                AftValidationError::MissingRecipientField("zip"),
+            ])
+        );
+    }
+
+    #[test]
+    fn prepaid_top_ups_are_sent_by_a_corporate_sender() {
+        let payout = CardPayoutRequest::prepaid_top_up(
+            PaymentRequestDestination::Card {
+                number: "4242424242424242".to_string(),
+                expiry_month: "6".to_string(),
+                expiry_year: "2025".to_string(),
+                first_name: "Jane".to_string(),
+                last_name: "Doe".to_string(),
+                name: None,
+                billing_address: None,
+                phone: None,
+            },
+            Money::new(Currency::EUR, BigDecimal::try_from(25.00).unwrap()).unwrap(),
+            Reference::new("topup-123").unwrap(),
+            "Acme Wallets Ltd",
+            Address {
+                address_line1: Some("1 Main Street".to_string()),
+                address_line2: None,
+                city: Some("Dublin".to_string()),
+                state: None,
+                zip: None,
+                country: Some("IE".to_string()),
+            },
+            "wallet-42",
+        );
+
+        let json = serde_json::to_value(&payout).unwrap();
+        assert_eq!(json["amount"], 2500);
+        assert_eq!(json["reference"], "topup-123");
+        assert_eq!(json["sender"]["type"], "corporate");
+        assert_eq!(json["sender"]["reference"], "wallet-42");
+        assert_eq!(json["instruction"]["funds_transfer_type"], "TU");
+    }
+
+    #[test]
+    fn actions_are_split_into_captures_and_refunds() {
+        let actions: GetPaymentActionsResponse = serde_json::from_value(serde_json::json!([
+            {
+                "id": "act_5", "type": "Refund", "processed_on": "2022-04-05T10:00:00Z",
+                "amount": 200, "response_code": "10000", "metadata": {}
+            },
+            {
+                "id": "act_4", "type": "Refund", "processed_on": "2022-04-04T10:00:00Z",
+                "amount": 500, "approved": false, "response_code": "20005", "metadata": {}
+            },
+            {
+                "id": "act_3", "type": "Refund", "processed_on": "2022-04-03T10:00:00Z",
+                "amount": 300, "approved": true, "response_code": "10000",
+                "reference": "refund-1", "metadata": {}
+            },
+            {
+                "id": "act_2", "type": "Capture", "processed_on": "2022-04-02T10:00:00Z",
+                "amount": 1000, "approved": true, "response_code": "10000",
+                "processing": { "acquirer_reference_number": "24021219099007452440793" },
+                "metadata": {}
+            },
+            {
+                "id": "act_1", "type": "Authorization", "processed_on": "2022-04-01T10:00:00Z",
+                "amount": 1000, "approved": true, "response_code": "10000", "metadata": {}
+            }
+        ]))
+        .unwrap();
+
+        let captures = actions.captures();
+        assert_eq!(captures.len(), 1);
+        assert_eq!(
+            captures[0].acquirer_reference_number.as_deref(),
+            Some("24021219099007452440793")
+        );
+
+        let refunds = actions.refunds();
+        assert_eq!(refunds.len(), 3);
+        assert!(!refunds[0].approved);
+        assert_eq!(refunds[2].reference.as_deref(), Some("refund-1"));
+
+        assert_eq!(actions.total_captured().minor_units(), 1000);
+        assert_eq!(actions.total_refunded().minor_units(), 300);
+    }
+
+    #[test]
+    fn presets_set_the_required_fields() {
+        let money = Money::new(Currency::GBP, BigDecimal::try_from(10.00).unwrap()).unwrap();
+        let card = || PaymentRequestSource::Token {
+            token: "tok_123".to_string(),
+        };
+
+        let one_click =
+            serde_json::to_value(CreatePaymentRequest::one_click_payment("src_123", money))
+                .unwrap();
+        assert_eq!(one_click["source"]["type"], "id");
+        assert_eq!(one_click["merchant_initiated"], false);
+
+        let moto = serde_json::to_value(CreatePaymentRequest::moto_payment(card(), money)).unwrap();
+        assert_eq!(moto["payment_type"], "MOTO");
+        assert_eq!(moto["3ds"]["enabled"], false);
+
+        let verification = CreatePaymentRequest::account_verification(card(), Currency::GBP);
+        assert!(verification.amount.is_verification());
+
+        let mit = CreatePaymentRequest::unscheduled_mit("src_123", money, "MCC123456");
+        assert_eq!(mit.payment_type, PaymentType::Unscheduled);
+        assert!(mit.merchant_initiated);
+        assert_eq!(mit.previous_payment_id.as_deref(), Some("MCC123456"));
+    }
+
+    #[test]
+    fn drift_is_detected_against_a_spec() {
+        let spec = serde_json::json!({
+            "info": { "version": "1.0.0" },
+            "paths": {
+                "/payments/{paymentId}/voids": {
+                    "post": {
+                        "requestBody": { "content": { "application/json": {
+                            "schema": { "$ref": "#/components/schemas/VoidRequest" }
+                        } } }
+                    }
+                },
+                "/workflows": { "get": {} }
+            },
+            "components": { "schemas": {
+                "VoidRequest": { "allOf": [
+                    { "properties": { "reference": {} } },
+                    { "properties": { "metadata": {}, "new_field": {} } }
+                ] }
+            } }
+        });
+
+        let report = drift::schema_drift(&spec);
+        assert_eq!(report.spec_version.as_deref(), Some("1.0.0"));
+        assert_eq!(report.missing_endpoints, ["GET /workflows"]);
+        assert!(report
+            .removed_endpoints
+            .contains(&"POST /payments".to_string()));
+        assert!(!report
+            .removed_endpoints
+            .contains(&"POST /payments/{id}/voids".to_string()));
+        assert_eq!(
+            report.missing_fields["POST /payments/{id}/voids"],
+            ["new_field"]
+        );
+        assert!(report.is_breaking());
+    }
+
+    #[tokio::test]
+    #[ignore] // fetches the spec from CKO_OPENAPI_SPEC_URL
+    async fn published_spec_has_not_drifted() {
+        let url = dotenv::var("CKO_OPENAPI_SPEC_URL").expect("CKO_OPENAPI_SPEC_URL");
+        let spec: serde_json::Value = reqwest::get(url).await.unwrap().json().await.unwrap();
+
+        let report = drift::schema_drift(&spec);
+        assert!(!report.is_breaking(), "{:#?}", report);
+    }
+
+    #[test]
+    fn health_requires_credentials_and_api() {
+        let report = HealthReport {
+            credentials: HealthCheck::new(Duration::from_millis(40), None),
+            api: Some(HealthCheck::new(Duration::from_millis(60), None)),
+        };
+        assert!(report.is_healthy());
+        assert_eq!(report.latency(), Duration::from_millis(100));
+
+        let report = HealthReport {
+            credentials: HealthCheck::new(Duration::from_millis(40), Some(Error::Unauthorized)),
+            api: None,
+        };
+        assert!(!report.is_healthy());
+        assert_eq!(report.latency(), Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_is_healthy() {
+        let report = client().health_check().await;
+        assert!(report.is_healthy(), "{:?}", report);
+    }
+
+    #[test]
+    fn customer_details_keep_the_full_payload() {
+        let customer: CustomerDetails = serde_json::from_value(serde_json::json!({
+            "id": "cus_123",
+            "email": "jane@example.com",
+            "name": "Jane Doe",
+            "phone": { "country_code": "+44", "number": "4155552671" },
+            "metadata": { "tier": "gold" },
+            "default": "src_2",
+            "instruments": [
+                { "id": "src_1", "type": "card", "last4": "4242" },
+                { "id": "src_2", "type": "card", "last4": "0005", "scheme": "Visa" }
+            ],
+            "created_on": "2022-04-01T10:00:00Z"
+        }))
+        .unwrap();
+
+        assert_eq!(customer.phone.as_ref().unwrap().number, "4155552671");
+        assert_eq!(customer.metadata.as_ref().unwrap()["tier"], "gold");
+        assert_eq!(
+            customer.default_instrument().unwrap().last4.as_deref(),
+            Some("0005")
+        );
+        assert_eq!(customer.extra["created_on"], "2022-04-01T10:00:00Z");
+    }
+
+    #[test]
+    fn co_badged_cards_are_routed_through_the_preferred_scheme() {
+        let mut payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2025,
+            None,
+            BigDecimal::try_from(20.00).unwrap(),
+        );
+        payment.prefer_scheme(PreferredScheme::CartesBancaires);
+        let json = serde_json::to_value(&payment).unwrap();
+        assert_eq!(json["processing"]["preferred_scheme"], "cartes_bancaires");
+        assert_eq!(json["processing"]["aft"], false);
+
+        let processed: PaymentProcessed = serde_json::from_value(serde_json::json!({
+            "id": "pay_123",
+            "action_id": "act_123",
+            "amount": 2000,
+            "currency": "EUR",
+            "approved": true,
+            "status": "Authorized",
+            "response_code": "10000",
+            "processed_on": "2022-04-01T10:00:00Z",
+            "processing": { "scheme": "cartes_bancaires" }
+        }))
+        .unwrap();
+        assert_eq!(
+            processed.processed_scheme(),
+            Some(&CardScheme::from(PreferredScheme::CartesBancaires))
+        );
+    }
+
+    #[test]
+    fn surcharges_follow_the_policy() {
+        let card = |card_type: &str, category: &str, country: &str| -> CardMetadataResponse {
+            serde_json::from_value(serde_json::json!({
+                "bin": "424242",
+                "scheme": "Visa",
+                "card_type": card_type,
+                "card_category": category,
+                "issuer_country": country
+            }))
+            .unwrap()
+        };
+        let policy = SurchargePolicy::new()
+            .prohibit_consumer_cards_from(EEA_AND_UK)
+            .max_rate(300)
+            .rule(
+                SurchargeRule::new(400)
+                    .card_type(CardType::Credit)
+                    .fixed(20),
+            )
+            .rule(SurchargeRule::new(100).card_category(CardCategory::Commercial));
+        let money = Money::new(Currency::USD, BigDecimal::try_from(100.00).unwrap()).unwrap();
+
+        let surcharge = policy
+            .surcharge(&card("CREDIT", "CONSUMER", "US"), money)
+            .unwrap();
+        assert_eq!(surcharge.amount.minor_units(), 320);
+        assert_eq!(surcharge.total.amount.minor_units(), 10_320);
+        let item = serde_json::to_value(&surcharge.item).unwrap();
+        assert_eq!(item["type"], "surcharge");
+        assert_eq!(item["unit_price"], 320);
+
+        assert!(policy
+            .surcharge(&card("CREDIT", "CONSUMER", "FR"), money)
+            .is_none());
+        assert!(policy
+            .surcharge(&card("DEBIT", "CONSUMER", "US"), money)
+            .is_none());
+        let commercial = policy
+            .surcharge(&card("DEBIT", "COMMERCIAL", "FR"), money)
+            .unwrap();
+        assert_eq!(commercial.amount.minor_units(), 100);
+    }
+
+    #[test]
+    fn dlocal_payments_are_validated_per_country() {
+        let brazil = DLocalProcessing::new("BR")
+            .payer_document("123.456.789-09")
+            .installments(3);
+        assert_eq!(brazil.validate(), Ok(()));
+        assert_eq!(
+            DLocalProcessing::new("BR").validate(),
+            Err(vec![DLocalValidationError::MissingDocument(
+                "BR".to_string()
+            )])
+        );
+        assert_eq!(
+            DLocalProcessing::new("UY")
+                .payer_document("1234567")
+                .installments(3)
+                .validate(),
+            Err(vec![DLocalValidationError::InstallmentsNotSupported(
+                "UY".to_string()
+            )])
+        );
+        assert_eq!(DLocalProcessing::new("mx").validate(), Ok(()));
+
+        let mut payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2025,
+            None,
+            BigDecimal::try_from(20.00).unwrap(),
+        );
+        assert_eq!(payment.validate_dlocal(), Ok(()));
+        payment.processing = Some(PaymentProcessingDescriptor {
+            dlocal: Some(brazil),
+            ..PaymentProcessingDescriptor::default()
+        });
+        assert_eq!(
+            payment.validate_dlocal(),
+            Err(vec![DLocalValidationError::MissingReference])
+        );
+    }
+
+    #[test]
+    fn sessions_carry_app_channel_data() {
+        let request = CreateSessionRequest {
+            source: PaymentRequestSource::Token {
+                token: "tok_ubfj2q76miwundwlk72vxt2i7q".to_string(),
+            },
+            amount: PaymentAmount::Charge(Money {
+                amount: Amount::from_minor_units(6540),
+                currency: Currency::GBP,
+            }),
+            reference: Some("ORD-5023-4E89".to_string()),
+            completion: Some(SessionCompletion::NonHosted { callback_url: None }),
+            channel_data: ChannelData::App {
+                sdk_app_id: "dbd64fcb-c19a-4728-8849-e3d50bfdde39".to_string(),
+                sdk_max_timeout: 5,
+                sdk_ephemeral_public_key: SdkEphemeralPublicKey {
+                    kty: "EC".to_string(),
+                    crv: "P-256".to_string(),
+                    x: "f83OJ3D2xF1Bg8vub9tLe1gHMzV76e8Tus9uPHvRVEU".to_string(),
+                    y: "x_FEzRu9m36HLN_tue659LNpXW6pCyStikYjKIWI5a0".to_string(),
+                },
+                sdk_reference_number: "3DS_LOA_SDK_PPFU_020100_00007".to_string(),
+                sdk_encrypted_data: "eyJhbGciOiJSU0EtT0FFUC0yNTYi".to_string(),
+                sdk_transaction_id: "b2385523-a66c-4907-ac3c-91848e8c0067".to_string(),
+                sdk_interface_type: Some(SdkInterfaceType::Both),
+                sdk_ui_elements: Some(vec![SdkUiElement::SingleSelect, SdkUiElement::HtmlOther]),
+            },
+            extra: None,
+        };
+
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["amount"], 6540);
+        assert_eq!(json["completion"]["type"], "non_hosted");
+        assert_eq!(json["channel_data"]["channel"], "app");
+        assert_eq!(json["channel_data"]["sdk_max_timeout"], 5);
+        assert_eq!(
+            json["channel_data"]["sdk_ephemeral_public_key"]["crv"],
+            "P-256"
+        );
+        assert_eq!(json["channel_data"]["sdk_interface_type"], "both");
+        assert_eq!(
+            json["channel_data"]["sdk_ui_elements"],
+            serde_json::json!(["single_select", "html_other"])
+        );
+
+        let session: Session = serde_json::from_str(
+            r#"{"id":"sid_y3oqhf46pyzuxjbcn2giaqnb44","session_secret":"sek_secret",
+            "status":"challenged","transaction_id":"30e4f3e4-1c8d-4a3d-8e35-5b7b5d4e3a2b",
+            "protocol_version":"2.2.0","next_actions":["challenge_cardholder"]}"#,
+        )
+        .unwrap();
+        assert_eq!(session.status, SessionStatus::Challenged);
+        assert_eq!(
+            session.next_actions,
+            vec!["challenge_cardholder".to_string()]
+        );
+        assert_eq!(
+            session.session_secret.unwrap().expose_secret(),
+            "sek_secret"
+        );
+        assert_eq!(
+            ProductArea::of_scope(&"sessions:app".parse().unwrap()),
+            ProductArea::Sessions
+        );
+    }
+
+    #[test]
+    fn payment_events_are_emitted_to_the_hook() {
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = events.clone();
+        let client = Client::new(
+            SecretString::new("ack_sandbox".to_string()),
+            SecretString::new("secret".to_string()),
+            Environment::Sandbox,
+        )
+        .with_event_hook(EventHook::new(move |event: &PaymentEvent| {
+            sink.lock().unwrap().push(event.clone());
+        }));
+
+        let processed: PaymentProcessed = serde_json::from_value(serde_json::json!({
+            "id": "pay_123",
+            "action_id": "act_123",
+            "amount": 2000,
+            "currency": "EUR",
+            "approved": true,
+            "status": "Authorized",
+            "response_code": "10000",
+            "processed_on": "2022-04-01T10:00:00Z",
+            "reference": "ORD-1"
+        }))
+        .unwrap();
+        let response = CreatePaymentResponse::Processed(processed);
+        client.emit(|| PaymentEvent::payment_created(&response));
+        let refund: RefundPaymentResponse =
+            serde_json::from_value(serde_json::json!({ "action_id": "act_456" })).unwrap();
+        client.emit(|| PaymentEvent::refund_requested("pay_123", Some(500), &refund));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|event| event.payment_id() == "pay_123"));
+        assert!(matches!(
+            &events[0],
+            PaymentEvent::PaymentCreated {
+                approved: Some(true),
+                amount: Some(2000),
+                currency: Some(Currency::EUR),
+                ..
+            }
+        ));
+        let json = serde_json::to_value(&events[1]).unwrap();
+        assert_eq!(json["type"], "RefundRequested");
+        assert_eq!(json["action_id"], "act_456");
+        assert_eq!(json["amount"], 500);
+    }
+
+    #[cfg(feature = "fixtures")]
+    #[test]
+    fn fixtures_roundtrip() {
+        fixtures::assert_roundtrip::<PaymentProcessed>(fixtures::PAYMENT_PROCESSED);
+        fixtures::assert_roundtrip::<PendingPayment>(fixtures::PENDING_PAYMENT);
+        fixtures::assert_roundtrip::<PaymentProcessed>(fixtures::PAYMENT_DECLINED);
+        fixtures::assert_roundtrip::<Action>(fixtures::PAYMENT_ACTION);
+        fixtures::assert_roundtrip::<GetPaymentActionsResponse>(fixtures::PAYMENT_ACTIONS);
+        fixtures::assert_roundtrip::<PendingPayment>(fixtures::PAYOUT);
+        fixtures::assert_roundtrip::<CaptureWebhookEvent>(fixtures::CAPTURE_WEBHOOK);
+        fixtures::assert_roundtrip::<DisputeSummary>(fixtures::DISPUTE);
+        assert_eq!(fixtures::FIXTURES.len(), 8);
+    }
+
+    #[cfg(feature = "rust_decimal")]
+    #[test]
+    fn amounts_convert_from_and_to_rust_decimal() {
+        use rust_decimal::Decimal;
+
+        let amount = Amount::from_rust_decimal(Currency::USD, Decimal::new(1050, 2)).unwrap();
+        assert_eq!(amount.minor_units(), 1050);
+        assert_eq!(amount.to_rust_decimal(Currency::USD), Decimal::new(1050, 2));
+
+        let dinars = Amount::from_rust_decimal(Currency::BHD, Decimal::new(1, 0)).unwrap();
+        assert_eq!(dinars.minor_units(), 1000);
+        let yen = Money::from_rust_decimal(Currency::JPY, Decimal::new(100, 0)).unwrap();
+        assert_eq!(yen.amount.minor_units(), 100);
+        assert_eq!(yen.to_rust_decimal(), Decimal::new(100, 0));
+
+        assert!(matches!(
+            Amount::from_rust_decimal(Currency::USD, Decimal::new(10999, 3)),
+            Err(AmountError::Precision(_))
+        ));
+        assert!(matches!(
+            Amount::from_rust_decimal(Currency::USD, Decimal::new(-1, 0)),
+            Err(AmountError::OutOfRange(_))
+        ));
+        let rounded = Amount::from_rust_decimal_rounded(
+            Currency::USD,
+            Decimal::new(10995, 3),
+            RoundingPolicy::RoundHalfUp,
+        )
+        .unwrap();
+        assert_eq!(rounded.minor_units(), 1100);
+    }
+
+    #[test]
+    fn sca_outcome_compares_requested_and_applied_exemptions() {
+        let processed = |applied: &str| -> PaymentProcessed {
+            serde_json::from_value(serde_json::json!({
+                "id": "pay_123",
+                "action_id": "act_123",
+                "amount": 2000,
+                "currency": "EUR",
+                "approved": true,
+                "status": "Authorized",
+                "response_code": "10000",
+                "processed_on": "2022-04-01T10:00:00Z",
+                "3ds": {
+                    "downgraded": false,
+                    "enrolled": "Y",
+                    "exemption": "low_value",
+                    "exemption_applied": applied,
+                    "challenged": false,
+                    "flow_type": "frictionless"
+                }
+            }))
+            .unwrap()
+        };
+
+        let honored = processed("low_value").sca_outcome().unwrap();
+        assert_eq!(honored.requested_exemption, Some(ScaExemption::LowValue));
+        assert_eq!(honored.exemption_honored(), Some(true));
+        assert_eq!(honored.flow_type, Some(_3dsFlowType::Frictionless));
+
+        let substituted = processed("transaction_risk_assessment")
+            .sca_outcome()
+            .unwrap();
+        assert_eq!(
+            substituted.applied_exemption,
+            Some(ScaExemption::TransactionRiskAssessment)
+        );
+        assert_eq!(substituted.exemption_honored(), Some(false));
+        assert!(substituted.is_exempted());
+
+        let rejected = processed("none").sca_outcome().unwrap();
+        assert_eq!(rejected.applied_exemption, None);
+        assert_eq!(rejected.exemption_honored(), Some(false));
+
+        let unknown = processed("new_exemption").sca_outcome().unwrap();
+        assert_eq!(unknown.applied_exemption, Some(ScaExemption::Other));
+        assert_eq!(
+            "3ds_outage".parse::<ScaExemption>(),
+            Ok(ScaExemption::ThreeDsOutage)
+        );
+    }
+
+    #[test]
+    fn card_categories_may_be_missing_or_unknown() {
+        let card = serde_json::json!({
+            "type": "card",
+            "expiry_month": 6,
+            "expiry_year": 2030,
+            "last4": "4242",
+            "fingerprint": "F31828E2BDABAE63EB694903825CDD36",
+            "bin": "424242"
+        });
+        let destination: PaymentProcessedDestination =
+            serde_json::from_value(card.clone()).unwrap();
+        assert!(matches!(
+            destination,
+            PaymentProcessedDestination::Card {
+                card_category: None,
+                ..
+            }
+        ));
+
+        let mut card = card;
+        card["card_category"] = "ALL".into();
+        let source: PaymentProcessedSource = serde_json::from_value(card).unwrap();
+        match source {
+            PaymentProcessedSource::Card { card_category, .. } => {
+                assert_eq!(card_category, Some(CardCategory::Other("ALL".to_string())));
+            }
+            other => panic!("unexpected source {:?}", other),
+        }
+
+        assert_eq!(
+            serde_json::from_str::<CardCategory>("\"Commercial\"").unwrap(),
+            CardCategory::Commercial
+        );
+        assert_eq!(
+            serde_json::to_string(&CardCategory::Consumer).unwrap(),
+            "\"CONSUMER\""
+        );
+    }
+
+    #[test]
+    fn payment_details_accessors_default_missing_fields() {
+        let details = |extra: serde_json::Value| -> PaymentDetails {
+            let mut json = serde_json::json!({
+                "id": "pay_123",
+                "requested_on": "2022-04-01T10:00:00Z",
+                "amount": 2000,
+                "currency": "EUR",
+                "payment_type": "Regular",
+                "approved": true,
+                "status": "Captured"
+            });
+            json.as_object_mut()
+                .unwrap()
+                .extend(extra.as_object().unwrap().clone());
+            serde_json::from_value(json).unwrap()
+        };
+
+        let bare = details(serde_json::json!({}));
+        assert!(bare.metadata().is_empty());
+        assert_eq!(bare.udf(1), None);
+        assert_eq!(bare.recipient_account_last4(), None);
+        assert_eq!(bare.recipient_name(), None);
+
+        let full = details(serde_json::json!({
+            "metadata": { "udf1": "loyal", "udf6": "ignored", "order": "ORD-1" },
+            "recipient": {
+                "account_number": "5555554444",
+                "first_name": "Jia",
+                "last_name": "Tsang"
+            }
+        }));
+        assert_eq!(full.metadata().len(), 3);
+        assert_eq!(full.metadata_value("order"), Some("ORD-1"));
+        assert_eq!(full.udf(1), Some("loyal"));
+        assert_eq!(full.udf(6), None);
+        assert_eq!(full.recipient_account_last4(), Some("4444"));
+        assert_eq!(full.recipient_name().as_deref(), Some("Jia Tsang"));
+    }
+
+    #[test]
+    fn pending_payments_describe_the_required_redirect() {
+        let pending =
+            |json: serde_json::Value| -> PendingPayment { serde_json::from_value(json).unwrap() };
+        let redirect = serde_json::json!({
+            "redirect": { "href": "https://api.checkout.com/3ds/pay_123" }
+        });
+
+        let three_ds = pending(serde_json::json!({
+            "id": "pay_123",
+            "status": "Pending",
+            "3ds": { "downgraded": false, "enrolled": "Y" },
+            "_links": redirect
+        }));
+        assert!(three_ds.requires_redirect());
+        assert_eq!(
+            three_ds.redirect_url().unwrap().as_str(),
+            "https://api.checkout.com/3ds/pay_123"
+        );
+        assert_eq!(three_ds.pending_reason(), PendingReason::ThreeDsRedirect);
+
+        let apm = pending(serde_json::json!({
+            "id": "pay_123",
+            "status": "Pending",
+            "_links": redirect
+        }));
+        assert_eq!(apm.pending_reason(), PendingReason::ApmRedirect);
+
+        let processing = pending(serde_json::json!({
+            "id": "pay_123",
+            "status": "Pending",
+            "_links": { "redirect": { "href": "not a url" } }
+        }));
+        assert!(!processing.requires_redirect());
+        assert_eq!(processing.pending_reason(), PendingReason::AsyncProcessing);
+    }
+
+    #[test]
+    fn payments_are_routed_by_rules_and_weights() {
+        let channel = |id: &str| ProcessingChannelId::new(id).unwrap();
+        let router = ChannelRouter::new()
+            .channel(ProcessingChannel::new(channel("pc_euprimary"), 3).currency(Currency::EUR))
+            .channel(ProcessingChannel::new(channel("pc_eusecondary"), 1).currency(Currency::EUR))
+            .channel(
+                ProcessingChannel::new(channel("pc_amex"), 1)
+                    .scheme(CardScheme::Amex)
+                    .bin_country("US"),
+            );
+
+        let eur = RoutingContext::new(Currency::EUR);
+        let selected: Vec<&str> = (0..8)
+            .map(|_| router.select(&eur).unwrap().id().as_str())
+            .collect();
+        assert_eq!(
+            selected.iter().filter(|id| **id == "pc_euprimary").count(),
+            6
+        );
+        assert_eq!(
+            selected
+                .iter()
+                .filter(|id| **id == "pc_eusecondary")
+                .count(),
+            2
+        );
+        assert_eq!(
+            selected[..4],
+            [
+                "pc_euprimary",
+                "pc_euprimary",
+                "pc_eusecondary",
+                "pc_euprimary"
+            ]
+        );
+
+        let amex = RoutingContext {
+            currency: Currency::USD,
+            scheme: Some(CardScheme::Amex),
+            bin_country: Some("us".to_string()),
+        };
+        assert_eq!(router.select(&amex).unwrap().id().as_str(), "pc_amex");
+        assert!(router.select(&RoutingContext::new(Currency::USD)).is_none());
+
+        router.record(&channel("pc_amex"));
+        assert_eq!(router.usage()[&channel("pc_amex")], 1);
+
+        let mut payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2030,
+            None,
+            BigDecimal::from(10),
+        );
+        payment.processing_channel_id = Some(channel("pc_euprimary"));
+        let json = serde_json::to_value(&payment).unwrap();
+        assert_eq!(json["processing_channel_id"], "pc_euprimary");
+    }
+
+    #[test]
+    fn only_temporary_failures_are_retried() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!is_retryable_status(StatusCode::UNPROCESSABLE_ENTITY));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::CREATED));
+
+        assert_eq!(retry_backoff(1), Duration::from_millis(200));
+        assert_eq!(retry_backoff(3), Duration::from_millis(800));
+        assert_eq!(retry_backoff(40), retry_backoff(7));
+
+        let options = RequestOptions::default().retries(2);
+        assert_eq!(options.retries, 2);
+        assert_eq!(RequestOptions::default().retries, 0);
+    }
+
+    #[test]
+    fn only_the_rejected_token_is_invalidated() {
+        let client = Client::new(
+            SecretString::new("ack_sandbox".to_string()),
+            SecretString::new("secret".to_string()),
+            Environment::Sandbox,
+        );
+        let token: OAuthTokenResponse = serde_json::from_str(
+            r#"{"access_token":"eyJnew","expires_in":3600,"token_type":"Bearer","scope":"gateway"}"#,
+        )
+        .unwrap();
+        client
+            .tokens
+            .write()
+            .unwrap()
+            .insert(ProductArea::Gateway, token);
+
+        // Already replaced by a concurrent request
+        client.invalidate_token(
+            ProductArea::Gateway,
+            &HeaderValue::from_static("Bearer eyJold"),
+        );
+        assert!(client
+            .tokens
+            .read()
+            .unwrap()
+            .contains_key(&ProductArea::Gateway));
+
+        client.invalidate_token(
+            ProductArea::Gateway,
+            &HeaderValue::from_static("Bearer eyJnew"),
+        );
+        assert!(!client
+            .tokens
+            .read()
+            .unwrap()
+            .contains_key(&ProductArea::Gateway));
+    }
+
+    #[test]
+    fn webhook_simulator_is_sandbox_only() {
+        let client = Client::new(
+            SecretString::new("ack_live".to_string()),
+            SecretString::new("secret".to_string()),
+            Environment::Production,
+        );
+        assert!(matches!(
+            sandbox::WebhookSimulator::new(&client),
+            Err(Error::SandboxOnly)
+        ));
+
+        let client = client.with_environment(Environment::Sandbox);
+        assert!(sandbox::WebhookSimulator::new(&client).is_ok());
+    }
+
+    #[test]
+    fn ids_are_validated_when_parsed() {
+        let channel: ProcessingChannelId = "pc_4kfr6rgdr3bu3m6bqq4kdglnru".parse().unwrap();
+        assert_eq!(channel.as_str(), "pc_4kfr6rgdr3bu3m6bqq4kdglnru");
+        assert!(EntityId::new("ent_yurpn7vzz3nuvhoi3svjcwbwyq").is_ok());
+
+        let error = EntityId::new("pc_4kfr6rgdr3bu3m6bqq4kdglnru").unwrap_err();
+        assert_eq!(error.prefix, "ent_");
+        assert!("ent_".parse::<SubEntityId>().is_err());
+        assert!("ent_ab-c".parse::<SubEntityId>().is_err());
+
+        let config: Result<HashMap<String, ProcessingChannelId>, _> =
+            serde_json::from_str(r#"{"eu": "ent_yurpn7vzz3nuvhoi3svjcwbwyq"}"#);
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn domain_types_are_reexported_flat() {
+        fn same_type<T>(_: &T, _: &T) {}
+
+        let status: payments::PaymentStatus = PaymentStatus::Authorized;
+        same_type(&status, &prelude::PaymentStatus::Authorized);
+
+        let channel = platforms::ProcessingChannelId::new("pc_abc").unwrap();
+        same_type(&channel, &ProcessingChannelId::new("pc_def").unwrap());
+
+        let dispute_status: disputes::DisputeStatus = DisputeStatus::Won;
+        same_type(&dispute_status, &DisputeStatus::Lost);
+        same_type(
+            &webhooks::RiskEventType::PaymentFlagged,
+            &RiskEventType::PaymentDeclined,
+        );
+
+        let account_type: payouts::BankAccountType = BankAccountType::Savings;
+        same_type(&account_type, &types::BankAccountType::Current);
+        let filter: payments::ActionFilter = ActionFilter::default();
+        same_type(&filter, &types::ActionFilter::default());
+    }
+
+    #[test]
+    fn deprecation_headers_are_parsed() {
+        use reqwest::header::{HeaderMap, LINK};
+
+        let url = reqwest::Url::parse("https://api.checkout.com/payments?limit=1").unwrap();
+        let mut headers = HeaderMap::new();
+        assert!(DeprecationNotice::from_headers(Method::GET, &url, &headers).is_none());
+
+        headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("@1688169599"));
+        headers.insert(
+            SUNSET_HEADER,
+            HeaderValue::from_static("Sun, 31 Dec 2023 23:59:59 GMT"),
+        );
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.checkout.com/payments?skip=1>; rel="next", <https://docs.checkout.com/deprecations>; rel="deprecation""#,
+            ),
+        );
+        headers.insert(CKO_VERSION_HEADER, HeaderValue::from_static("2023-01-01"));
+
+        let notice = DeprecationNotice::from_headers(Method::GET, &url, &headers).unwrap();
+        assert_eq!(notice.endpoint.path, "/payments");
+        assert!(notice.deprecated);
+        assert_eq!(notice.deprecated_at.unwrap().timestamp(), 1_688_169_599);
+        assert_eq!(notice.sunset_at.unwrap().timestamp(), 1_704_067_199);
+        assert!(notice.is_sunset());
+        assert_eq!(
+            notice.link.as_deref(),
+            Some("https://docs.checkout.com/deprecations")
+        );
+        assert_eq!(notice.api_version.as_deref(), Some("2023-01-01"));
+
+        headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+        let notice = DeprecationNotice::from_headers(Method::GET, &url, &headers).unwrap();
+        assert!(notice.deprecated);
+        assert!(notice.deprecated_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn payment_actions_are_filtered_after_fetching() {
+        let server = MockServer::start(vec![(
+            200,
+            r#"[{"id":"act_2","type":"Refund","processed_on":"2022-04-02T00:00:00Z","amount":500,"approved":true,"response_code":"10000","metadata":{}},
+                {"id":"act_1","type":"Capture","processed_on":"2022-04-01T00:00:00Z","amount":2000,"approved":true,"response_code":"10000","metadata":{}}]"#,
+        )]);
+        let client = server.client();
+
+        let filter = ActionFilter::default().action_type(ActionType::Capture);
+        let actions = client
+            .get_payment_actions_filtered("pay_123".to_string(), &filter)
+            .await
+            .unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id(), "act_1");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pages_follow_next_links() {
+        let server = MockServer::start(vec![
+            (
+                200,
+                r#"{"count":1,"limit":1,"data":[{"id":"rpt_2","created_on":"2022-04-02T00:00:00Z","type":"PayoutStatement"}]}"#,
+            ),
+            (
+                200,
+                r#"{"count":1,"limit":1,"data":[{"id":"rpt_2","created_on":"2022-04-02T00:00:00Z","type":"PayoutStatement"}]}"#,
+            ),
+        ]);
+        let client = server.client();
+        let first_page = || -> GetReportsResponse {
+            serde_json::from_value(serde_json::json!({
+                "count": 1,
+                "limit": 1,
+                "data": [{"id": "rpt_1", "created_on": "2022-04-01T00:00:00Z", "type": "PayoutStatement"}],
+                "_links": {"next": {"href": format!("{}/reports?pagination_token=abc", server.url)}}
+            }))
+            .unwrap()
+        };
+
+        let next = client.next_page(&first_page()).await.unwrap().unwrap();
+        assert_eq!(next.data[0].id, "rpt_2");
+        assert!(client.next_page(&next).await.unwrap().is_none());
+
+        let mut pages = client.pages(first_page());
+        let mut ids = Vec::new();
+        while let Some(page) = std::future::poll_fn(|cx| {
+            futures_core::Stream::poll_next(std::pin::Pin::new(&mut pages), cx)
+        })
+        .await
+        {
+            ids.extend(
+                page.unwrap()
+                    .into_items()
+                    .into_iter()
+                    .map(|report| report.id),
+            );
+        }
+        assert_eq!(ids, ["rpt_1", "rpt_2"]);
+
+        let requests = server.requests();
+        assert!(requests[0].starts_with("get /reports?pagination_token=abc"));
+        assert!(requests[1].starts_with("get /reports?pagination_token=abc"));
+    }
+
+    #[test]
+    fn payout_statements_are_paginated() {
+        let page: GetPayoutStatementsResponse = serde_json::from_value(serde_json::json!({
+            "count": 1,
+            "data": [{
+                "id": "190110B107654",
+                "period_start": "2019-01-01T00:00:00.000Z",
+                "period_end": "2019-01-10T23:59:59.999Z",
+                "date": "2019-01-10T00:00:00.000Z",
+                "payouts": [{
+                    "id": "pay_190110B107654",
+                    "status": "Paid",
+                    "currency": "USD",
+                    "carried_forward_amount": 0,
+                    "current_period_amount": 1000.5,
+                    "net_amount": 1000.5,
+                    "date": "2019-01-17T00:00:00.000Z"
+                }]
+            }],
+            "_links": {"next": {"href": "https://api.checkout.com/reporting/statements?pagination_token=abc"}}
+        }))
+        .unwrap();
+        assert!(page.next_page_url().is_some());
+        let statements = page.into_items();
+        assert_eq!(
+            statements[0].payouts[0].net_amount,
+            Some(BigDecimal::from_str("1000.5").unwrap())
+        );
+    }
+
+    #[test]
+    fn financial_actions_break_down_fees() {
+        let page: GetFinancialActionsResponse = serde_json::from_str(
+            r#"{
+                "count": 1,
+                "limit": 5,
+                "data": [{
+                    "payment_id": "pay_qqufbhe5jslmnk3lbxbqe4lx4a",
+                    "action_id": "act_wsnyzbzmr2huxcekoj7qqhxwuy",
+                    "action_type": "CAPTURE",
+                    "entity_id": "ent_yurpn7vzz3nuvhoi3svjcwbwyq",
+                    "processing_channel_id": "pc_4kfr6rgdr3bu3m6bqq4kdglnru",
+                    "processed_on": "2022-02-18T13:00:12.357Z",
+                    "breakdown": [
+                        {"breakdown_type": "Gross Sales", "holding_currency": "GBP", "holding_currency_amount": 100},
+                        {"breakdown_type": "Scheme Fixed Fee", "holding_currency": "GBP", "holding_currency_amount": -0.02},
+                        {"breakdown_type": "Interchange Variable Fee", "holding_currency": "GBP", "holding_currency_amount": -0.3},
+                        {"breakdown_type": "Interchange Fixed Fee", "holding_currency": "GBP", "holding_currency_amount": -0.05},
+                        {"breakdown_type": "Premium Variable Fee", "holding_currency": "GBP", "holding_currency_amount": -0.25}
+                    ]
+                }],
+                "_links": {"next": {"href": "https://api.checkout.com/financial-actions?pagination_token=abc"}}
+            }"#,
+        )
+        .unwrap();
+        assert!(page.next_page_url().is_some());
+
+        let action = &page.data[0];
+        assert_eq!(action.holding_currency(), Some(Currency::GBP));
+        assert_eq!(
+            action.holding_currency_total(FeeCategory::Interchange),
+            BigDecimal::from_str("-0.35").unwrap()
+        );
+        assert_eq!(
+            action.holding_currency_total(FeeCategory::SchemeFee),
+            BigDecimal::from_str("-0.02").unwrap()
+        );
+        assert_eq!(
+            action.holding_currency_total(FeeCategory::Commission),
+            BigDecimal::from_str("-0.25").unwrap()
+        );
+        assert_eq!(
+            action.holding_currency_total(FeeCategory::Gross),
+            BigDecimal::from(100)
+        );
+
+        let filter =
+            FinancialActionFilter::payment(PaymentId("pay_qqufbhe5jslmnk3lbxbqe4lx4a".to_string()))
+                .limit(5);
+        assert_eq!(
+            serde_urlencoded::to_string(&filter).unwrap(),
+            "payment_id=pay_qqufbhe5jslmnk3lbxbqe4lx4a&limit=5"
+        );
+    }
+
+    #[test]
+    fn udfs_are_sent_in_the_metadata() {
+        let mut payment = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2030,
+            None,
+            BigDecimal::from(10),
+        );
+        let mut metadata = Metadata::new();
+        metadata.insert("coupon_code".to_string(), "NY2018".to_string());
+        metadata.insert("udf2".to_string(), "previous".to_string());
+        payment.metadata = Some(metadata);
+
+        payment.set_udfs(&UserDefinedFields {
+            udf1: Some("high-value".to_string()),
+            udf2: Some("web".to_string()),
+            ..UserDefinedFields::default()
+        });
+        let json = serde_json::to_value(&payment).unwrap();
+        assert_eq!(json["metadata"]["udf1"], "high-value");
+        assert_eq!(json["metadata"]["udf2"], "web");
+        assert_eq!(json["metadata"]["coupon_code"], "NY2018");
+        assert!(json["metadata"].get("udf3").is_none());
+        assert_eq!(payment.udfs().udf1.as_deref(), Some("high-value"));
+
+        payment.set_udfs(&UserDefinedFields {
+            udf1: Some("high-value".to_string()),
+            ..UserDefinedFields::default()
+        });
+        let metadata = payment.metadata.as_ref().unwrap();
+        assert!(metadata.get("udf2").is_none());
+        assert_eq!(metadata["coupon_code"], "NY2018");
+
+        payment.metadata = None;
+        payment.set_udfs(&UserDefinedFields::default());
+        assert!(payment.metadata.is_none());
+    }
+
+    #[test]
+    fn authorization_handles_track_captures() {
+        let processed: PaymentProcessed = serde_json::from_value(serde_json::json!({
+            "id": "pay_123",
+            "action_id": "act_123",
+            "amount": 2000,
+            "currency": "EUR",
+            "approved": true,
+            "status": "Authorized",
+            "response_code": "10000",
+            "processed_on": "2022-04-01T10:00:00Z",
+            "expires_on": "2022-04-08T10:00:00Z"
+        }))
+        .unwrap();
+        let handle = AuthorizationHandle::new(&processed).unwrap();
+
+        // Handles survive being stored and loaded
+        let stored = serde_json::to_string(&handle).unwrap();
+        let mut handle: AuthorizationHandle = serde_json::from_str(&stored).unwrap();
+        assert_eq!(handle.remaining_amount(), 2000);
+
+        let before_expiry = "2022-04-05T10:00:00Z".parse().unwrap();
+        assert_eq!(handle.check_capture(Some(500), before_expiry), Ok(500));
+        assert_eq!(
+            handle.check_capture(Some(2500), before_expiry),
+            Err(CaptureFailure::AmountExceeded)
+        );
+        assert_eq!(
+            handle.check_capture(None, "2022-04-09T10:00:00Z".parse().unwrap()),
+            Err(CaptureFailure::AuthorizationExpired)
+        );
+
+        let first_key = handle.capture_key();
+        handle.record_capture(500, Some(CaptureType::NonFinal));
+        assert_ne!(handle.capture_key(), first_key);
+        assert_eq!(handle.remaining_amount(), 1500);
+        assert_eq!(handle.check_capture(None, before_expiry), Ok(1500));
+
+        handle.record_capture(1000, Some(CaptureType::Final));
+        assert_eq!(handle.captured_amount(), 1500);
+        assert!(handle.is_closed());
+        assert!(CaptureFailure::of(&Error::InvalidCapture(
+            handle.check_capture(None, before_expiry).unwrap_err()
+        ))
+        .is_some());
+
+        let mut declined = processed;
+        declined.approved = false;
+        assert!(AuthorizationHandle::new(&declined).is_none());
     }
 
-    /// Get payment actions
-    ///
-    /// Returns all the actions associated with a payment ordered by processing
-    /// date in descending order (latest first).
-    ///
-    /// [`GET /payments/{id}/actions`](https://api-reference.checkout.com/#operation/getPaymentActions)
-    pub async fn get_payment_actions(
-        &self,
-        payment_id: String,
-    ) -> Result<GetPaymentActionsResponse, Error> {
-        let url = format!(
-            "{}/payments/{}/actions",
-            self.environment.api_url(),
-            payment_id
+    #[test]
+    fn capture_notifications_match_child_actions() {
+        let capture: CapturePaymentResponse = serde_json::from_value(serde_json::json!({
+            "action_id": "act_123",
+            "reference": "SHIP-1",
+            "child_action_ids": ["act_456"],
+            "_links": {
+                "payment": { "href": "https://api.checkout.com/payments/pay_123" }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            capture.payment_url(),
+            Some("https://api.checkout.com/payments/pay_123")
         );
-        self.send_get_request(&url).await
+
+        let notification = |action_id: &str| -> CaptureEventData {
+            serde_json::from_value(serde_json::json!({
+                "id": "pay_123",
+                "action_id": action_id,
+                "amount": 500,
+                "currency": "EUR",
+                "capture_type": "NonFinal"
+            }))
+            .unwrap()
+        };
+        assert!(notification("act_123").is_for(&capture));
+        assert!(notification("act_456").is_for(&capture));
+        assert!(!notification("act_789").is_for(&capture));
+
+        let single: CapturePaymentResponse =
+            serde_json::from_value(serde_json::json!({ "action_id": "act_123" })).unwrap();
+        assert!(single.child_action_ids.is_empty());
+        assert!(single.payment_url().is_none());
     }
 
-    /// Capture a payment
-    ///
-    /// Captures a payment if supported by the payment method.
-    ///
-    /// For card payments, capture requests are processed asynchronously. You
-    /// can use webhooks to be notified if the capture is successful.
-    ///
-    /// [`POST /payments/{id}/captures`](https://api-reference.checkout.com/#operation/captureAPayment)
-    pub async fn capture_payment(
-        &self,
-        payment_id: String,
-        body: &CapturePaymentBody,
-    ) -> Result<CapturePaymentResponse, Error> {
-        let url = format!(
-            "{}/payments/{}/captures",
-            self.environment.api_url(),
-            payment_id
-        );
-        self.send_post_request(&url, &body).await
+    #[test]
+    fn action_amounts_are_money_of_the_payment() {
+        let eur = |amount| Money {
+            amount: Amount::from_minor_units(amount),
+            currency: Currency::EUR,
+        };
+        let body = RefundPaymentBody {
+            amount: Some(eur(1050)),
+            reference: None,
+            metadata: None,
+            extra: None,
+        };
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["amount"], 1050);
+        assert!(json.get("currency").is_none());
+
+        assert!(check_action_amount(eur(2000), None).is_ok());
+        assert!(check_action_amount(eur(2000), Some(eur(2000))).is_ok());
+        assert!(matches!(
+            check_action_amount(eur(2000), Some(eur(200_000))),
+            Err(Error::AmountMismatch { .. })
+        ));
+        let usd = Money {
+            amount: Amount::from_minor_units(1050),
+            currency: Currency::USD,
+        };
+        assert!(matches!(
+            check_action_amount(eur(2000), Some(usd)),
+            Err(Error::AmountMismatch { .. })
+        ));
     }
 
-    /// Refund a payment
-    ///
-    /// Refunds a payment if supported by the payment method.
-    ///
-    /// For card payments, refund requests are processed asynchronously. You
-    /// can use webhooks to be notified if the refund is successful.
-    ///
-    /// [`POST /payments/{id}/refunds`](https://api-reference.checkout.com/#operation/refundAPayment)
-    pub async fn refund_payment(
-        &self,
-        payment_id: String,
-        body: &RefundPaymentBody,
-    ) -> Result<RefundPaymentResponse, Error> {
-        let url = format!(
-            "{}/payments/{}/refunds",
-            self.environment.api_url(),
-            payment_id
+    #[tokio::test]
+    async fn failed_remaining_refunds_return_the_refunds_made() {
+        let server = MockServer::start(vec![
+            (
+                200,
+                r#"{"id":"pay_123","requested_on":"2022-04-01T10:00:00Z","amount":1000,"currency":"EUR",
+                    "payment_type":"Regular","approved":true,"status":"Captured",
+                    "balances":{"total_authorized":1000,"total_voided":0,"available_to_void":0,
+                        "total_captured":1000,"available_to_capture":0,"total_refunded":0,"available_to_refund":1000}}"#,
+            ),
+            (202, r#"{"action_id":"act_1"}"#),
+            (
+                422,
+                r#"{"request_id":"req_1","error_type":"processing_error","error_codes":["refund_amount_exceeds_balance"]}"#,
+            ),
+        ]);
+
+        let result = server
+            .client()
+            .refund_remaining("pay_123".to_string(), Some(500), None)
+            .await;
+        match result {
+            Err(Error::PartiallyRefunded { refunds, source }) => {
+                assert_eq!(refunds.len(), 1);
+                assert_eq!(refunds[0].action_id, "act_1");
+                assert!(matches!(*source, Error::Api(_)));
+            }
+            other => panic!("refunds were not partial: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn checked_refunds_exclude_earlier_refunds() {
+        let payment = r#"{
+            "id": "pay_123",
+            "requested_on": "2022-04-01T10:00:00Z",
+            "amount": 2000,
+            "currency": "EUR",
+            "payment_type": "Regular",
+            "approved": true,
+            "status": "Partially Refunded",
+            "balances": {
+                "total_authorized": 2000,
+                "total_voided": 0,
+                "available_to_void": 0,
+                "total_captured": 2000,
+                "available_to_capture": 0,
+                "total_refunded": 1500,
+                "available_to_refund": 500
+            }
+        }"#;
+        let server = MockServer::start(vec![
+            (200, payment),
+            (200, payment),
+            (202, r#"{"action_id":"act_1"}"#),
+        ]);
+        let client = server.client();
+        let refund = |amount| RefundPaymentBody {
+            amount: Some(Money {
+                amount: Amount::from_minor_units(amount),
+                currency: Currency::EUR,
+            }),
+            reference: None,
+            metadata: None,
+            extra: None,
+        };
+
+        let result = client
+            .refund_payment_checked("pay_123".to_string(), &refund(1000))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::AmountMismatch { payment, .. }) if payment.amount.minor_units() == 500
+        ));
+        client
+            .refund_payment_checked("pay_123".to_string(), &refund(500))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].starts_with("post /payments/pay_123/refunds"));
+    }
+
+    #[tokio::test]
+    async fn checked_captures_exclude_earlier_captures() {
+        let payment = r#"{
+            "id": "pay_123",
+            "requested_on": "2022-04-01T10:00:00Z",
+            "amount": 2000,
+            "currency": "EUR",
+            "payment_type": "Regular",
+            "approved": true,
+            "status": "Partially Captured"
+        }"#;
+        let actions = r#"[
+            {"id":"act_2","type":"Capture","processed_on":"2022-04-02T10:00:00Z","amount":1500,"approved":true,"response_code":"10000","metadata":{}},
+            {"id":"act_1","type":"Authorization","processed_on":"2022-04-01T10:00:00Z","amount":2000,"approved":true,"response_code":"10000","metadata":{}}
+        ]"#;
+        let server = MockServer::start(vec![
+            (200, payment),
+            (200, actions),
+            (200, payment),
+            (200, actions),
+            (202, r#"{"action_id":"act_3"}"#),
+        ]);
+        let client = server.client();
+        let capture = |amount| CapturePaymentBody {
+            amount: Some(Money {
+                amount: Amount::from_minor_units(amount),
+                currency: Currency::EUR,
+            }),
+            capture_type: None,
+            reference: None,
+            metadata: None,
+            extra: None,
+        };
+
+        let result = client
+            .capture_payment_checked("pay_123".to_string(), &capture(1000))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::AmountMismatch { payment, .. }) if payment.amount.minor_units() == 500
+        ));
+        client
+            .capture_payment_checked("pay_123".to_string(), &capture(500))
+            .await
+            .unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 5);
+        assert!(requests[4].starts_with("post /payments/pay_123/captures"));
+    }
+
+    #[test]
+    fn decline_kinds_follow_response_codes() {
+        assert_eq!(DeclineKind::from_response_code("20051"), DeclineKind::Soft);
+        assert_eq!(DeclineKind::from_response_code("30004"), DeclineKind::Hard);
+        assert_eq!(DeclineKind::from_response_code("40101"), DeclineKind::Risk);
+        assert_eq!(DeclineKind::from_response_code("10000"), DeclineKind::Other);
+        assert_eq!(DeclineKind::from_response_code("2"), DeclineKind::Other);
+        assert_eq!(
+            DeclineKind::from_response_code("300041"),
+            DeclineKind::Other
         );
-        self.send_post_request(&url, &body).await
     }
 
-    /// Void a payment
-    ///
-    /// Voids a payment if supported by the payment method.
-    ///
-    /// For card payments, void requests are processed asynchronously. You can
-    /// use webhooks to be notified if the void is successful.
-    ///
-    /// [`POST /payments/{id}/voids`](https://api-reference.checkout.com/#operation/voidAPayment)
-    pub async fn void_payment(
-        &self,
-        payment_id: String,
-        body: &VoidPaymentBody,
-    ) -> Result<VoidPaymentResponse, Error> {
-        let url = format!(
-            "{}/payments/{}/voids",
-            self.environment.api_url(),
-            payment_id
+    #[test]
+    fn declined_recurring_payments_are_scheduled_for_retry() {
+        let declined = |response_code: &str, advice: Option<&str>| -> PaymentProcessed {
+            serde_json::from_value(serde_json::json!({
+                "id": "pay_123",
+                "action_id": "act_123",
+                "amount": 2000,
+                "currency": "EUR",
+                "approved": false,
+                "status": "Declined",
+                "response_code": response_code,
+                "processed_on": "2022-04-01T10:00:00Z",
+                "processing": { "merchant_advice_code": advice }
+            }))
+            .unwrap()
+        };
+
+        let later = declined("20051", Some("24"));
+        assert_eq!(
+            later.merchant_advice(),
+            Some(MerchantAdviceCode::RetryAfterOneHour)
+        );
+        let schedule = later.retry_advice().unwrap().schedule().cloned().unwrap();
+        assert_eq!(schedule.delays()[0], chrono::Duration::hours(1));
+        let declined_at = "2022-04-01T10:00:00Z".parse().unwrap();
+        assert_eq!(
+            schedule.next_attempt(declined_at, 1),
+            Some("2022-04-02T10:00:00Z".parse().unwrap())
+        );
+        assert_eq!(
+            schedule.next_attempt(declined_at, schedule.delays().len()),
+            None
+        );
+
+        assert_eq!(
+            declined("20051", Some("21")).retry_advice(),
+            Some(RetryAdvice::DoNotRetry)
+        );
+        assert_eq!(
+            declined("20051", Some("01")).retry_advice(),
+            Some(RetryAdvice::UpdatePaymentDetails)
         );
-        self.send_post_request(&url, &body).await
+        assert_eq!(
+            declined("20051", None).retry_advice(),
+            Some(RetryAdvice::Retry(RetrySchedule::standard()))
+        );
+        assert_eq!(
+            declined("20054", None).retry_advice(),
+            Some(RetryAdvice::UpdatePaymentDetails)
+        );
+        assert_eq!(
+            declined("30043", None).retry_advice(),
+            Some(RetryAdvice::DoNotRetry)
+        );
+
+        let error = CreatePaymentResponse::Processed(declined("20051", Some("03")))
+            .ensure_approved()
+            .unwrap_err();
+        assert_eq!(
+            error.merchant_advice(),
+            Some(&MerchantAdviceCode::DoNotTryAgain)
+        );
+        assert_eq!(error.retry_advice(), RetryAdvice::DoNotRetry);
+
+        let days = |days: &[i64]| days.iter().map(|d| chrono::Duration::days(*d)).collect();
+        let capped = RetrySchedule::new(days(&[0, 2, 1, 45, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]));
+        assert_eq!(capped.delays().len(), MAX_RETRY_ATTEMPTS);
+        assert_eq!(capped.delays()[0], chrono::Duration::days(1));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use bigdecimal::BigDecimal;
-    use once_cell::sync::OnceCell;
+    #[tokio::test]
+    async fn unreachable_hosts_fail_over_to_the_next_host() {
+        let down = MockServer::start(Vec::new());
+        let server = MockServer::start(vec![(202, r#"{"action_id":"act_1"}"#)]);
+        let hosts = ApiHosts::new(vec![down.url.clone(), server.url.clone()])
+            .with_primary_retry_interval(Duration::from_secs(3600));
+        let client = server.client().with_api_hosts(hosts.clone());
+        let body = VoidPaymentBody {
+            reference: None,
+            metadata: None,
+        };
 
-    use super::*;
+        client
+            .void_payment("pay_1".to_string(), &body)
+            .await
+            .unwrap();
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(hosts.active(), server.url);
 
-    fn client() -> &'static Client {
-        static INSTANCE: OnceCell<Client> = OnceCell::new();
-        INSTANCE.get_or_init(|| {
-            let dotenv_var = |key: &str| SecretString::new(dotenv::var(key).expect(key));
-            Client::new(
-                dotenv_var("CKO_USERNAME"),
-                dotenv_var("CKO_PASSWORD"),
-                Environment::Sandbox,
-            )
-        })
+        let hosts = hosts.with_primary_retry_interval(Duration::ZERO);
+        assert_eq!(hosts.active(), down.url);
+        assert!(hosts.is_primary_active());
     }
 
-    fn create_payment(
-        number: String,
-        month: u32,
-        year: u32,
-        cvv: Option<String>,
-        amount: BigDecimal,
-    ) -> CreatePaymentRequest {
-        // The Checkout sandbox uses certain card numbers, expiration dates,
-        // cvvs, and amounts to trigger failure cases.
-        //
-        // https://docs.checkout.com/testing
+    #[tokio::test]
+    async fn redirects_do_not_use_up_retries() {
+        let unavailable =
+            r#"{"request_id":"req_1","error_type":"service_unavailable","error_codes":[]}"#;
+        let down = MockServer::start(Vec::new());
+        let server = MockServer::start(vec![(503, unavailable), (200, r#"{"id":"pay_1"}"#)]);
+        let hosts = ApiHosts::new(vec![down.url.clone(), server.url.clone()])
+            .with_primary_retry_interval(Duration::from_secs(3600));
+        let client = server
+            .client()
+            .with_api_hosts(hosts)
+            .with_request_options(RequestOptions::default().retries(1));
 
-        CreatePaymentRequest {
-            source: Some(PaymentRequestSource::Card {
-                number,
-                expiry_month: month,
-                expiry_year: year,
-                name: None,
-                cvv,
-                stored: None,
-                billing_address: None,
-                phone: None,
-            }),
-            destination: None,
-            amount: Some(Amount::from(Currency::USD, amount)),
-            currency: Currency::USD,
-            payment_type: PaymentType::Regular,
-            merchant_initiated: false,
+        let response = client
+            .send_get_request_measured::<serde_json::Value>(&format!("{}/payments/pay_1", down.url))
+            .await
+            .unwrap();
+        assert_eq!(response.body["id"], "pay_1");
+        assert_eq!(response.attempts, 2);
+        assert_eq!(response.redirects, 1);
+        assert!(response.total_latency >= response.latency + retry_backoff(1));
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens_are_replaced_on_the_active_host() {
+        let down = MockServer::start(Vec::new());
+        let server = MockServer::start(vec![(401, "{}"), (200, r#"{"id":"pay_1"}"#)]);
+        let hosts = ApiHosts::new(vec![down.url.clone(), server.url.clone()])
+            .with_primary_retry_interval(Duration::from_secs(3600));
+        let client = server.client().with_api_hosts(hosts);
+
+        let url = format!("{}/payments/pay_1", down.url);
+        let request = client.http_client.get(&url).bearer_auth("eyJrevoked");
+        let summary = RequestSummary::new(Method::GET, &url, None);
+        let response = client
+            .send_measured(request, summary, None, &RequestOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].contains("authorization: bearer eyjrevoked"));
+        assert!(requests[1].contains("authorization: bearer eyjmock"));
+    }
+
+    #[tokio::test]
+    async fn truncated_error_bodies_do_not_abort_host_checks() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let truncated = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.read(&mut [0; 4096]).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 503 Mock\r\ncontent-length: 100\r\n\r\nBad")
+                .unwrap();
+        });
+        let server = MockServer::start(vec![(200, "{}")]);
+        let hosts = ApiHosts::new(vec![truncated, server.url.clone()])
+            .with_primary_retry_interval(Duration::from_secs(3600));
+        let client = server.client().with_api_hosts(hosts.clone());
+
+        assert_eq!(client.check_hosts().await.unwrap(), server.url);
+        assert_eq!(hosts.active(), server.url);
+    }
+
+    #[test]
+    fn registry_resolves_clients_by_key() {
+        let client = Client::new(
+            SecretString::new("ack_sandbox".to_string()),
+            SecretString::new("secret".to_string()),
+            Environment::Sandbox,
+        );
+        let registry = ClientRegistry::new().with_client("uk-entity", client);
+
+        assert!(registry.client("uk-entity").is_ok());
+        assert!(matches!(
+            registry.client("us-entity"),
+            Err(Error::UnknownClient(key)) if key == "us-entity"
+        ));
+    }
+
+    #[tokio::test]
+    async fn registry_keeps_the_http_clients_of_its_clients() {
+        let http_client = |pool: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert("x-pool", HeaderValue::from_str(pool).unwrap());
+            ReqwestClient::builder()
+                .default_headers(headers)
+                .build()
+                .unwrap()
+        };
+        let server = MockServer::start(vec![
+            (202, r#"{"action_id":"act_1"}"#),
+            (202, r#"{"action_id":"act_2"}"#),
+        ]);
+        let registry = ClientRegistry::with_http_client(http_client("registry"))
+            .with_client("shared", server.client())
+            .with_client(
+                "proxied",
+                server.client().with_http_client(http_client("own")),
+            );
+        let body = VoidPaymentBody {
             reference: None,
-            description: None,
-            capture: None,
-            capture_on: None,
-            customer: None,
-            billing_descriptor: None,
-            shipping: None,
-            three_ds: None,
-            previous_payment_id: None,
-            risk: None,
-            success_url: None,
-            failure_url: None,
-            payment_ip: None,
-            recipient: None,
-            processing: None,
             metadata: None,
+        };
+
+        for key in &["shared", "proxied"] {
+            let client = registry.client(key).unwrap();
+            client
+                .void_payment("pay_1".to_string(), &body)
+                .await
+                .unwrap();
         }
+
+        let requests = server.requests();
+        assert!(requests[0].contains("x-pool: registry"));
+        assert!(requests[1].contains("x-pool: own"));
+    }
+
+    #[tokio::test]
+    async fn cancellable_prefers_ready_response() {
+        let cancelled = cancel::Cancellable::new(std::future::pending::<()>(), async {}).await;
+        assert!(cancelled.is_none());
+
+        let completed = cancel::Cancellable::new(async { 42 }, async {}).await;
+        assert_eq!(completed, Some(42));
+
+        let request = create_payment(
+            "4242424242424242".to_string(),
+            6,
+            2030,
+            None,
+            BigDecimal::from(10),
+        );
+        let client = MockServer::start(Vec::new()).client();
+        let err = client
+            .create_payment_with_cancellation(&request, async {})
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, Error::Cancelled(Some(_))));
+
+        let options = RequestOptions::default().idempotency_key("order-1");
+        let err = client
+            .with_request_options(options)
+            .create_payment_with_cancellation(&request, async {})
+            .await
+            .unwrap_err();
+        assert_eq!(err.idempotency_key(), Some("order-1"));
+    }
+
+    #[test]
+    fn scopes_are_warmed_up_per_product_area() {
+        assert_eq!(ProductArea::of_scope(&Scope::Gateway), ProductArea::Gateway);
+        assert_eq!(
+            ProductArea::of_scope(&Scope::Disputes),
+            ProductArea::Gateway
+        );
+        assert_eq!(
+            ProductArea::of_scope(&Scope::Accounts),
+            ProductArea::Platforms
+        );
+        assert_eq!(
+            ProductArea::of_scope(&"issuing:card-mgmt".parse().unwrap()),
+            ProductArea::Issuing
+        );
+    }
+
+    #[test]
+    fn product_area_of_url() {
+        let api = Environment::Sandbox.api_url();
+        assert_eq!(
+            ProductArea::of_url(&format!("{}/payments/pay_123", api)),
+            ProductArea::Gateway
+        );
+        assert_eq!(
+            ProductArea::of_url(&format!("{}/accounts/entities", api)),
+            ProductArea::Platforms
+        );
+        assert_eq!(
+            ProductArea::of_url(&format!("{}/issuing/cards", api)),
+            ProductArea::Issuing
+        );
+        assert_eq!(
+            ProductArea::of_url(&format!("{}/sessions", api)),
+            ProductArea::Sessions
+        );
+        assert_eq!(
+            ProductArea::of_url(&format!("{}/financial-actions?payment_id=pay_123", api)),
+            ProductArea::FinancialActions
+        );
+        assert_eq!(
+            ProductArea::of_scope(&"financial-actions".parse().unwrap()),
+            ProductArea::FinancialActions
+        );
+        assert_eq!(ProductArea::FinancialActions.scope(), "financial-actions");
+    }
+
+    #[test]
+    fn amount_conversion_respects_rounding_policy() {
+        let value: BigDecimal = "10.995".parse().unwrap();
+
+        assert!(matches!(
+            Amount::from(Currency::USD, value.clone()),
+            Err(AmountError::Precision(_))
+        ));
+        assert_eq!(
+            Amount::from_rounded(Currency::USD, value.clone(), RoundingPolicy::Truncate)
+                .unwrap()
+                .minor_units(),
+            1099
+        );
+        assert_eq!(
+            Amount::from_rounded(Currency::USD, value, RoundingPolicy::RoundHalfUp)
+                .unwrap()
+                .minor_units(),
+            1100
+        );
+        assert_eq!(
+            Amount::from(Currency::KWD, "1.5".parse().unwrap())
+                .unwrap()
+                .minor_units(),
+            1500
+        );
+        assert!(matches!(
+            Amount::from(Currency::USD, "-1".parse().unwrap()),
+            Err(AmountError::OutOfRange(_))
+        ));
+
+        let amount = Amount::from_minor_units(1234);
+        assert_eq!(amount.into(Currency::JPY), "1234".parse().unwrap());
+        assert_eq!(amount.into(Currency::USD), "12.34".parse().unwrap());
+        assert_eq!(amount.into(Currency::BHD), "1.234".parse().unwrap());
     }
 
     #[tokio::test]
@@ -495,37 +5202,75 @@ mod tests {
         };
     }
 
-    #[tokio::test]
-    #[ignore] // response code is 10000 (Approved) even with XXX05 as the amount
-    async fn payout_request_declined() {
-        let payment = create_payment(
-            "4242424242424242".to_string(),
-            6,
-            2025,
-            None,
-            BigDecimal::try_from(123.05).unwrap(),
-        );
+    /// Pays the trigger's amount with the test card in the sandbox and checks
+    /// that the payment is declined with the trigger's response code
+    async fn assert_triggers(trigger: sandbox::ResponseCodeTrigger) {
+        let mut payment = create_payment(String::new(), 6, 2030, None, BigDecimal::from(0));
+        payment.source = Some(sandbox::test_card());
+        payment.amount = PaymentAmount::Charge(Money {
+            amount: trigger.amount(),
+            currency: Currency::USD,
+        });
         let payment: &'static _ = Box::leak(Box::new(payment));
 
-        let response = client().create_payment(payment).await;
+        let response = client().create_payment(payment).await.unwrap();
+        match response.ensure_approved() {
+            Err(decline) => assert_eq!(decline.response_code, trigger.response_code()),
+            Ok(response) => panic!("payment was not declined: {:?}", response),
+        }
+    }
 
-        assert!(matches!(response, Ok(_)));
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_do_not_honour() {
+        assert_triggers(sandbox::ResponseCodeTrigger::DoNotHonour).await;
     }
 
-    #[ignore] // response code is 10000 (Approved) even with XXX12 as the amount
     #[tokio::test]
-    async fn payout_request_invalid() {
-        let payment = create_payment(
-            "4242424242424242".to_string(),
-            6,
-            2025,
-            Some("100".to_string()),
-            BigDecimal::try_from(123.12).unwrap(),
-        );
-        let payment: &'static _ = Box::leak(Box::new(payment));
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_invalid_transaction() {
+        assert_triggers(sandbox::ResponseCodeTrigger::InvalidTransaction).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_invalid_card_number() {
+        assert_triggers(sandbox::ResponseCodeTrigger::InvalidCardNumber).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_insufficient_funds() {
+        assert_triggers(sandbox::ResponseCodeTrigger::InsufficientFunds).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_expired_card() {
+        assert_triggers(sandbox::ResponseCodeTrigger::ExpiredCard).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_restricted_card() {
+        assert_triggers(sandbox::ResponseCodeTrigger::RestrictedCard).await;
+    }
+
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_bad_track_data() {
+        assert_triggers(sandbox::ResponseCodeTrigger::BadTrackData).await;
+    }
 
-        let response = client().create_payment(payment).await;
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_pick_up_card() {
+        assert_triggers(sandbox::ResponseCodeTrigger::PickUpCard).await;
+    }
 
-        assert!(matches!(response, Ok(_)));
+    #[tokio::test]
+    #[ignore] // run against the sandbox with `cargo test -- --ignored`
+    async fn sandbox_triggers_lost_card() {
+        assert_triggers(sandbox::ResponseCodeTrigger::LostCard).await;
     }
 }
@@ -0,0 +1,204 @@
+//! Options applied to the requests sent by a client
+
+use std::time::Duration;
+
+use reqwest::{Client as ReqwestClient, Error as ReqwestError, RequestBuilder};
+
+use crate::{RequestSummary, CKO_IDEMPOTENCY_KEY_HEADER};
+
+/// Additional headers, an idempotency key and a timeout to send with requests
+///
+/// Options set with [`Client::with_request_options`](crate::Client::with_request_options)
+/// apply to every request of the client. To send them with a single request,
+/// e.g. an idempotency key, pass them to the `*_with_options` variant of the
+/// call. They are sent instead of the client's options:
+///
+/// ```ignore
+/// let options = client.request_options().clone().idempotency_key(order_id);
+/// client.create_payment_with_options(&request, &options).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    /// Additional headers to send, e.g. to enable feature previews
+    pub headers: Vec<(String, String)>,
+
+    /// A key that makes retries of the same request safe. Checkout returns
+    /// the original response for requests repeated with the same key
+    pub idempotency_key: Option<String>,
+
+    /// The maximum time to wait for a response
+    pub timeout: Option<Duration>,
+
+    /// How many times to send a request again after a connection error, a
+    /// timeout, rate limiting or a 5xx response (default: 0). Retried `POST`
+    /// requests without an idempotency key get a generated key that is sent
+    /// with every attempt, so a retried payment is never processed twice. The
+    /// key is returned in [`Error::Unconfirmed`](crate::Error::Unconfirmed)
+    /// if the retries are exhausted.
+    pub retries: u32,
+}
+
+impl RequestOptions {
+    /// Adds a header to send with requests
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> RequestOptions {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sets the idempotency key to send with requests
+    #[must_use]
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> RequestOptions {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Sets the maximum time to wait for a response
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> RequestOptions {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how many times to send a failed request again
+    #[must_use]
+    pub fn retries(mut self, retries: u32) -> RequestOptions {
+        self.retries = retries;
+        self
+    }
+
+    pub(crate) fn apply(
+        &self,
+        mut request: RequestBuilder,
+        summary: &mut RequestSummary,
+    ) -> RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some(key) = &self.idempotency_key {
+            request = request.header(CKO_IDEMPOTENCY_KEY_HEADER, key.as_str());
+            summary.idempotency_key = Some(key.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            request = request.timeout(timeout);
+        }
+        request
+    }
+}
+
+/// Connection pool and keep-alive settings of the HTTP client of a
+/// [`Client`](crate::Client)
+///
+/// Unset options keep reqwest's defaults. Reusing connections avoids the TLS
+/// handshakes of new connections during traffic spikes.
+///
+/// With the `gzip` and `brotli` features, compressed responses are accepted
+/// and decompressed transparently, which cuts the transfer time of large
+/// report files.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionOptions {
+    /// The maximum number of idle connections kept per host
+    pub pool_max_idle_per_host: Option<usize>,
+
+    /// How long idle connections are kept in the pool
+    pub pool_idle_timeout: Option<Duration>,
+
+    /// The interval of TCP keep-alive probes
+    pub tcp_keepalive: Option<Duration>,
+
+    /// The interval of HTTP/2 keep-alive pings
+    pub http2_keep_alive_interval: Option<Duration>,
+
+    /// Whether to use HTTP/2 adaptive flow control
+    pub http2_adaptive_window: bool,
+
+    #[cfg(feature = "gzip")]
+    gzip: Option<bool>,
+
+    #[cfg(feature = "brotli")]
+    brotli: Option<bool>,
+}
+
+impl ConnectionOptions {
+    /// Sets the maximum number of idle connections kept per host
+    #[must_use]
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> ConnectionOptions {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool
+    #[must_use]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> ConnectionOptions {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the interval of TCP keep-alive probes
+    #[must_use]
+    pub fn tcp_keepalive(mut self, interval: Duration) -> ConnectionOptions {
+        self.tcp_keepalive = Some(interval);
+        self
+    }
+
+    /// Sets the interval of HTTP/2 keep-alive pings
+    #[must_use]
+    pub fn http2_keep_alive_interval(mut self, interval: Duration) -> ConnectionOptions {
+        self.http2_keep_alive_interval = Some(interval);
+        self
+    }
+
+    /// Enables HTTP/2 adaptive flow control
+    #[must_use]
+    pub fn http2_adaptive_window(mut self, enabled: bool) -> ConnectionOptions {
+        self.http2_adaptive_window = enabled;
+        self
+    }
+
+    /// Enables or disables gzip decompression of responses, e.g. of large
+    /// report downloads (default: true)
+    #[cfg(feature = "gzip")]
+    #[must_use]
+    pub fn gzip(mut self, enabled: bool) -> ConnectionOptions {
+        self.gzip = Some(enabled);
+        self
+    }
+
+    /// Enables or disables brotli decompression of responses, e.g. of large
+    /// report downloads (default: true)
+    #[cfg(feature = "brotli")]
+    #[must_use]
+    pub fn brotli(mut self, enabled: bool) -> ConnectionOptions {
+        self.brotli = Some(enabled);
+        self
+    }
+
+    /// Builds an HTTP client with the options
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS backend cannot be initialized
+    pub fn build_http_client(&self) -> Result<ReqwestClient, ReqwestError> {
+        let mut builder = ReqwestClient::builder()
+            .tcp_keepalive(self.tcp_keepalive)
+            .http2_adaptive_window(self.http2_adaptive_window);
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(timeout);
+        }
+        if let Some(interval) = self.http2_keep_alive_interval {
+            builder = builder.http2_keep_alive_interval(interval);
+        }
+        #[cfg(feature = "gzip")]
+        if let Some(enabled) = self.gzip {
+            builder = builder.gzip(enabled);
+        }
+        #[cfg(feature = "brotli")]
+        if let Some(enabled) = self.brotli {
+            builder = builder.brotli(enabled);
+        }
+        builder.build()
+    }
+}
@@ -0,0 +1,98 @@
+//! Streaming the pages of paginated lists
+//!
+//! [`Client::pages`] follows the `next` links of a paginated list lazily, so
+//! that long lists, e.g. a year of reports, are processed one page at a time
+//! as they arrive.
+
+use std::{
+    fmt,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{Client, Error, Paginated};
+
+type NextPage<'a, P> = Pin<Box<dyn Future<Output = Result<P, Error>> + Send + 'a>>;
+
+/// A [`Stream`] of the pages of a paginated list, starting with the first
+/// page and ending after the page without a `next` link or the first error
+///
+/// ```ignore
+/// let mut pages = client.pages(client.get_reports(&filter).await?);
+/// while let Some(page) = pages.next().await {
+///     for report in page?.into_items() {
+///         // ...
+///     }
+/// }
+/// ```
+pub struct Pages<'a, P> {
+    client: &'a Client,
+    state: State<'a, P>,
+}
+
+enum State<'a, P> {
+    Ready(P),
+    Fetching(NextPage<'a, P>),
+    Done,
+}
+
+impl<'a, P> Pages<'a, P>
+where
+    P: Paginated + Send + 'a,
+{
+    pub(crate) fn new(client: &'a Client, first_page: P) -> Pages<'a, P> {
+        Pages {
+            client,
+            state: State::Ready(first_page),
+        }
+    }
+
+    /// Yields `page` and starts fetching the page after it, if any
+    fn yield_page(&mut self, page: P) -> Poll<Option<Result<P, Error>>> {
+        if let Some(url) = page.next_page_url() {
+            let url = url.to_string();
+            let client = self.client;
+            self.state =
+                State::Fetching(Box::pin(async move { client.send_get_request(&url).await }));
+        }
+        Poll::Ready(Some(Ok(page)))
+    }
+}
+
+impl<'a, P> Stream for Pages<'a, P>
+where
+    P: Paginated + Send + Unpin + 'a,
+{
+    type Item = Result<P, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pages = self.get_mut();
+        match mem::replace(&mut pages.state, State::Done) {
+            State::Ready(page) => pages.yield_page(page),
+            State::Fetching(mut next) => match next.as_mut().poll(cx) {
+                Poll::Ready(Ok(page)) => pages.yield_page(page),
+                Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
+                Poll::Pending => {
+                    pages.state = State::Fetching(next);
+                    Poll::Pending
+                }
+            },
+            State::Done => Poll::Ready(None),
+        }
+    }
+}
+
+impl<P> fmt::Debug for Pages<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self.state {
+            State::Ready(_) => "ready",
+            State::Fetching(_) => "fetching",
+            State::Done => "done",
+        };
+        f.debug_struct("Pages").field("state", &state).finish()
+    }
+}
@@ -0,0 +1,175 @@
+//! Typed captures and refunds of a payment's actions, and filters of the
+//! actions
+
+use super::{Action, ActionType};
+use crate::types::Amount;
+
+/// A capture of a payment, as returned by [`PaymentActions::captures`]
+#[derive(Debug, Clone)]
+pub struct CaptureAction {
+    /// The unique identifier of the capture action (format: `act_*`)
+    pub action_id: String,
+
+    /// The date/time the capture was processed
+    pub processed_on: String,
+
+    /// The captured amount
+    pub amount: Amount,
+
+    /// Whether the capture was successful. A capture whose outcome isn't
+    /// reported is not approved.
+    pub approved: bool,
+
+    /// Your reference for the capture request
+    pub reference: Option<String>,
+
+    /// The Gateway response code
+    pub response_code: String,
+
+    /// The acquirer reference number the capture was submitted to the card
+    /// scheme with
+    pub acquirer_reference_number: Option<String>,
+}
+
+/// A refund of a payment, as returned by [`PaymentActions::refunds`]
+#[derive(Debug, Clone)]
+pub struct RefundAction {
+    /// The unique identifier of the refund action (format: `act_*`)
+    pub action_id: String,
+
+    /// The date/time the refund was processed
+    pub processed_on: String,
+
+    /// The refunded amount
+    pub amount: Amount,
+
+    /// Whether the refund was successful. A refund whose outcome isn't
+    /// reported is not approved.
+    pub approved: bool,
+
+    /// Your reference for the refund request
+    pub reference: Option<String>,
+
+    /// The Gateway response code
+    pub response_code: String,
+}
+
+/// Filters and sums the actions of a payment, as returned by
+/// [`Client::get_payment_actions`](crate::Client::get_payment_actions)
+///
+/// # Example
+///
+/// ```ignore
+/// let actions = client.get_payment_actions(payment_id).await?;
+/// let refundable = actions.total_captured().minor_units()
+///     - actions.total_refunded().minor_units();
+/// ```
+pub trait PaymentActions {
+    /// Returns the captures of the payment, including declined ones
+    fn captures(&self) -> Vec<CaptureAction>;
+
+    /// Returns the refunds of the payment, including declined ones
+    fn refunds(&self) -> Vec<RefundAction>;
+
+    /// Returns the sum of the approved captures
+    fn total_captured(&self) -> Amount;
+
+    /// Returns the sum of the approved refunds
+    fn total_refunded(&self) -> Amount;
+}
+
+impl PaymentActions for [Action] {
+    fn captures(&self) -> Vec<CaptureAction> {
+        self.iter()
+            .filter(|action| action.ty == "Capture")
+            .map(|action| CaptureAction {
+                action_id: action.id.clone(),
+                processed_on: action.processed_on.clone(),
+                amount: action.amount,
+                approved: action.approved.unwrap_or(false),
+                reference: action.reference.clone(),
+                response_code: action.response_code.clone(),
+                acquirer_reference_number: action
+                    .processing
+                    .as_ref()
+                    .and_then(|processing| processing.acquirer_reference_number.clone()),
+            })
+            .collect()
+    }
+
+    fn refunds(&self) -> Vec<RefundAction> {
+        self.iter()
+            .filter(|action| action.ty == "Refund")
+            .map(|action| RefundAction {
+                action_id: action.id.clone(),
+                processed_on: action.processed_on.clone(),
+                amount: action.amount,
+                approved: action.approved.unwrap_or(false),
+                reference: action.reference.clone(),
+                response_code: action.response_code.clone(),
+            })
+            .collect()
+    }
+
+    fn total_captured(&self) -> Amount {
+        approved_total(self, "Capture")
+    }
+
+    fn total_refunded(&self) -> Amount {
+        approved_total(self, "Refund")
+    }
+}
+
+fn approved_total(actions: &[Action], ty: &str) -> Amount {
+    Amount::from_minor_units(
+        actions
+            .iter()
+            .filter(|action| action.ty == ty && action.approved.unwrap_or(false))
+            .map(|action| action.amount.minor_units())
+            .sum(),
+    )
+}
+
+/// Filters a payment's actions
+///
+/// The actions endpoint can't be filtered or paged and returns all actions of
+/// a payment in one response, so the filter is applied to the returned
+/// actions rather than sent to the API.
+#[derive(Debug, Clone, Default)]
+pub struct ActionFilter {
+    types: Vec<ActionType>,
+    approved_only: bool,
+}
+
+impl ActionFilter {
+    /// Only return actions of this type. Can be called multiple times to
+    /// match any of several types
+    #[must_use]
+    pub fn action_type(mut self, action_type: ActionType) -> ActionFilter {
+        self.types.push(action_type);
+        self
+    }
+
+    /// Only return approved actions
+    #[must_use]
+    pub fn approved_only(mut self) -> ActionFilter {
+        self.approved_only = true;
+        self
+    }
+
+    /// Returns the actions that match the filter, keeping their order
+    #[must_use]
+    pub fn apply(&self, actions: Vec<Action>) -> Vec<Action> {
+        actions
+            .into_iter()
+            .filter(|action| {
+                self.types.is_empty()
+                    || action
+                        .ty
+                        .parse::<ActionType>()
+                        .is_ok_and(|ty| self.types.contains(&ty))
+            })
+            .filter(|action| !self.approved_only || action.approved.unwrap_or(false))
+            .collect()
+    }
+}
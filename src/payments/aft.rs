@@ -0,0 +1,307 @@
+//! Account Funding Transactions (AFTs), which pull funds from a card to fund
+//! another account, e.g. a wallet top-up or a money transfer
+//!
+//! Card schemes require sender and recipient data depending on the merchant
+//! category code (MCC) of the merchant. [`AftRequest`] checks these
+//! requirements before the payment is sent, since an AFT with missing data
+//! may be processed but is non-compliant.
+//!
+//! See: [Account Funding Transactions](https://docs.checkout.com/payments/manage-payments/account-funding-transactions)
+
+use crate::types::{
+    CreatePaymentRequest, Metadata, Money, PaymentAmount, PaymentProcessingDescriptor,
+    PaymentRecipient, PaymentRequestSource, PaymentType, PayoutSender,
+};
+
+/// The merchant category of an AFT, which determines the data card schemes
+/// require
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AftCategory {
+    /// Money transfers (MCC 4829)
+    MoneyTransfer,
+
+    /// Financial institutions, e.g. funding a bank account (MCC 6012)
+    FinancialInstitution,
+
+    /// Quasi-cash, e.g. funding a wallet or prepaid card (MCC 6051)
+    QuasiCash,
+
+    /// Security brokers and dealers (MCC 6211)
+    Securities,
+
+    /// Gambling (MCC 7995)
+    Gambling,
+
+    /// Any other merchant category code
+    Other(u16),
+}
+
+impl AftCategory {
+    /// Returns the category of a merchant category code
+    #[must_use]
+    pub fn from_mcc(mcc: u16) -> AftCategory {
+        match mcc {
+            4829 => AftCategory::MoneyTransfer,
+            6012 => AftCategory::FinancialInstitution,
+            6051 => AftCategory::QuasiCash,
+            6211 => AftCategory::Securities,
+            7995 => AftCategory::Gambling,
+            mcc => AftCategory::Other(mcc),
+        }
+    }
+
+    /// Returns the merchant category code
+    #[must_use]
+    pub fn mcc(self) -> u16 {
+        match self {
+            AftCategory::MoneyTransfer => 4829,
+            AftCategory::FinancialInstitution => 6012,
+            AftCategory::QuasiCash => 6051,
+            AftCategory::Securities => 6211,
+            AftCategory::Gambling => 7995,
+            AftCategory::Other(mcc) => mcc,
+        }
+    }
+
+    /// Returns whether the recipient's name and account number are required
+    #[must_use]
+    pub fn requires_recipient_account(self) -> bool {
+        matches!(
+            self,
+            AftCategory::MoneyTransfer | AftCategory::QuasiCash | AftCategory::Securities
+        )
+    }
+
+    /// Returns whether the recipient's date of birth, account number, postcode
+    /// and last name are required, as for UK domestic transactions of
+    /// financial institutions
+    #[must_use]
+    pub fn requires_recipient_identity(self) -> bool {
+        self == AftCategory::FinancialInstitution
+    }
+
+    /// Returns whether the purpose of the transfer is required
+    #[must_use]
+    pub fn requires_purpose(self) -> bool {
+        self == AftCategory::MoneyTransfer
+    }
+}
+
+/// An AFT is missing data card schemes require, it was not sent
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AftValidationError {
+    /// The payment has no amount
+    #[error("an AFT must have an amount")]
+    ZeroAmount,
+
+    /// The sender has no name
+    #[error("the sender's name is required")]
+    MissingSenderName,
+
+    /// The sender has no address, or an address without a country
+    #[error("the sender's address with country is required")]
+    MissingSenderAddress,
+
+    /// A recipient field required for the merchant category is missing,
+    /// contains the field name
+    #[error("the recipient's {0} is required for this merchant category")]
+    MissingRecipientField(&'static str),
+
+    /// The recipient's account number is not the first six and last four
+    /// digits of a card, or up to ten digits of an account number
+    #[error("the recipient's account number must be up to 10 digits")]
+    InvalidRecipientAccountNumber,
+
+    /// The purpose is required for the merchant category
+    #[error("the purpose of the transfer is required for this merchant category")]
+    MissingPurpose,
+}
+
+/// A request for an Account Funding Transaction
+///
+/// # Example
+///
+/// ```ignore
+/// let aft = AftRequest::new(source, money, AftCategory::MoneyTransfer, sender, recipient)
+///     .purpose("family_support")
+///     .reference("transfer-123");
+///
+/// let response = client.create_aft(&aft).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct AftRequest {
+    source: PaymentRequestSource,
+    money: Money,
+    category: AftCategory,
+    sender: PayoutSender,
+    recipient: PaymentRecipient,
+    purpose: Option<String>,
+    reference: Option<String>,
+    metadata: Option<Metadata>,
+}
+
+impl AftRequest {
+    /// Creates an AFT request funded by the source
+    #[must_use]
+    pub fn new(
+        source: PaymentRequestSource,
+        money: Money,
+        category: AftCategory,
+        sender: PayoutSender,
+        recipient: PaymentRecipient,
+    ) -> AftRequest {
+        AftRequest {
+            source,
+            money,
+            category,
+            sender,
+            recipient,
+            purpose: None,
+            reference: None,
+            metadata: None,
+        }
+    }
+
+    /// Sets the purpose of the transfer, e.g. `"family_support"`. Required
+    /// for money transfers.
+    #[must_use]
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+
+    /// Sets a reference you can later use to identify this payment (<= 50
+    /// characters)
+    #[must_use]
+    pub fn reference(mut self, reference: impl Into<String>) -> Self {
+        self.reference = Some(reference.into());
+        self
+    }
+
+    /// Sets the metadata of the payment
+    #[must_use]
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = Some(metadata);
+        self
+    }
+
+    /// Returns the merchant category of the AFT
+    #[must_use]
+    pub fn category(&self) -> AftCategory {
+        self.category
+    }
+
+    /// Checks that the sender, recipient and processing data required for the
+    /// merchant category are present
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found in the request
+    pub fn validate(&self) -> Result<(), Vec<AftValidationError>> {
+        let mut errors = Vec::new();
+
+        if self.money.amount.minor_units() == 0 {
+            errors.push(AftValidationError::ZeroAmount);
+        }
+
+        let (has_name, address) = match &self.sender {
+            PayoutSender::Individual {
+                first_name,
+                last_name,
+                address,
+                ..
+            } => (!first_name.is_empty() && !last_name.is_empty(), address),
+            PayoutSender::Corporate {
+                company_name,
+                address,
+                ..
+            } => (!company_name.is_empty(), address),
+        };
+        if !has_name {
+            errors.push(AftValidationError::MissingSenderName);
+        }
+        if address.as_ref().and_then(|a| a.country.as_ref()).is_none() {
+            errors.push(AftValidationError::MissingSenderAddress);
+        }
+
+        let recipient = &self.recipient;
+        let mut required = Vec::new();
+        if self.category.requires_recipient_account() {
+            required.push(("first_name", &recipient.first_name));
+            required.push(("last_name", &recipient.last_name));
+            required.push(("account_number", &recipient.account_number));
+        }
+        if self.category.requires_recipient_identity() {
+            required.push(("dob", &recipient.dob));
+            required.push(("account_number", &recipient.account_number));
+            required.push(("zip", &recipient.zip));
+            required.push(("last_name", &recipient.last_name));
+        }
+        for (field, value) in required {
+            if value.as_deref().is_none_or(str::is_empty) {
+                errors.push(AftValidationError::MissingRecipientField(field));
+            }
+        }
+
+        if let Some(number) = &recipient.account_number {
+            if number.len() > 10 || !number.chars().all(|c| c.is_ascii_digit()) {
+                errors.push(AftValidationError::InvalidRecipientAccountNumber);
+            }
+        }
+
+        if self.category.requires_purpose() && self.purpose.is_none() {
+            errors.push(AftValidationError::MissingPurpose);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Validates the request and converts it into a payment request flagged
+    /// as an AFT
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found in the request
+    pub fn into_payment_request(self) -> Result<CreatePaymentRequest, Vec<AftValidationError>> {
+        self.validate()?;
+
+        Ok(CreatePaymentRequest {
+            source: Some(self.source),
+            destination: None,
+            amount: PaymentAmount::Charge(self.money),
+            payment_type: PaymentType::Regular,
+            merchant_initiated: false,
+            reference: self.reference,
+            description: None,
+            capture: None,
+            capture_on: None,
+            customer: None,
+            billing_descriptor: None,
+            shipping: None,
+            three_ds: None,
+            previous_payment_id: None,
+            risk: None,
+            success_url: None,
+            failure_url: None,
+            payment_ip: None,
+            sender: Some(self.sender),
+            recipient: Some(self.recipient),
+            processing_channel_id: None,
+            processing: Some(PaymentProcessingDescriptor {
+                aft: true,
+                merchant_category_code: Some(self.category.mcc().to_string()),
+                purpose: self.purpose,
+                preferred_scheme: None,
+                dlocal: None,
+            }),
+            store: None,
+            items: None,
+            metadata: self.metadata,
+            extra: None,
+        })
+    }
+}
@@ -0,0 +1,137 @@
+//! Payment requests that borrow their data
+//!
+//! [`CreatePaymentRequest`] owns all of its fields, so sending a payment for
+//! data that is already held elsewhere, e.g. in an order, clones every string.
+//! [`PaymentRequestRef`] covers the fields of high-throughput card payments
+//! and serializes them by reference instead.
+
+use serde::Serialize;
+
+use crate::types::{Amount, Currency, Cvv, Metadata, Money, PaymentType};
+
+/// The source of a [`PaymentRequestRef`]
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaymentSourceRef<'a> {
+    /// A debit/credit/etc card
+    Card {
+        /// The card number (without separators) (<=19 characters)
+        number: &'a str,
+
+        /// The expiry month of the card (1-2 characters)
+        expiry_month: u32,
+
+        /// The expiry year of the card (4 characters)
+        expiry_year: u32,
+
+        /// The name of the cardholder
+        #[serde(skip_serializing_if = "Option::is_none")]
+        name: Option<&'a str>,
+
+        /// The card verification value/code. 3 digits, except for Amex (4
+        /// digits)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cvv: Option<&'a Cvv>,
+
+        /// Whether the payment uses stored card details
+        #[serde(skip_serializing_if = "Option::is_none")]
+        stored: Option<bool>,
+    },
+
+    /// A token representing a debit/credit/etc card
+    Token {
+        /// The token retrieved by posting card details to `/tokens` beforehand
+        token: &'a str,
+    },
+
+    /// An existing payment source, such as a stored card instrument
+    Id {
+        /// The payment source identifier (format: `src_*`)
+        id: &'a str,
+
+        /// The card verification value/code, if required by the source
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cvv: Option<&'a Cvv>,
+    },
+
+    /// The default payment instrument of an existing customer
+    Customer {
+        /// The customer's unique identifier (format: `cus_*`)
+        id: &'a str,
+    },
+}
+
+/// Request body for a payment that borrows its data, see
+/// [`Client::create_payment_ref`](crate::Client::create_payment_ref)
+#[derive(Serialize, Debug, Clone)]
+pub struct PaymentRequestRef<'a> {
+    /// The source of the payment
+    pub source: PaymentSourceRef<'a>,
+
+    /// The payment amount, in the currency's minor units
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code of the payment
+    pub currency: Currency,
+
+    /// The type of the payment (default: Regular)
+    pub payment_type: PaymentType,
+
+    /// Flags the payment as a merchant-initiated transaction (MIT)
+    pub merchant_initiated: bool,
+
+    /// A reference you can later use to identify this payment (<= 50
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<&'a str>,
+
+    /// A description of the payment (<= 100 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<&'a str>,
+
+    /// Whether to capture the payment (if applicable) (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub capture: Option<bool>,
+
+    /// An existing payment identifier from the recurring series or the
+    /// Scheme Transaction Id, for payments that use stored card details
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub previous_payment_id: Option<&'a str>,
+
+    /// Additional information about the payment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<&'a Metadata>,
+}
+
+impl<'a> PaymentRequestRef<'a> {
+    /// Creates a regular, customer-initiated payment request
+    #[must_use]
+    pub fn new(source: PaymentSourceRef<'a>, money: Money) -> PaymentRequestRef<'a> {
+        PaymentRequestRef {
+            source,
+            amount: money.amount,
+            currency: money.currency,
+            payment_type: PaymentType::Regular,
+            merchant_initiated: false,
+            reference: None,
+            description: None,
+            capture: None,
+            previous_payment_id: None,
+            metadata: None,
+        }
+    }
+
+    /// Sets the reference of the payment
+    #[must_use]
+    pub fn reference(mut self, reference: &'a str) -> PaymentRequestRef<'a> {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// Sets the metadata of the payment
+    #[must_use]
+    pub fn metadata(mut self, metadata: &'a Metadata) -> PaymentRequestRef<'a> {
+        self.metadata = Some(metadata);
+        self
+    }
+}
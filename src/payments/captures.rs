@@ -0,0 +1,83 @@
+//! Types for multiple partial captures of a payment and their webhook
+//! notifications
+//!
+//! See: [Capture a payment](https://docs.checkout.com/payments/manage-payments/capture-a-payment)
+
+use serde::{Deserialize, Serialize};
+
+use super::ActionProcessingInfo;
+use crate::types::{Amount, CapturePaymentResponse, Currency, ExtraFields, Metadata};
+
+/// Whether a capture is the last one of a payment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureType {
+    /// No further captures are expected, the remaining authorization is
+    /// released
+    Final,
+
+    /// Further captures are expected, e.g. one per shipment
+    NonFinal,
+}
+
+/// The data of a `payment_captured` or `payment_capture_declined` webhook
+/// notification
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CaptureEventData {
+    /// The payment's unique identifier (format: `pay_*`)
+    pub id: String,
+
+    /// The unique identifier of the capture action (format: `act_*`), as
+    /// returned by [`CapturePaymentResponse::action_id`]
+    pub action_id: String,
+
+    /// The captured amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code of the capture
+    pub currency: Currency,
+
+    /// Your reference for the capture request
+    pub reference: Option<String>,
+
+    /// Whether more captures are expected for the payment
+    pub capture_type: Option<CaptureType>,
+
+    /// The Gateway response code
+    pub response_code: Option<String>,
+
+    /// The Gateway response summary
+    pub response_summary: Option<String>,
+
+    /// Information related to the processing of the capture, including the
+    /// scheme references of the capture
+    pub processing: Option<ActionProcessingInfo>,
+
+    /// The metadata attached to the capture request
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields returned that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl CaptureEventData {
+    /// Returns whether the notification is for the given capture request,
+    /// including its child actions with multi-capture
+    #[must_use]
+    pub fn is_for(&self, capture: &CapturePaymentResponse) -> bool {
+        capture.has_action(&self.action_id)
+    }
+
+    /// Returns the acquirer reference number the capture was submitted to
+    /// the card scheme with
+    #[must_use]
+    pub fn acquirer_reference_number(&self) -> Option<&str> {
+        self.processing
+            .as_ref()?
+            .acquirer_reference_number
+            .as_deref()
+    }
+}
+
+/// A capture webhook notification
+pub type CaptureWebhookEvent = crate::WebhookEvent<CaptureEventData>;
@@ -0,0 +1,204 @@
+//! Processing information for payments routed through dLocal
+//!
+//! dLocal processes local payment methods and cards in Latin America. Most
+//! countries require the payer's national identity document, and only some
+//! allow installments. A payment missing these fields is declined with an
+//! error that doesn't name the missing field, so
+//! [`Client::create_payment`](crate::Client::create_payment) validates them
+//! before the payment is sent.
+//!
+//! See: [dLocal](https://docs.checkout.com/payments/payment-methods/dlocal)
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::CreatePaymentRequest;
+
+/// The countries dLocal payments can be processed in, whether they require
+/// the payer's document, the number of digits the document may have, and
+/// whether they allow installments
+const COUNTRIES: &[(&str, bool, &[usize], bool)] = &[
+    ("AR", true, &[7, 8, 11], true),
+    ("BR", true, &[11, 14], true),
+    ("CL", true, &[8, 9], true),
+    ("CO", true, &[6, 7, 8, 9, 10], true),
+    ("MX", false, &[], true),
+    ("PE", true, &[8, 11], true),
+    ("UY", true, &[7, 8], false),
+];
+
+/// The maximum number of installments of a payment
+pub const MAX_DLOCAL_INSTALLMENTS: u32 = 24;
+
+/// Processing information required for dLocal payments
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DLocalProcessing {
+    /// The two-letter ISO code of the country the payment is processed in
+    pub country: String,
+
+    /// The payer of the payment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<DLocalPayer>,
+
+    /// The installments to split the payment into
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installments: Option<DLocalInstallments>,
+}
+
+/// The payer of a dLocal payment
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DLocalPayer {
+    /// The payer's national identity document, e.g. a CPF in Brazil
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document: Option<String>,
+
+    /// The payer's full name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The payer's email address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+}
+
+/// The installments of a dLocal payment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct DLocalInstallments {
+    /// The number of installments
+    pub count: u32,
+}
+
+/// A dLocal payment is missing fields its country requires, it was not sent
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum DLocalValidationError {
+    /// dLocal payments can't be processed in the country
+    #[error("dLocal payments are not supported in {0}")]
+    UnsupportedCountry(String),
+
+    /// The country requires the payer's document
+    #[error("the payer's document is required in {0}")]
+    MissingDocument(String),
+
+    /// The payer's document doesn't have a valid number of digits for the
+    /// country
+    #[error("the payer's document is not valid in {0}")]
+    InvalidDocument(String),
+
+    /// The country doesn't allow installments
+    #[error("installments are not supported in {0}")]
+    InstallmentsNotSupported(String),
+
+    /// The number of installments is zero or too large
+    #[error("{0} installments are not supported")]
+    InvalidInstallments(u32),
+
+    /// dLocal payments require a reference
+    #[error("a reference is required for dLocal payments")]
+    MissingReference,
+}
+
+impl DLocalProcessing {
+    /// Creates the processing information of a payment in the country
+    #[must_use]
+    pub fn new(country: impl Into<String>) -> DLocalProcessing {
+        DLocalProcessing {
+            country: country.into(),
+            payer: None,
+            installments: None,
+        }
+    }
+
+    /// Sets the payer's national identity document. Separators like `.` and
+    /// `-` are allowed.
+    #[must_use]
+    pub fn payer_document(mut self, document: impl Into<String>) -> Self {
+        self.payer.get_or_insert_with(DLocalPayer::default).document = Some(document.into());
+        self
+    }
+
+    /// Splits the payment into installments
+    #[must_use]
+    pub fn installments(mut self, count: u32) -> Self {
+        self.installments = Some(DLocalInstallments { count });
+        self
+    }
+
+    /// Checks that the fields required in the country are present
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found
+    pub fn validate(&self) -> Result<(), Vec<DLocalValidationError>> {
+        let country = self.country.to_ascii_uppercase();
+        let (requires_document, document_lengths, allows_installments) =
+            match COUNTRIES.iter().find(|(code, ..)| *code == country) {
+                Some((_, document, lengths, installments)) => (*document, *lengths, *installments),
+                None => return Err(vec![DLocalValidationError::UnsupportedCountry(country)]),
+            };
+
+        let mut errors = Vec::new();
+        let document = self
+            .payer
+            .as_ref()
+            .and_then(|payer| payer.document.as_deref());
+        match document {
+            Some(document) => {
+                let valid_chars = document
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '/' | ' '));
+                let digits = document.chars().filter(char::is_ascii_alphanumeric).count();
+                if !valid_chars || (requires_document && !document_lengths.contains(&digits)) {
+                    errors.push(DLocalValidationError::InvalidDocument(country.clone()));
+                }
+            }
+            None if requires_document => {
+                errors.push(DLocalValidationError::MissingDocument(country.clone()));
+            }
+            None => {}
+        }
+
+        if let Some(installments) = self.installments {
+            if !allows_installments {
+                errors.push(DLocalValidationError::InstallmentsNotSupported(country));
+            } else if installments.count == 0 || installments.count > MAX_DLOCAL_INSTALLMENTS {
+                errors.push(DLocalValidationError::InvalidInstallments(
+                    installments.count,
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl CreatePaymentRequest {
+    /// Checks the dLocal processing information of the payment, if any, and
+    /// that the payment has the reference dLocal requires
+    ///
+    /// # Errors
+    ///
+    /// Returns every problem found
+    pub fn validate_dlocal(&self) -> Result<(), Vec<DLocalValidationError>> {
+        let Some(dlocal) = self
+            .processing
+            .as_ref()
+            .and_then(|processing| processing.dlocal.as_ref())
+        else {
+            return Ok(());
+        };
+
+        let mut errors = dlocal.validate().err().unwrap_or_default();
+        if self.reference.as_deref().is_none_or(str::is_empty) {
+            errors.push(DLocalValidationError::MissingReference);
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Retrying declined recurring payments (dunning)
+//!
+//! Card schemes limit how often a declined merchant-initiated payment may be
+//! retried, and the issuer's merchant advice code may forbid retries or ask
+//! for a delay. [`RetryAdvice`] combines the response code and the advice
+//! code of a decline into whether and when to retry.
+//!
+//! See: [Response codes](https://docs.checkout.com/resources/codes/api-response-codes)
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::types::{DeclineReason, MerchantAdviceCode, PaymentProcessed, ResponseCode};
+
+/// The maximum number of retries of a declined payment within
+/// [`RETRY_WINDOW_DAYS`] that the schemes allow
+pub const MAX_RETRY_ATTEMPTS: usize = 10;
+
+/// The number of days after a decline within which retries are counted
+pub const RETRY_WINDOW_DAYS: i64 = 30;
+
+/// The days after a decline at which it is retried by
+/// [`RetrySchedule::standard`]
+const STANDARD_RETRY_DAYS: [i64; 4] = [1, 3, 7, 14];
+
+/// Whether and when to retry a declined payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RetryAdvice {
+    /// Do not retry the payment with the same payment details
+    DoNotRetry,
+
+    /// Retry only with updated payment details, e.g. a new card from the
+    /// customer or an account updater
+    UpdatePaymentDetails,
+
+    /// Retry according to the schedule
+    Retry(RetrySchedule),
+}
+
+impl RetryAdvice {
+    /// Returns the advice for a decline from its Gateway response code and
+    /// summary and the merchant advice code of the issuer, which takes
+    /// precedence. Returns `None` if the response code is not a decline.
+    #[must_use]
+    pub fn from_decline(
+        response_code: &str,
+        response_summary: Option<&str>,
+        advice: Option<&MerchantAdviceCode>,
+    ) -> Option<RetryAdvice> {
+        let reason = DeclineReason::from_response(response_code, response_summary)?;
+
+        match advice {
+            Some(
+                MerchantAdviceCode::DoNotTryAgain
+                | MerchantAdviceCode::PaymentCancelled
+                | MerchantAdviceCode::NonReloadablePrepaidCard
+                | MerchantAdviceCode::SingleUseVirtualCard,
+            ) => return Some(RetryAdvice::DoNotRetry),
+            Some(
+                MerchantAdviceCode::NewAccountInformation
+                | MerchantAdviceCode::TokenRequirementsNotFulfilled,
+            ) => return Some(RetryAdvice::UpdatePaymentDetails),
+            Some(MerchantAdviceCode::TryAgainLater) => {
+                return Some(RetryAdvice::Retry(RetrySchedule::standard()))
+            }
+            Some(advice) => {
+                if let Some(delay) = advice.retry_after() {
+                    return Some(RetryAdvice::Retry(RetrySchedule::starting_after(delay)));
+                }
+            }
+            None => {}
+        }
+
+        Some(match (ResponseCode::from(response_code), reason) {
+            (
+                ResponseCode::SoftDecline(_),
+                DeclineReason::ExpiredCard
+                | DeclineReason::InvalidCard
+                | DeclineReason::ClosedAccount,
+            ) => RetryAdvice::UpdatePaymentDetails,
+            (ResponseCode::SoftDecline(_), _) => RetryAdvice::Retry(RetrySchedule::standard()),
+            _ => RetryAdvice::DoNotRetry,
+        })
+    }
+
+    /// Returns the schedule to retry the payment at, or `None` if it should
+    /// not be retried with the same payment details
+    #[must_use]
+    pub fn schedule(&self) -> Option<&RetrySchedule> {
+        match self {
+            RetryAdvice::Retry(schedule) => Some(schedule),
+            _ => None,
+        }
+    }
+}
+
+/// The delays after a decline at which to retry the payment, limited to
+/// [`MAX_RETRY_ATTEMPTS`] retries within [`RETRY_WINDOW_DAYS`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetrySchedule {
+    delays: Vec<Duration>,
+}
+
+impl RetrySchedule {
+    /// Creates a schedule from delays after the decline. Delays that are not
+    /// positive or fall outside of the retry window are dropped, and only the
+    /// first [`MAX_RETRY_ATTEMPTS`] are kept.
+    #[must_use]
+    pub fn new(mut delays: Vec<Duration>) -> RetrySchedule {
+        let window = Duration::days(RETRY_WINDOW_DAYS);
+        delays.retain(|delay| *delay > Duration::zero() && *delay <= window);
+        delays.sort();
+        delays.dedup();
+        delays.truncate(MAX_RETRY_ATTEMPTS);
+        RetrySchedule { delays }
+    }
+
+    /// Creates the schedule used when the issuer does not ask for a delay:
+    /// retries 1, 3, 7 and 14 days after the decline
+    #[must_use]
+    pub fn standard() -> RetrySchedule {
+        RetrySchedule::new(
+            STANDARD_RETRY_DAYS
+                .iter()
+                .map(|days| Duration::days(*days))
+                .collect(),
+        )
+    }
+
+    /// Creates a schedule whose first retry is after `delay`, followed by
+    /// the later retries of the standard schedule
+    #[must_use]
+    pub fn starting_after(delay: Duration) -> RetrySchedule {
+        let later = STANDARD_RETRY_DAYS
+            .iter()
+            .map(|days| Duration::days(*days))
+            .filter(|standard| *standard > delay);
+        RetrySchedule::new(std::iter::once(delay).chain(later).collect())
+    }
+
+    /// Returns the delays after the decline, in ascending order
+    #[must_use]
+    pub fn delays(&self) -> &[Duration] {
+        &self.delays
+    }
+
+    /// Returns when to retry a payment that was declined at `declined_at`
+    #[must_use]
+    pub fn attempts(&self, declined_at: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        self.delays
+            .iter()
+            .map(|delay| declined_at + *delay)
+            .collect()
+    }
+
+    /// Returns when to make the next retry after `retries` retries of a
+    /// payment that was declined at `declined_at`, or `None` if no retries
+    /// are left
+    #[must_use]
+    pub fn next_attempt(
+        &self,
+        declined_at: DateTime<Utc>,
+        retries: usize,
+    ) -> Option<DateTime<Utc>> {
+        self.delays.get(retries).map(|delay| declined_at + *delay)
+    }
+}
+
+impl PaymentProcessed {
+    /// Returns the issuer's merchant advice code of a declined payment
+    #[must_use]
+    pub fn merchant_advice(&self) -> Option<MerchantAdviceCode> {
+        self.processing.as_ref()?.merchant_advice()
+    }
+
+    /// Returns whether and when to retry the payment, or `None` if it was
+    /// approved
+    #[must_use]
+    pub fn retry_advice(&self) -> Option<RetryAdvice> {
+        if self.approved {
+            return None;
+        }
+        RetryAdvice::from_decline(
+            &self.response_code,
+            self.response_summary.as_deref(),
+            self.merchant_advice().as_ref(),
+        )
+    }
+}
@@ -0,0 +1,239 @@
+//! Types for the financial actions of payments, i.e. how each capture,
+//! refund or chargeback was settled and which fees were deducted
+//!
+//! See: [Financial actions](https://docs.checkout.com/reporting-and-insights/financial-actions)
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::types::{
+    links::Links, ActionId, Currency, EntityId, Paginated, PaymentId, ProcessingChannelId,
+    SubEntityId,
+};
+
+/// A settlement-impacting action of a payment, e.g. a capture, and its fee
+/// breakdown
+#[derive(Deserialize, Debug, Clone)]
+pub struct FinancialAction {
+    /// The unique identifier of the payment
+    pub payment_id: Option<PaymentId>,
+
+    /// The unique identifier of the action
+    pub action_id: Option<ActionId>,
+
+    /// The type of the action, e.g. `"CAPTURE"` or `"REFUND"`
+    pub action_type: Option<String>,
+
+    /// The entity the action was processed for
+    pub entity_id: Option<EntityId>,
+
+    /// The sub-entity the action was processed for, on platforms
+    pub sub_entity_id: Option<SubEntityId>,
+
+    /// The currency account the action was settled to (format: `ca_*`)
+    pub currency_account_id: Option<String>,
+
+    /// The payment method, e.g. `"VISA"`
+    pub payment_method: Option<String>,
+
+    /// The processing channel the action was processed with
+    pub processing_channel_id: Option<ProcessingChannelId>,
+
+    /// Your reference for the payment
+    pub reference: Option<String>,
+
+    /// The merchant identification number the action was processed with
+    pub mid: Option<String>,
+
+    /// The Gateway response code of the action
+    pub response_code: Option<String>,
+
+    /// The description of the response code
+    pub response_description: Option<String>,
+
+    /// The region of the card relative to the entity, e.g. `"Domestic"`
+    pub region: Option<String>,
+
+    /// The type of the card, e.g. `"DEBIT"`
+    pub card_type: Option<String>,
+
+    /// The category of the card, e.g. `"CONSUMER"`
+    pub card_category: Option<String>,
+
+    /// The country the card was issued in (two-letter ISO code)
+    pub issuer_country: Option<String>,
+
+    /// The merchant category code the action was processed with
+    pub merchant_category_code: Option<String>,
+
+    /// The identifier of the FX trade of the action, if converted
+    pub fx_trade_id: Option<String>,
+
+    /// The date/time the action was processed
+    pub processed_on: Option<DateTime<Utc>>,
+
+    /// The date/time the action was requested
+    pub requested_on: Option<DateTime<Utc>>,
+
+    /// The amounts the action settled, one per gross amount or fee
+    #[serde(default)]
+    pub breakdown: Vec<FeeBreakdown>,
+}
+
+impl FinancialAction {
+    /// Returns the sum of the breakdown entries of `category` in the holding
+    /// currency, e.g. the interchange paid for the action. Fees are
+    /// negative.
+    #[must_use]
+    pub fn holding_currency_total(&self, category: FeeCategory) -> BigDecimal {
+        self.breakdown
+            .iter()
+            .filter(|entry| entry.category() == category)
+            .filter_map(|entry| entry.holding_currency_amount.clone())
+            .fold(BigDecimal::from(0), |total, amount| total + amount)
+    }
+
+    /// Returns the currency the action was settled in, if known
+    #[must_use]
+    pub fn holding_currency(&self) -> Option<Currency> {
+        self.breakdown
+            .iter()
+            .find_map(|entry| entry.holding_currency)
+    }
+}
+
+/// An amount of a financial action: its gross amount, a fee or a reserve
+///
+/// Amounts are in major units, e.g. `0.12` for 12 cents, and in up to three
+/// currencies: the currency of the transaction, the currency it was
+/// processed in, and the currency of the account it was settled to.
+#[derive(Deserialize, Debug, Clone)]
+pub struct FeeBreakdown {
+    /// The type of the amount, e.g. `"Interchange Variable Fee"`. See
+    /// [`FeeBreakdown::category`].
+    pub breakdown_type: String,
+
+    /// The FX rate applied to the amount, if converted
+    pub fx_rate_applied: Option<BigDecimal>,
+
+    /// The currency of the account the action was settled to
+    pub holding_currency: Option<Currency>,
+
+    /// The amount in the holding currency
+    pub holding_currency_amount: Option<BigDecimal>,
+
+    /// The currency the action was processed in
+    pub processing_currency: Option<Currency>,
+
+    /// The amount in the processing currency
+    pub processing_currency_amount: Option<BigDecimal>,
+
+    /// The currency of the transaction
+    pub transaction_currency: Option<Currency>,
+
+    /// The amount in the transaction currency
+    pub transaction_currency_amount: Option<BigDecimal>,
+
+    /// The FX rate from the processing to the transaction currency
+    pub processing_to_transaction_currency_fx_rate: Option<BigDecimal>,
+
+    /// The FX rate from the transaction to the holding currency
+    pub transaction_to_holding_currency_fx_rate: Option<BigDecimal>,
+
+    /// A description of the fee, e.g. `"Visa Fixed Acquirer Network Fee"`
+    pub fee_detail: Option<String>,
+
+    /// The rate of a rolling reserve, e.g. `"5%"`
+    pub reserve_rate: Option<String>,
+
+    /// When a rolling reserve is released
+    pub reserve_release_date: Option<DateTime<Utc>>,
+
+    /// When a rolling reserve was deducted
+    pub reserve_deducted_date: Option<DateTime<Utc>>,
+
+    /// The FX rate to the tax currency of the entity's country
+    pub tax_fx_rate: Option<BigDecimal>,
+
+    /// The tax currency of the entity's country
+    pub entity_country_tax_currency: Option<Currency>,
+
+    /// The amount in the tax currency
+    pub tax_currency_amount: Option<BigDecimal>,
+}
+
+impl FeeBreakdown {
+    /// Returns who the amount is paid to, derived from its type
+    #[must_use]
+    pub fn category(&self) -> FeeCategory {
+        let ty = self.breakdown_type.to_ascii_lowercase();
+        if ty.contains("interchange") {
+            FeeCategory::Interchange
+        } else if ty.contains("scheme") {
+            FeeCategory::SchemeFee
+        } else if ty.contains("reserve") {
+            FeeCategory::Reserve
+        } else if ty.contains("gross") {
+            FeeCategory::Gross
+        } else if ty.contains("fee") {
+            FeeCategory::Commission
+        } else {
+            FeeCategory::Other
+        }
+    }
+}
+
+/// What an amount of a [`FeeBreakdown`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FeeCategory {
+    /// The gross amount of the action
+    Gross,
+
+    /// A fee of the card scheme
+    SchemeFee,
+
+    /// The interchange fee of the issuer
+    Interchange,
+
+    /// A fee of Checkout, e.g. a fixed or variable processing fee
+    Commission,
+
+    /// A rolling reserve that is withheld or released
+    Reserve,
+
+    /// An amount of a type this library doesn't categorize
+    Other,
+}
+
+/// Response to list financial actions
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetFinancialActionsResponse {
+    /// The number of actions on the page
+    pub count: u32,
+
+    /// The maximum number of actions on a page
+    pub limit: u32,
+
+    /// The financial actions
+    #[serde(default)]
+    pub data: Vec<FinancialAction>,
+
+    /// The links related to the page
+    ///
+    /// - Optional: `"next"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+impl Paginated for GetFinancialActionsResponse {
+    type Item = FinancialAction;
+
+    fn links(&self) -> Option<&Links> {
+        self.links.as_ref()
+    }
+
+    fn into_items(self) -> Vec<FinancialAction> {
+        self.data
+    }
+}
@@ -0,0 +1,255 @@
+//! Types for Hosted Payments Pages and Payment Links
+//!
+//! See: [Hosted Payments Page](https://docs.checkout.com/hosted-payments-page)
+//! and [Payment Links](https://docs.checkout.com/payment-links)
+
+#![allow(missing_docs)]
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    links::Links, Address, Amount, Currency, CustomerDescriptor, ExtraFields, Metadata,
+    PhoneNumber, ShippingDescriptor,
+};
+
+/// Request body to create a Hosted Payments Page session
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateHostedPaymentRequest {
+    /// The payment amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code
+    pub currency: Currency,
+
+    /// A reference you can later use to identify this payment, such as an
+    /// order number (<= 50 characters)
+    pub reference: Option<String>,
+
+    /// A description of the payment (<= 100 characters)
+    pub description: Option<String>,
+
+    /// The customer's details
+    pub customer: Option<CustomerDescriptor>,
+
+    /// The billing details of the customer
+    pub billing: Option<HostedBilling>,
+
+    /// The shipping details
+    pub shipping: Option<ShippingDescriptor>,
+
+    /// The URL the customer is redirected to after a successful payment (<=
+    /// 255 characters)
+    pub success_url: String,
+
+    /// The URL the customer is redirected to if they cancel the payment (<=
+    /// 255 characters)
+    pub cancel_url: String,
+
+    /// The URL the customer is redirected to after a failed payment (<= 255
+    /// characters)
+    pub failure_url: String,
+
+    /// Allows you to store additional information about a transaction
+    pub metadata: Option<Metadata>,
+
+    /// Controls how the page is displayed to the customer
+    #[serde(flatten)]
+    pub display: PageDisplayOptions,
+
+    /// Controls the receipt Checkout sends to the customer after the payment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<ReceiptOptions>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// Request body to create a Payment Link
+#[derive(Serialize, Debug, Clone)]
+pub struct CreatePaymentLinkRequest {
+    /// The payment amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code
+    pub currency: Currency,
+
+    /// A reference you can later use to identify this payment, such as an
+    /// order number (<= 50 characters)
+    pub reference: Option<String>,
+
+    /// A description of the payment (<= 100 characters)
+    pub description: Option<String>,
+
+    /// The number of seconds until the link expires (default: 86400)
+    pub expires_in: Option<u32>,
+
+    /// The customer's details
+    pub customer: Option<CustomerDescriptor>,
+
+    /// The billing details of the customer
+    pub billing: Option<HostedBilling>,
+
+    /// The shipping details
+    pub shipping: Option<ShippingDescriptor>,
+
+    /// The URL the customer is redirected to after completing the payment
+    /// (<= 255 characters)
+    pub return_url: Option<String>,
+
+    /// Allows you to store additional information about a transaction
+    pub metadata: Option<Metadata>,
+
+    /// Controls how the page is displayed to the customer
+    #[serde(flatten)]
+    pub display: PageDisplayOptions,
+
+    /// Controls the receipt Checkout sends to the customer after the payment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<ReceiptOptions>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// The billing details collected on a hosted page
+#[derive(Serialize, Debug, Clone)]
+pub struct HostedBilling {
+    /// The billing address
+    pub address: Address,
+
+    /// The phone number associated with the billing address
+    pub phone: Option<PhoneNumber>,
+}
+
+/// Display customisation shared by Hosted Payments Pages and Payment Links
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PageDisplayOptions {
+    /// The language the page is displayed in (default: `en-GB`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+
+    /// The payment methods offered on the page. All methods enabled on your
+    /// account are offered if not specified
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_payment_methods: Option<Vec<HostedPaymentMethod>>,
+
+    /// Whether to ask the customer for their billing address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect_billing_address: Option<bool>,
+
+    /// Whether to ask the customer for their shipping address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect_shipping_address: Option<bool>,
+
+    /// The branding applied to the page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub brand: Option<PageBrand>,
+}
+
+/// The receipt sent to the customer after a payment on a hosted page
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReceiptOptions {
+    /// Whether to email a receipt to the customer's email address
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub send_email: Option<bool>,
+
+    /// The language of the receipt (default: the locale of the page)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+}
+
+/// The branding applied to a hosted page
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PageBrand {
+    /// The primary brand color as a hex code (e.g. `#186AFF`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+
+    /// The identifier of an uploaded logo file (format: `file_*`) or an
+    /// HTTPS URL of the logo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logo: Option<String>,
+}
+
+/// The languages a hosted page can be displayed in
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    #[serde(rename = "ar")]
+    Arabic,
+    #[serde(rename = "da-DK")]
+    Danish,
+    #[serde(rename = "de-DE")]
+    German,
+    #[serde(rename = "el")]
+    Greek,
+    #[serde(rename = "en-GB")]
+    English,
+    #[serde(rename = "es-ES")]
+    Spanish,
+    #[serde(rename = "fi-FI")]
+    Finnish,
+    #[serde(rename = "fr-FR")]
+    French,
+    #[serde(rename = "it-IT")]
+    Italian,
+    #[serde(rename = "ja-JP")]
+    Japanese,
+    #[serde(rename = "nb-NO")]
+    Norwegian,
+    #[serde(rename = "nl-NL")]
+    Dutch,
+    #[serde(rename = "pt-PT")]
+    Portuguese,
+    #[serde(rename = "sv-SE")]
+    Swedish,
+    #[serde(rename = "zh-CN")]
+    ChineseSimplified,
+    #[serde(rename = "zh-TW")]
+    ChineseTraditional,
+}
+
+/// The payment methods that can be offered on a hosted page
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HostedPaymentMethod {
+    Card,
+    ApplePay,
+    GooglePay,
+    PayPal,
+    Klarna,
+    Sofort,
+    Ideal,
+    Giropay,
+    Bancontact,
+    Eps,
+    P24,
+    Knet,
+}
+
+/// Response to create a Hosted Payments Page session or a Payment Link
+#[derive(Deserialize, Debug, Clone)]
+pub struct HostedPageResponse {
+    /// The unique identifier of the session or link (format: `hpp_*` or
+    /// `pl_*`)
+    pub id: String,
+
+    /// Your reference for the payment
+    pub reference: Option<String>,
+
+    /// The date/time the page expires
+    pub expires_on: Option<String>,
+
+    /// The links related to the page
+    ///
+    /// - Required: `"redirect"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// Response to create a Hosted Payments Page session
+pub type CreateHostedPaymentResponse = HostedPageResponse;
+
+/// Response to create a Payment Link
+pub type CreatePaymentLinkResponse = HostedPageResponse;
@@ -0,0 +1,258 @@
+//! Payments and their actions: requests, responses, captures, refunds and
+//! voids, and the card, 3D Secure and risk details they carry
+//!
+//! See: [Payments](https://docs.checkout.com/payments)
+
+mod actions;
+mod aft;
+mod borrowed;
+mod captures;
+mod dlocal;
+mod dunning;
+mod financial;
+mod hosted;
+mod presets;
+mod recurring;
+mod risk;
+mod sca;
+mod sessions;
+mod statement;
+mod surcharge;
+mod timeline;
+mod udf;
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::ParseVariantError;
+
+pub use crate::types::{
+    _3DSRequest, _3dsAuthenticationStatus, _3dsEnrollmentStatus, _3dsFlowType, _3dsStatus,
+    ActionId, Address, Amount, AmountError, BillingDescriptor, CapturePaymentBody,
+    CapturePaymentResponse, CaptureScheduleError, CardCategory, CardMetadataFormat,
+    CardMetadataRequest, CardMetadataResponse, CardMetadataSource, CardScheme, CardType,
+    CreatePaymentRequest, CreatePaymentResponse, Currency, CustomerDescriptor, CustomerInfo, Cvv,
+    DeclineReason, Eci, ExtraFields, FinancialActionFilter, GetPaymentActionsResponse,
+    GetPaymentDetailsResponse, InvalidReferenceError, ItemType, MerchantAdviceCode, Metadata,
+    Money, PaymentAmount, PaymentBalances, PaymentDetails, PaymentId, PaymentItem,
+    PaymentProcessed, PaymentProcessedDestination, PaymentProcessedSource,
+    PaymentProcessingDescriptor, PaymentProcessingInfo, PaymentRecipient,
+    PaymentRequestDestination, PaymentRequestSource, PaymentStatus, PaymentType, PendingPayment,
+    PendingReason, PhoneNumber, PreferredScheme, Reference, RefundPaymentBody,
+    RefundPaymentResponse, ResponseCode, RiskDecision, RiskRequest, RiskResults, RiskRule,
+    RoundingPolicy, SalesChannel, ScaExemption, ShippingDescriptor, StoreDescriptor,
+    ThreeDsRequest, VoidPaymentBody, VoidPaymentResponse, CURRENCY_LIST_VERSION,
+    MAX_REFERENCE_LENGTH,
+};
+pub use crate::{
+    AuthorizationHandle, CaptureFailure, CaptureOutcome, ChannelRouter, DeclineError, DeclineKind,
+    EventHook, PaymentEvent, ProcessingChannel, RoutedPayment, RoutingContext,
+};
+pub use actions::*;
+pub use aft::*;
+pub use borrowed::*;
+pub use captures::*;
+pub use dlocal::*;
+pub use dunning::*;
+pub use financial::*;
+pub use hosted::*;
+pub use recurring::*;
+pub use risk::*;
+pub use sca::*;
+pub use sessions::*;
+pub use surcharge::*;
+pub use timeline::*;
+pub use udf::*;
+
+/// A shortened summary of a payment action
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionSummary {
+    /// The unique identifier of the payment action (format: `act_*`)
+    pub id: String,
+
+    /// The type of action
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// The Gateway response code
+    pub response_code: String,
+
+    /// The Gateway response summary
+    pub response_summary: Option<String>,
+}
+
+impl ActionSummary {
+    /// Returns the type of the action, if it is a known type
+    #[must_use]
+    pub fn action_type(&self) -> Option<ActionType> {
+        self.ty.parse().ok()
+    }
+
+    /// Returns the classified Gateway response code
+    #[must_use]
+    pub fn code(&self) -> ResponseCode {
+        ResponseCode::from(self.response_code.as_str())
+    }
+
+    /// Returns whether the action is of the given type and was approved
+    #[must_use]
+    pub fn is_approved(&self, action_type: &ActionType) -> bool {
+        self.action_type().as_ref() == Some(action_type) && self.code().is_approved()
+    }
+}
+
+/// An action of a payment, as returned by `GET /payments/{id}/actions`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Action {
+    /// The unique identifier of the payment action (format: `act_*`)
+    id: String,
+
+    /// The type of action
+    #[serde(rename = "type")]
+    ty: String,
+
+    /// The date/time the action was processed
+    processed_on: String,
+
+    /// The action amount
+    amount: Amount,
+
+    /// Whether the action was successful
+    approved: Option<bool>,
+
+    /// The acquirer authorization code for cards
+    auth_code: Option<String>,
+
+    /// The Gateway response code
+    response_code: String,
+
+    /// The Gateway response summary
+    response_summary: Option<String>,
+
+    /// Your reference for the action
+    reference: Option<String>,
+
+    /// Returns information related to the processing of the payment
+    processing: Option<ActionProcessingInfo>,
+
+    /// The final Electronic Commerce Indicator (ECI) security level of the
+    /// action, if any
+    eci: Option<Eci>,
+
+    /// The scheme transaction identifier of the action, if any
+    scheme_id: Option<String>,
+
+    /// A set of key-value pairs that you can attach to an action
+    metadata: Metadata,
+}
+
+impl Action {
+    /// Returns the unique identifier of the payment action (format: `act_*`)
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Returns the final Electronic Commerce Indicator (ECI) security level
+    /// of the action, if any
+    #[must_use]
+    pub fn eci(&self) -> Option<&Eci> {
+        self.eci.as_ref()
+    }
+
+    /// Returns the scheme transaction identifier of the action, if any. Store
+    /// it for stored-credential compliance.
+    #[must_use]
+    pub fn scheme_id(&self) -> Option<&str> {
+        self.scheme_id.as_deref()
+    }
+}
+
+/// Information related to the processing of an action
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionProcessingInfo {
+    /// A unique identifier for the authorization that is submitted to the card
+    /// scheme during processing
+    pub retrieval_reference_number: Option<String>,
+
+    /// A unique identifier for the capture that is submitted to the card
+    /// scheme during processing
+    pub acquirer_reference_number: Option<String>,
+
+    /// A unique identifier for the transaction generated by the acquirer
+    pub acquirer_transaction_id: Option<String>,
+
+    /// The scheme's advice on whether and when to retry a declined action
+    pub merchant_advice_code: Option<String>,
+}
+
+impl ActionProcessingInfo {
+    /// Returns the classified merchant advice code of a declined action
+    #[must_use]
+    pub fn merchant_advice(&self) -> Option<MerchantAdviceCode> {
+        self.merchant_advice_code
+            .as_deref()
+            .map(MerchantAdviceCode::from)
+    }
+}
+
+/// The type of an action
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ActionType {
+    /// An authorization of the payment amount
+    Authorization,
+
+    /// A zero-amount verification of a card
+    #[serde(rename = "Card Verification")]
+    CardVerification,
+
+    /// A void of an authorization
+    Void,
+
+    /// A capture of authorized funds
+    Capture,
+
+    /// A refund of captured funds
+    Refund,
+
+    /// A payout to a card or bank account
+    Payout,
+}
+
+impl ActionType {
+    /// Returns the action type as it is represented by the API
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionType::Authorization => "Authorization",
+            ActionType::CardVerification => "Card Verification",
+            ActionType::Void => "Void",
+            ActionType::Capture => "Capture",
+            ActionType::Refund => "Refund",
+            ActionType::Payout => "Payout",
+        }
+    }
+}
+
+impl fmt::Display for ActionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ActionType {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Authorization" => Ok(ActionType::Authorization),
+            "Card Verification" => Ok(ActionType::CardVerification),
+            "Void" => Ok(ActionType::Void),
+            "Capture" => Ok(ActionType::Capture),
+            "Refund" => Ok(ActionType::Refund),
+            "Payout" => Ok(ActionType::Payout),
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Payment requests for documented Checkout scenarios, with the field
+//! combinations each scenario requires
+//!
+//! See: [Requirements for stored payment details](https://docs.checkout.com/payments/store-payment-details/requirements-for-stored-payment-details)
+
+use super::{StoredChargeOptions, StoredPaymentSource};
+use crate::types::{
+    CreatePaymentRequest, Currency, Money, PaymentAmount, PaymentRequestSource, PaymentType,
+    ThreeDsRequest,
+};
+
+impl CreatePaymentRequest {
+    /// Creates a customer-initiated payment with a stored instrument (format:
+    /// `src_*`), e.g. a one-click checkout of a returning customer
+    #[must_use]
+    pub fn one_click_payment(instrument: impl Into<String>, money: Money) -> CreatePaymentRequest {
+        preset(
+            PaymentRequestSource::Id {
+                id: instrument.into(),
+                cvv: None,
+            },
+            PaymentAmount::Charge(money),
+            PaymentType::Regular,
+        )
+    }
+
+    /// Creates a Mail Order / Telephone Order payment with card details
+    /// taken by an agent. MOTO payments are out of scope of strong customer
+    /// authentication, so 3D Secure is disabled.
+    #[must_use]
+    pub fn moto_payment(card: PaymentRequestSource, money: Money) -> CreatePaymentRequest {
+        CreatePaymentRequest {
+            three_ds: Some(ThreeDsRequest {
+                enabled: Some(false),
+                attempt_n3d: None,
+                sci: None,
+                cryptogram: None,
+                xid: None,
+                version: None,
+                exemption: None,
+            }),
+            ..preset(card, PaymentAmount::Charge(money), PaymentType::Moto)
+        }
+    }
+
+    /// Creates a card verification, which checks the card with the issuer
+    /// without charging it, e.g. before storing it for later payments
+    #[must_use]
+    pub fn account_verification(
+        card: PaymentRequestSource,
+        currency: Currency,
+    ) -> CreatePaymentRequest {
+        preset(card, PaymentAmount::Verify(currency), PaymentType::Regular)
+    }
+
+    /// Creates a merchant-initiated payment with a stored instrument (format:
+    /// `src_*`) at a time that is not fixed in advance, e.g. an automatic
+    /// top-up. `previous_payment_id` is the scheme transaction identifier of
+    /// the customer-initiated payment the instrument was stored with.
+    #[must_use]
+    pub fn unscheduled_mit(
+        instrument: impl Into<String>,
+        money: Money,
+        previous_payment_id: impl Into<String>,
+    ) -> CreatePaymentRequest {
+        CreatePaymentRequest::merchant_initiated(
+            StoredPaymentSource::Instrument(instrument.into()),
+            money,
+            StoredChargeOptions {
+                payment_type: PaymentType::Unscheduled,
+                previous_payment_id: Some(previous_payment_id.into()),
+                ..StoredChargeOptions::default()
+            },
+        )
+    }
+}
+
+/// Creates a customer-initiated payment request with only the source, amount
+/// and payment type set
+fn preset(
+    source: PaymentRequestSource,
+    amount: PaymentAmount,
+    payment_type: PaymentType,
+) -> CreatePaymentRequest {
+    CreatePaymentRequest {
+        source: Some(source),
+        destination: None,
+        amount,
+        payment_type,
+        merchant_initiated: false,
+        reference: None,
+        description: None,
+        capture: None,
+        capture_on: None,
+        customer: None,
+        billing_descriptor: None,
+        shipping: None,
+        three_ds: None,
+        previous_payment_id: None,
+        risk: None,
+        success_url: None,
+        failure_url: None,
+        payment_ip: None,
+        sender: None,
+        recipient: None,
+        processing_channel_id: None,
+        processing: None,
+        store: None,
+        items: None,
+        metadata: None,
+        extra: None,
+    }
+}
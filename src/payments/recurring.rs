@@ -0,0 +1,118 @@
+//! Merchant-initiated transactions (MITs) charged to stored payment details
+//!
+//! See: [Requirements for stored payment details](https://docs.checkout.com/payments/store-payment-details/requirements-for-stored-payment-details)
+
+use crate::types::{
+    CreatePaymentRequest, CreatePaymentResponse, Metadata, Money, PaymentAmount,
+    PaymentRequestSource, PaymentType,
+};
+
+/// The stored payment details to charge
+#[derive(Debug, Clone)]
+pub enum StoredPaymentSource {
+    /// A stored instrument (format: `src_*`)
+    Instrument(String),
+
+    /// The default instrument of a customer (format: `cus_*`)
+    Customer(String),
+}
+
+impl From<StoredPaymentSource> for PaymentRequestSource {
+    fn from(source: StoredPaymentSource) -> PaymentRequestSource {
+        match source {
+            StoredPaymentSource::Instrument(id) => PaymentRequestSource::Id { id, cvv: None },
+            StoredPaymentSource::Customer(id) => PaymentRequestSource::Customer { id },
+        }
+    }
+}
+
+/// Options for charging stored payment details
+#[derive(Debug, Clone)]
+pub struct StoredChargeOptions {
+    /// The type of the payment series (default: Recurring)
+    pub payment_type: PaymentType,
+
+    /// The scheme transaction identifier (or payment identifier) of the
+    /// previous payment in the series, as returned by
+    /// [`CreatePaymentResponse::scheme_id`]
+    pub previous_payment_id: Option<String>,
+
+    /// A reference you can later use to identify this payment (<= 50
+    /// characters)
+    pub reference: Option<String>,
+
+    /// A description of the payment (<= 100 characters)
+    pub description: Option<String>,
+
+    /// Whether to capture the payment (default: true)
+    pub capture: Option<bool>,
+
+    /// Allows you to store additional information about a transaction
+    pub metadata: Option<Metadata>,
+}
+
+impl Default for StoredChargeOptions {
+    fn default() -> StoredChargeOptions {
+        StoredChargeOptions {
+            payment_type: PaymentType::Recurring,
+            previous_payment_id: None,
+            reference: None,
+            description: None,
+            capture: None,
+            metadata: None,
+        }
+    }
+}
+
+impl CreatePaymentRequest {
+    /// Creates a merchant-initiated payment request charging stored payment
+    /// details
+    #[must_use]
+    pub fn merchant_initiated(
+        source: StoredPaymentSource,
+        money: Money,
+        options: StoredChargeOptions,
+    ) -> CreatePaymentRequest {
+        CreatePaymentRequest {
+            source: Some(source.into()),
+            destination: None,
+            amount: PaymentAmount::Charge(money),
+            payment_type: options.payment_type,
+            merchant_initiated: true,
+            reference: options.reference,
+            description: options.description,
+            capture: options.capture,
+            capture_on: None,
+            customer: None,
+            billing_descriptor: None,
+            shipping: None,
+            three_ds: None,
+            previous_payment_id: options.previous_payment_id,
+            risk: None,
+            success_url: None,
+            failure_url: None,
+            payment_ip: None,
+            sender: None,
+            recipient: None,
+            processing_channel_id: None,
+            processing: None,
+            store: None,
+            items: None,
+            metadata: options.metadata,
+            extra: None,
+        }
+    }
+}
+
+impl CreatePaymentResponse {
+    /// Returns the scheme transaction identifier of a processed payment. Store
+    /// it to use as the `previous_payment_id` of the next payment in the
+    /// series.
+    #[must_use]
+    pub fn scheme_id(&self) -> Option<&str> {
+        match self {
+            CreatePaymentResponse::Processed(payment) => payment.scheme_id.as_deref(),
+            CreatePaymentResponse::Pending(_) => None,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Types for the risk-related webhook notifications of payments, e.g. to feed
+//! a manual review queue
+//!
+//! See: [Risk management](https://docs.checkout.com/risk-management)
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    ActionId, Amount, Currency, ExtraFields, Metadata, ParseVariantError, PaymentDetails,
+    PaymentId, RiskResults, RiskRule,
+};
+
+/// The type of a risk-related payment webhook notification
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskEventType {
+    /// The payment was flagged by a risk rule and should be reviewed
+    PaymentFlagged,
+
+    /// The payment was declined, e.g. by a risk rule
+    PaymentDeclined,
+
+    /// A capture of the payment was declined
+    PaymentCaptureDeclined,
+}
+
+impl FromStr for RiskEventType {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "payment_flagged" => Ok(RiskEventType::PaymentFlagged),
+            "payment_declined" => Ok(RiskEventType::PaymentDeclined),
+            "payment_capture_declined" => Ok(RiskEventType::PaymentCaptureDeclined),
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
+}
+
+/// The data of a risk-related payment webhook notification
+#[derive(Deserialize, Debug, Clone)]
+pub struct RiskEventData {
+    /// The payment's unique identifier
+    pub id: PaymentId,
+
+    /// The unique identifier of the action that triggered the notification,
+    /// if any
+    pub action_id: Option<ActionId>,
+
+    /// The amount of the payment or action
+    pub amount: Option<Amount>,
+
+    /// The three-letter ISO currency code of the amount
+    pub currency: Option<Currency>,
+
+    /// Your reference for the payment
+    pub reference: Option<String>,
+
+    /// The Gateway response code
+    pub response_code: Option<String>,
+
+    /// The Gateway response summary
+    pub response_summary: Option<String>,
+
+    /// The payment's risk assessment results
+    pub risk: Option<RiskResults>,
+
+    /// The metadata attached to the payment
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields returned that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+impl RiskEventData {
+    /// Returns whether the notification is for the given payment
+    #[must_use]
+    pub fn is_for(&self, payment: &PaymentDetails) -> bool {
+        self.id.as_str() == payment.id
+    }
+
+    /// Returns the risk rules triggered by the payment, if reported
+    #[must_use]
+    pub fn triggered_rules(&self) -> &[RiskRule] {
+        self.risk
+            .as_ref()
+            .map_or(&[], |risk| risk.triggered_rules.as_slice())
+    }
+}
+
+/// A risk-related payment webhook notification
+pub type RiskWebhookEvent = crate::WebhookEvent<RiskEventData>;
+
+impl RiskWebhookEvent {
+    /// Returns the type of the event, if it is a risk-related event
+    #[must_use]
+    pub fn risk_event_type(&self) -> Option<RiskEventType> {
+        self.ty.parse().ok()
+    }
+
+    /// Returns the identifier of the payment the event is for, e.g. to fetch
+    /// its details with [`Client::get_payment_details`](crate::Client::get_payment_details)
+    #[must_use]
+    pub fn payment_id(&self) -> &PaymentId {
+        &self.data.id
+    }
+
+    /// Returns whether the payment needs a manual review before the order is
+    /// fulfilled
+    #[must_use]
+    pub fn requires_review(&self) -> bool {
+        self.risk_event_type() == Some(RiskEventType::PaymentFlagged)
+            || self
+                .data
+                .risk
+                .as_ref()
+                .is_some_and(RiskResults::requires_review)
+    }
+}
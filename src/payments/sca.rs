@@ -0,0 +1,100 @@
+//! The outcome of Strong Customer Authentication (SCA) for a payment
+//!
+//! The exemption requested in [`_3DSRequest::exemption`] is a request: the
+//! card scheme or issuer decides which exemption, if any, is applied. A
+//! [`ScaOutcome`] puts both side by side, e.g. to measure the acceptance rate
+//! of each exemption type.
+//!
+//! See: [SCA compliance guide](https://docs.checkout.com/risk-management/sca-compliance-guide)
+
+use crate::types::{
+    _3dsAuthenticationStatus, _3dsFlowType, _3dsStatus, PaymentDetails, PaymentProcessed,
+    ScaExemption,
+};
+
+/// The value of `exemption_applied` when no exemption was applied
+const NO_EXEMPTION: &str = "none";
+
+/// How a payment was authenticated and which exemption was applied
+#[derive(Debug, Clone)]
+pub struct ScaOutcome {
+    /// The exemption requested for the payment
+    pub requested_exemption: Option<ScaExemption>,
+
+    /// The exemption the card scheme or issuer applied. Values that are not
+    /// modeled by this library are reported as [`ScaExemption::Other`].
+    pub applied_exemption: Option<ScaExemption>,
+
+    /// Whether the cardholder was challenged, if known
+    pub challenged: Option<bool>,
+
+    /// The 3D Secure 2 authentication flow, if known
+    pub flow_type: Option<_3dsFlowType>,
+
+    /// Whether the cardholder was authenticated, if authentication was
+    /// attempted
+    pub authentication: Option<_3dsAuthenticationStatus>,
+
+    /// Whether the payment was downgraded to non-3D Secure
+    pub downgraded: bool,
+}
+
+impl ScaOutcome {
+    /// Returns whether the requested exemption was applied, or `None` if no
+    /// exemption was requested
+    #[must_use]
+    pub fn exemption_honored(&self) -> Option<bool> {
+        let requested = self.requested_exemption?;
+        Some(self.applied_exemption == Some(requested))
+    }
+
+    /// Returns whether the payment was exempted from authentication, with
+    /// the requested or another exemption
+    #[must_use]
+    pub fn is_exempted(&self) -> bool {
+        self.applied_exemption.is_some()
+    }
+}
+
+impl _3dsStatus {
+    /// Returns the exemption the card scheme or issuer applied, if any
+    #[must_use]
+    pub fn applied_exemption(&self) -> Option<ScaExemption> {
+        match self.exemption_applied.as_deref()?.trim() {
+            "" | NO_EXEMPTION => None,
+            applied => Some(applied.parse().unwrap_or(ScaExemption::Other)),
+        }
+    }
+
+    /// Returns the requested and applied exemptions and the authentication
+    /// result of the payment
+    #[must_use]
+    pub fn sca_outcome(&self) -> ScaOutcome {
+        ScaOutcome {
+            requested_exemption: self.exemption,
+            applied_exemption: self.applied_exemption(),
+            challenged: self.challenged,
+            flow_type: self.flow_type,
+            authentication: self.authentication_response.clone(),
+            downgraded: self.downgraded,
+        }
+    }
+}
+
+impl PaymentProcessed {
+    /// Returns the SCA outcome of the payment, or `None` if it was not
+    /// processed with 3D Secure
+    #[must_use]
+    pub fn sca_outcome(&self) -> Option<ScaOutcome> {
+        self.three_ds.as_ref().map(_3dsStatus::sca_outcome)
+    }
+}
+
+impl PaymentDetails {
+    /// Returns the SCA outcome of the payment, or `None` if it was not
+    /// processed with 3D Secure
+    #[must_use]
+    pub fn sca_outcome(&self) -> Option<ScaOutcome> {
+        self.three_ds.as_ref().map(_3dsStatus::sca_outcome)
+    }
+}
@@ -0,0 +1,285 @@
+//! Types for 3D Secure authentication sessions and their webhook notifications
+//!
+//! See: [Standalone authentication](https://docs.checkout.com/payments/authenticate-payments/3d-secure/standalone-sessions)
+
+#![allow(missing_docs)]
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    links::Links, Amount, Currency, Eci, ExtraFields, ParseVariantError, PaymentAmount,
+    PaymentRequestSource,
+};
+
+/// The status of an authentication session
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Pending,
+    Processing,
+    Challenged,
+    ChallengeAbandoned,
+    Expired,
+    Approved,
+    Attempted,
+    Unavailable,
+    Declined,
+    Rejected,
+}
+
+impl SessionStatus {
+    /// Returns whether the session has reached a final status
+    #[must_use]
+    pub fn is_final(self) -> bool {
+        !matches!(
+            self,
+            SessionStatus::Pending | SessionStatus::Processing | SessionStatus::Challenged
+        )
+    }
+
+    /// Returns whether the cardholder was authenticated, so the payment can
+    /// proceed with the authentication result
+    #[must_use]
+    pub fn is_authenticated(self) -> bool {
+        matches!(self, SessionStatus::Approved | SessionStatus::Attempted)
+    }
+}
+
+/// The type of a session webhook notification
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionEventType {
+    AuthenticationStarted,
+    AuthenticationChallenged,
+    AuthenticationApproved,
+    AuthenticationAttempted,
+    AuthenticationFailed,
+    AuthenticationExpired,
+    AuthenticationCardNotEnrolled,
+}
+
+impl FromStr for SessionEventType {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "authentication_started" => Ok(SessionEventType::AuthenticationStarted),
+            "authentication_challenged" => Ok(SessionEventType::AuthenticationChallenged),
+            "authentication_approved" => Ok(SessionEventType::AuthenticationApproved),
+            "authentication_attempted" => Ok(SessionEventType::AuthenticationAttempted),
+            "authentication_failed" => Ok(SessionEventType::AuthenticationFailed),
+            "authentication_expired" => Ok(SessionEventType::AuthenticationExpired),
+            "authentication_card_not_enrolled" => {
+                Ok(SessionEventType::AuthenticationCardNotEnrolled)
+            }
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
+}
+
+/// The data of a session webhook notification
+#[derive(Deserialize, Debug, Clone)]
+pub struct SessionEventData {
+    /// The session's unique identifier (format: `sid_*`)
+    pub session_id: String,
+
+    /// The status of the session
+    pub status: SessionStatus,
+
+    /// A reason for the status, e.g. why the authentication was declined
+    pub status_reason: Option<String>,
+
+    /// Your reference for the session
+    pub reference: Option<String>,
+
+    /// The amount being authenticated
+    pub amount: Option<Amount>,
+
+    /// The three-letter ISO currency code of the amount
+    pub currency: Option<Currency>,
+
+    /// The 3D Secure protocol version used, e.g. `"2.2.0"`
+    pub protocol_version: Option<String>,
+
+    /// The Electronic Commerce Indicator (ECI) of the authentication
+    pub eci: Option<Eci>,
+
+    /// Whether an exemption was applied to the authentication
+    pub exemption_applied: Option<String>,
+
+    /// The date/time the session was created
+    pub created_on: Option<DateTime<Utc>>,
+
+    /// Additional fields returned that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
+
+/// A session webhook notification
+pub type SessionWebhookEvent = crate::WebhookEvent<SessionEventData>;
+
+impl SessionWebhookEvent {
+    /// Returns the type of the event, if it is a session event
+    #[must_use]
+    pub fn session_event_type(&self) -> Option<SessionEventType> {
+        self.ty.parse().ok()
+    }
+}
+
+/// Request body to create an authentication session
+#[derive(Serialize, Debug, Clone)]
+pub struct CreateSessionRequest {
+    /// The card to authenticate
+    pub source: PaymentRequestSource,
+
+    /// The amount and currency of the payment to authenticate
+    #[serde(flatten)]
+    pub amount: PaymentAmount,
+
+    /// Your reference for the session (<= 50 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    /// How the session is completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion: Option<SessionCompletion>,
+
+    /// Information about the channel the authentication is performed in
+    pub channel_data: ChannelData,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// How an authentication session is completed
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionCompletion {
+    /// The challenge is displayed by your app or page, e.g. with the 3DS SDK
+    NonHosted {
+        /// The URL notified once the session is complete (<= 256
+        /// characters)
+        #[serde(skip_serializing_if = "Option::is_none")]
+        callback_url: Option<String>,
+    },
+
+    /// The customer is redirected to a page hosted by Checkout
+    Hosted {
+        /// The URL the customer is redirected to after a successful
+        /// authentication (<= 256 characters)
+        success_url: String,
+
+        /// The URL the customer is redirected to after a failed
+        /// authentication (<= 256 characters)
+        failure_url: String,
+    },
+}
+
+/// Information about the channel an authentication is performed in
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "channel", rename_all = "snake_case")]
+pub enum ChannelData {
+    /// A native mobile app using a 3DS2 SDK. The values are provided by the
+    /// SDK when it creates the authentication request parameters.
+    App {
+        /// The unique identifier of the app installation (36 characters)
+        sdk_app_id: String,
+
+        /// How long the cardholder has to complete the challenge, in minutes
+        /// (>= 5)
+        sdk_max_timeout: u32,
+
+        /// The public key of the SDK's ephemeral key pair
+        sdk_ephemeral_public_key: SdkEphemeralPublicKey,
+
+        /// The identifier of the SDK assigned by `EMVCo` (<= 32 characters)
+        sdk_reference_number: String,
+
+        /// The device data encrypted by the SDK (JWE)
+        sdk_encrypted_data: String,
+
+        /// The identifier of the transaction assigned by the SDK (36
+        /// characters)
+        sdk_transaction_id: String,
+
+        /// The challenge interfaces the SDK supports
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sdk_interface_type: Option<SdkInterfaceType>,
+
+        /// The challenge UI elements the SDK supports
+        #[serde(skip_serializing_if = "Option::is_none")]
+        sdk_ui_elements: Option<Vec<SdkUiElement>>,
+    },
+}
+
+/// The public key of a 3DS2 SDK's ephemeral key pair, as a JSON Web Key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SdkEphemeralPublicKey {
+    /// The key type, e.g. `"EC"`
+    pub kty: String,
+
+    /// The curve, e.g. `"P-256"`
+    pub crv: String,
+
+    /// The x coordinate of the key
+    pub x: String,
+
+    /// The y coordinate of the key
+    pub y: String,
+}
+
+/// The challenge interfaces a 3DS2 SDK supports
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SdkInterfaceType {
+    Native,
+    Html,
+    Both,
+}
+
+/// A challenge UI element a 3DS2 SDK supports
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SdkUiElement {
+    Text,
+    SingleSelect,
+    MultiSelect,
+    Oob,
+    HtmlOther,
+}
+
+/// Response to create an authentication session
+#[derive(Deserialize, Debug, Clone)]
+pub struct Session {
+    /// The session's unique identifier (format: `sid_*`)
+    pub id: String,
+
+    /// The secret the 3DS2 SDK uses to complete the session in the app
+    pub session_secret: Option<SecretString>,
+
+    /// The status of the session
+    pub status: SessionStatus,
+
+    /// The identifier of the transaction with the 3DS server
+    pub transaction_id: Option<String>,
+
+    /// The 3D Secure protocol version used, e.g. `"2.2.0"`
+    pub protocol_version: Option<String>,
+
+    /// The actions required to complete the session, e.g. `"issuer_fingerprint"`
+    #[serde(default)]
+    pub next_actions: Vec<String>,
+
+    /// The links related to the session
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+
+    /// Additional fields returned that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: ExtraFields,
+}
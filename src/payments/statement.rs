@@ -0,0 +1,52 @@
+//! Previews of how billing descriptors appear on cardholder statements
+
+use crate::types::{BillingDescriptor, CardScheme};
+
+/// The maximum length of the city shown on a statement
+const CITY_LENGTH: usize = 13;
+
+impl CardScheme {
+    /// Returns the maximum length of the merchant name shown on a statement.
+    /// Longer names are truncated by the scheme.
+    #[must_use]
+    pub fn descriptor_name_length(&self) -> usize {
+        match self {
+            CardScheme::Amex => 20,
+            CardScheme::Discover | CardScheme::Diners => 23,
+            _ => 25,
+        }
+    }
+}
+
+impl BillingDescriptor {
+    /// Renders the descriptor as issuers display it on statements of cards of
+    /// the given scheme: upper-cased, restricted to printable ASCII, with the
+    /// name padded or truncated to the scheme's name length and the city
+    /// truncated to 13 characters.
+    ///
+    /// Issuers may apply further formatting, so the preview is indicative.
+    #[must_use]
+    pub fn statement_preview(&self, scheme: &CardScheme) -> String {
+        let name = statement_text(&self.name, scheme.descriptor_name_length());
+        let city = statement_text(&self.city, CITY_LENGTH);
+
+        format!(
+            "{:width$}{}",
+            name,
+            city,
+            width = scheme.descriptor_name_length()
+        )
+        .trim_end()
+        .to_string()
+    }
+}
+
+fn statement_text(text: &str, length: usize) -> String {
+    text.chars()
+        .filter(|c| c.is_ascii() && !c.is_ascii_control())
+        .map(|c| c.to_ascii_uppercase())
+        .take(length)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
@@ -0,0 +1,214 @@
+//! Surcharges computed from card metadata
+//!
+//! Whether a card may be surcharged and by how much depends on the card and
+//! where it was issued: surcharging consumer cards issued in the EEA and UK
+//! is prohibited, and card schemes cap surcharge rates elsewhere. A
+//! [`SurchargePolicy`] encodes these rules once, and is applied to the
+//! [`CardMetadataResponse`] of the card before the payment is requested.
+//!
+//! See: [Card metadata](https://docs.checkout.com/payments/card-metadata)
+
+use std::convert::TryFrom;
+
+use crate::types::{
+    Amount, CardCategory, CardMetadataResponse, CardScheme, CardType, ItemType, Money, PaymentItem,
+};
+
+/// The issuer countries of the EEA and the UK, where surcharging consumer
+/// cards is prohibited
+pub const EEA_AND_UK: &[&str] = &[
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GB", "GR", "HR", "HU", "IE",
+    "IS", "IT", "LI", "LT", "LU", "LV", "MT", "NL", "NO", "PL", "PT", "RO", "SE", "SI", "SK",
+];
+
+/// A surcharge for the cards that match all of the rule's conditions
+#[derive(Debug, Clone, Default)]
+pub struct SurchargeRule {
+    rate_basis_points: u32,
+    fixed: u64,
+    card_type: Option<CardType>,
+    card_category: Option<CardCategory>,
+    scheme: Option<CardScheme>,
+}
+
+impl SurchargeRule {
+    /// Creates a rule that surcharges every card by a rate in basis points,
+    /// e.g. `150` for 1.5%
+    #[must_use]
+    pub fn new(rate_basis_points: u32) -> SurchargeRule {
+        SurchargeRule {
+            rate_basis_points,
+            ..SurchargeRule::default()
+        }
+    }
+
+    /// Adds a fixed surcharge in the currency's minor units
+    #[must_use]
+    pub fn fixed(mut self, minor_units: u64) -> Self {
+        self.fixed = minor_units;
+        self
+    }
+
+    /// Only applies the rule to cards of the type
+    #[must_use]
+    pub fn card_type(mut self, card_type: CardType) -> Self {
+        self.card_type = Some(card_type);
+        self
+    }
+
+    /// Only applies the rule to cards of the category
+    #[must_use]
+    pub fn card_category(mut self, card_category: CardCategory) -> Self {
+        self.card_category = Some(card_category);
+        self
+    }
+
+    /// Only applies the rule to cards of the scheme
+    #[must_use]
+    pub fn scheme(mut self, scheme: CardScheme) -> Self {
+        self.scheme = Some(scheme);
+        self
+    }
+
+    fn matches(&self, card: &CardMetadataResponse) -> bool {
+        condition_matches(self.card_type, card.card_type)
+            && condition_matches(self.card_category.as_ref(), card.card_category.as_ref())
+            && condition_matches(self.scheme.as_ref(), card.scheme.as_ref())
+    }
+}
+
+/// Returns whether a rule's condition is unset or equal to the card's value
+fn condition_matches<T: PartialEq>(expected: Option<T>, actual: Option<T>) -> bool {
+    expected.is_none_or(|expected| actual.is_some_and(|actual| actual == expected))
+}
+
+/// A surcharge and the payment amount it was added to
+#[derive(Debug, Clone)]
+pub struct Surcharge {
+    /// The surcharge
+    pub amount: Amount,
+
+    /// The payment amount including the surcharge
+    pub total: Money,
+
+    /// The line item documenting the surcharge, to add to the payment's
+    /// `items`
+    pub item: PaymentItem,
+}
+
+/// The rules that determine the surcharge of a card
+///
+/// # Example
+///
+/// ```ignore
+/// let policy = SurchargePolicy::new()
+///     .prohibit_consumer_cards_from(EEA_AND_UK)
+///     .max_rate(300)
+///     .rule(SurchargeRule::new(250).card_type(CardType::Credit).scheme(CardScheme::Amex))
+///     .rule(SurchargeRule::new(150).card_type(CardType::Credit));
+///
+/// let card = client.get_card_metadata(&lookup).await?;
+/// if let Some(surcharge) = policy.surcharge(&card, money) {
+///     request.amount = PaymentAmount::Charge(surcharge.total);
+///     request.items.get_or_insert_with(Vec::new).push(surcharge.item);
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SurchargePolicy {
+    rules: Vec<SurchargeRule>,
+    prohibited_countries: Vec<String>,
+    max_rate_basis_points: Option<u32>,
+}
+
+impl SurchargePolicy {
+    /// Creates a policy that doesn't surcharge any card
+    #[must_use]
+    pub fn new() -> SurchargePolicy {
+        SurchargePolicy::default()
+    }
+
+    /// Adds a rule. The first rule that matches a card determines its
+    /// surcharge.
+    #[must_use]
+    pub fn rule(mut self, rule: SurchargeRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Never surcharges consumer cards issued in the countries (two-letter
+    /// ISO codes), e.g. [`EEA_AND_UK`]. Cards whose issuer country is
+    /// unknown are not surcharged either.
+    #[must_use]
+    pub fn prohibit_consumer_cards_from(mut self, countries: &[&str]) -> Self {
+        self.prohibited_countries
+            .extend(countries.iter().map(|country| (*country).to_string()));
+        self
+    }
+
+    /// Caps the rate of a surcharge, excluding its fixed part, in basis
+    /// points, e.g. `300` for 3%
+    #[must_use]
+    pub fn max_rate(mut self, basis_points: u32) -> Self {
+        self.max_rate_basis_points = Some(basis_points);
+        self
+    }
+
+    /// Returns whether the card may be surcharged
+    #[must_use]
+    pub fn allows(&self, card: &CardMetadataResponse) -> bool {
+        if self.prohibited_countries.is_empty() {
+            return true;
+        }
+
+        let Some(issuer_country) = &card.issuer_country else {
+            return false;
+        };
+        card.card_category == Some(CardCategory::Commercial)
+            || !self
+                .prohibited_countries
+                .iter()
+                .any(|country| country.eq_ignore_ascii_case(issuer_country))
+    }
+
+    /// Returns the surcharge of a payment with the card, or `None` if the
+    /// card may not or need not be surcharged
+    #[must_use]
+    pub fn surcharge(&self, card: &CardMetadataResponse, money: Money) -> Option<Surcharge> {
+        if !self.allows(card) {
+            return None;
+        }
+        let rule = self.rules.iter().find(|rule| rule.matches(card))?;
+
+        let rate = match self.max_rate_basis_points {
+            Some(max) => rule.rate_basis_points.min(max),
+            None => rule.rate_basis_points,
+        };
+        // Rounded half up to the currency's minor units
+        let variable = (u128::from(money.amount.minor_units()) * u128::from(rate) + 5_000) / 10_000;
+        let surcharge = u64::try_from(variable).ok()?.checked_add(rule.fixed)?;
+        if surcharge == 0 {
+            return None;
+        }
+
+        let amount = Amount::from_minor_units(surcharge);
+        Some(Surcharge {
+            amount,
+            total: Money {
+                amount: Amount::from_minor_units(
+                    money.amount.minor_units().checked_add(surcharge)?,
+                ),
+                currency: money.currency,
+            },
+            item: PaymentItem {
+                ty: Some(ItemType::Surcharge),
+                name: "Card surcharge".to_string(),
+                quantity: 1,
+                unit_price: amount,
+                reference: None,
+                total_amount: Some(amount),
+                tax_amount: None,
+                discount_amount: None,
+            },
+        })
+    }
+}
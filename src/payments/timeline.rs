@@ -0,0 +1,185 @@
+//! A chronological view of a payment's actions with running balances
+
+use super::Action;
+use crate::types::{GetPaymentActionsResponse, PaymentDetails};
+
+/// A typed event in a payment's timeline
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimelineEvent {
+    /// The payment was authorized
+    Authorized {
+        /// The authorized amount in minor units
+        amount: u64,
+    },
+
+    /// The card was verified without an amount
+    CardVerified,
+
+    /// Part or all of the authorized amount was captured
+    Captured {
+        /// The captured amount in minor units
+        amount: u64,
+
+        /// Whether less than the authorized amount has been captured so far
+        partial: bool,
+    },
+
+    /// Part or all of the captured amount was refunded
+    Refunded {
+        /// The refunded amount in minor units
+        amount: u64,
+
+        /// Whether less than the captured amount has been refunded so far
+        partial: bool,
+    },
+
+    /// The authorization was voided
+    Voided,
+
+    /// Funds were paid out to the destination
+    Payout {
+        /// The paid out amount in minor units
+        amount: u64,
+    },
+
+    /// An action type that is not modeled by this library
+    Other {
+        /// The type of the action
+        action_type: String,
+
+        /// The action amount in minor units
+        amount: u64,
+    },
+}
+
+/// An entry in a payment's timeline
+#[derive(Debug, Clone)]
+pub struct TimelineEntry {
+    /// The unique identifier of the payment action (format: `act_*`)
+    pub action_id: String,
+
+    /// The date/time the action was processed
+    pub processed_on: String,
+
+    /// Whether the action was successful. Declined actions do not affect the
+    /// running balances
+    pub approved: bool,
+
+    /// The Gateway response code
+    pub response_code: String,
+
+    /// The event represented by the action
+    pub event: TimelineEvent,
+
+    /// The total authorized amount after this action, in minor units
+    pub authorized: u64,
+
+    /// The total captured amount after this action, in minor units
+    pub captured: u64,
+
+    /// The total refunded amount after this action, in minor units
+    pub refunded: u64,
+}
+
+impl TimelineEntry {
+    /// The amount that has been captured and not refunded after this action,
+    /// in minor units
+    #[must_use]
+    pub fn net_captured(&self) -> u64 {
+        self.captured.saturating_sub(self.refunded)
+    }
+}
+
+/// A payment's details together with its actions in chronological order
+#[derive(Debug, Clone)]
+pub struct PaymentTimeline {
+    /// The details of the payment
+    pub payment: PaymentDetails,
+
+    /// The payment's actions, oldest first
+    pub entries: Vec<TimelineEntry>,
+}
+
+impl PaymentTimeline {
+    /// Builds the timeline of a payment from its details and actions
+    #[must_use]
+    pub fn new(payment: PaymentDetails, actions: GetPaymentActionsResponse) -> PaymentTimeline {
+        PaymentTimeline {
+            payment,
+            entries: timeline_entries(actions),
+        }
+    }
+
+    /// Returns the most recent entry of the timeline
+    #[must_use]
+    pub fn latest(&self) -> Option<&TimelineEntry> {
+        self.entries.last()
+    }
+}
+
+/// Orders actions oldest first and computes the running balances
+#[must_use]
+pub fn timeline_entries(mut actions: Vec<Action>) -> Vec<TimelineEntry> {
+    // Actions are returned latest first. Timestamps are ISO 8601 so they sort
+    // lexicographically; the sort is stable for actions processed at the same
+    // time.
+    actions.reverse();
+    actions.sort_by(|a, b| a.processed_on.cmp(&b.processed_on));
+
+    let mut authorized = 0;
+    let mut captured = 0;
+    let mut refunded = 0;
+
+    actions
+        .into_iter()
+        .map(|action| {
+            let approved = action.approved.unwrap_or(true);
+            let amount = action.amount.minor_units();
+
+            let event = match action.ty.as_str() {
+                "Authorization" => {
+                    if approved {
+                        authorized += amount;
+                    }
+                    TimelineEvent::Authorized { amount }
+                }
+                "Card Verification" => TimelineEvent::CardVerified,
+                "Capture" => {
+                    if approved {
+                        captured += amount;
+                    }
+                    TimelineEvent::Captured {
+                        amount,
+                        partial: captured < authorized,
+                    }
+                }
+                "Refund" => {
+                    if approved {
+                        refunded += amount;
+                    }
+                    TimelineEvent::Refunded {
+                        amount,
+                        partial: refunded < captured,
+                    }
+                }
+                "Void" => TimelineEvent::Voided,
+                "Payout" => TimelineEvent::Payout { amount },
+                other => TimelineEvent::Other {
+                    action_type: other.to_string(),
+                    amount,
+                },
+            };
+
+            TimelineEntry {
+                action_id: action.id,
+                processed_on: action.processed_on,
+                approved,
+                response_code: action.response_code,
+                event,
+                authorized,
+                captured,
+                refunded,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,159 @@
+//! The user-defined fields `udf1` to `udf5` of a payment's metadata
+//!
+//! Checkout reads five user-defined fields from the metadata of a payment,
+//! for reporting and, for `udf1`, in risk rules. [`UserDefinedFields`] names
+//! them explicitly so that a value can't end up under a misspelled key such
+//! as `udf_1` or `UDF1`, which Checkout would store as free-form metadata.
+//! [`UdfMetadata`] sets them on every request that has metadata.
+
+use super::{CreateHostedPaymentRequest, CreatePaymentLinkRequest};
+use crate::types::{CreatePaymentRequest, Metadata, PaymentDetails};
+
+/// The metadata keys of the user-defined fields, in order
+pub const UDF_KEYS: [&str; 5] = ["udf1", "udf2", "udf3", "udf4", "udf5"];
+
+/// The user-defined fields of a payment
+///
+/// They are sent in the payment's metadata under `udf1` to `udf5`, alongside
+/// any other metadata keys.
+///
+/// # Example
+///
+/// ```ignore
+/// use checkout::payments::UdfMetadata;
+///
+/// request.set_udfs(&UserDefinedFields {
+///     udf1: Some(customer.risk_segment.clone()),
+///     ..UserDefinedFields::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserDefinedFields {
+    /// Sent as `metadata.udf1`, which Checkout's risk rules can match on
+    pub udf1: Option<String>,
+
+    /// Sent as `metadata.udf2`
+    pub udf2: Option<String>,
+
+    /// Sent as `metadata.udf3`
+    pub udf3: Option<String>,
+
+    /// Sent as `metadata.udf4`
+    pub udf4: Option<String>,
+
+    /// Sent as `metadata.udf5`
+    pub udf5: Option<String>,
+}
+
+impl UserDefinedFields {
+    /// Reads the user-defined fields from metadata, ignoring other keys
+    #[must_use]
+    pub fn from_metadata(metadata: &Metadata) -> UserDefinedFields {
+        let get = |key: &str| metadata.get(key).cloned();
+        UserDefinedFields {
+            udf1: get(UDF_KEYS[0]),
+            udf2: get(UDF_KEYS[1]),
+            udf3: get(UDF_KEYS[2]),
+            udf4: get(UDF_KEYS[3]),
+            udf5: get(UDF_KEYS[4]),
+        }
+    }
+
+    /// Returns whether no field is set
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.fields().iter().all(|(_, value)| value.is_none())
+    }
+
+    /// Writes the fields into the metadata, replacing the values of their
+    /// keys. Fields that are `None` remove their keys, so that a value set
+    /// earlier is cleared. Other keys are kept.
+    pub fn write_to(&self, metadata: &mut Metadata) {
+        for (key, value) in self.fields() {
+            match value {
+                Some(value) => metadata.insert(key.to_string(), value.clone()),
+                None => metadata.remove(key),
+            };
+        }
+    }
+
+    fn fields(&self) -> [(&'static str, &Option<String>); 5] {
+        [
+            (UDF_KEYS[0], &self.udf1),
+            (UDF_KEYS[1], &self.udf2),
+            (UDF_KEYS[2], &self.udf3),
+            (UDF_KEYS[3], &self.udf4),
+            (UDF_KEYS[4], &self.udf5),
+        ]
+    }
+}
+
+/// A request whose metadata holds the user-defined fields
+///
+/// [`UdfMetadata::set_udfs`] replaces all five fields at once, see
+/// [`UserDefinedFields::write_to`].
+pub trait UdfMetadata {
+    /// Returns the metadata the fields are stored in
+    fn udf_metadata(&self) -> Option<&Metadata>;
+
+    /// Returns the metadata the fields are stored in, to modify it
+    fn udf_metadata_mut(&mut self) -> &mut Option<Metadata>;
+
+    /// Sets the user-defined fields in the metadata, keeping its other keys.
+    /// Fields that are `None` are removed, and no metadata is created if
+    /// none of the fields is set.
+    fn set_udfs(&mut self, udfs: &UserDefinedFields) {
+        let metadata = self.udf_metadata_mut();
+        if metadata.is_some() || !udfs.is_empty() {
+            udfs.write_to(metadata.get_or_insert_with(Metadata::new));
+        }
+    }
+
+    /// Returns the user-defined fields set in the metadata
+    fn udfs(&self) -> UserDefinedFields {
+        self.udf_metadata()
+            .map(UserDefinedFields::from_metadata)
+            .unwrap_or_default()
+    }
+}
+
+impl UdfMetadata for CreatePaymentRequest {
+    fn udf_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    fn udf_metadata_mut(&mut self) -> &mut Option<Metadata> {
+        &mut self.metadata
+    }
+}
+
+impl UdfMetadata for CreateHostedPaymentRequest {
+    fn udf_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    fn udf_metadata_mut(&mut self) -> &mut Option<Metadata> {
+        &mut self.metadata
+    }
+}
+
+impl UdfMetadata for CreatePaymentLinkRequest {
+    fn udf_metadata(&self) -> Option<&Metadata> {
+        self.metadata.as_ref()
+    }
+
+    fn udf_metadata_mut(&mut self) -> &mut Option<Metadata> {
+        &mut self.metadata
+    }
+}
+
+impl PaymentDetails {
+    /// Returns the user-defined fields set in the payment's metadata
+    #[must_use]
+    pub fn udfs(&self) -> UserDefinedFields {
+        self.metadata
+            .as_ref()
+            .map(UserDefinedFields::from_metadata)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,299 @@
+//! Payouts to cards and bank accounts, individually or in batches
+//!
+//! See: [Payouts](https://docs.checkout.com/payouts)
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    Address, Amount, BillingDescriptor, Currency, ExtraFields, Metadata, Money,
+    PaymentRequestDestination, ProcessingChannelId, Reference,
+};
+
+pub use crate::types::{
+    CardPayoutsEligibility, GetPayoutStatementsResponse, PayoutEligibility, PayoutStatement,
+    PayoutStatementFilter, StatementPayout,
+};
+pub use crate::{
+    BatchPayout, BatchReport, BatchStore, BatchValidationError, MemoryBatchStore, PayoutBatch,
+};
+
+/// Request body for a payout to a card
+#[derive(Serialize, Debug, Clone)]
+pub struct CardPayoutRequest {
+    /// The card to pay out to
+    pub destination: PaymentRequestDestination,
+
+    /// The payout amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code
+    pub currency: Currency,
+
+    /// A reference you can later use to identify this payout (<= 50
+    /// characters)
+    pub reference: Option<String>,
+
+    /// The sender of the payout
+    pub sender: Option<PayoutSender>,
+
+    /// Details about the payout instruction
+    pub instruction: Option<PayoutInstruction>,
+
+    /// An optional dynamic billing descriptor displayed on the account owner's
+    /// statement
+    pub billing_descriptor: Option<BillingDescriptor>,
+
+    /// Allows you to store additional information about a payout
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// The funds transfer type of a top-up of a prepaid or gift card
+pub const PREPAID_TOP_UP_FUNDS_TRANSFER_TYPE: &str = "TU";
+
+impl CardPayoutRequest {
+    /// Creates a payout that tops up a prepaid or gift card from your
+    /// business
+    ///
+    /// The payout is sent with the top-up funds transfer type and your
+    /// business as a corporate sender. `sender_reference` identifies the
+    /// account the funds come from in your systems, e.g. the customer's
+    /// wallet.
+    #[must_use]
+    pub fn prepaid_top_up(
+        destination: PaymentRequestDestination,
+        money: Money,
+        reference: Reference,
+        company_name: impl Into<String>,
+        company_address: Address,
+        sender_reference: impl Into<String>,
+    ) -> CardPayoutRequest {
+        CardPayoutRequest {
+            destination,
+            amount: money.amount,
+            currency: money.currency,
+            reference: Some(reference.into()),
+            sender: Some(PayoutSender::Corporate {
+                company_name: company_name.into(),
+                address: Some(company_address),
+                reference: Some(sender_reference.into()),
+            }),
+            instruction: Some(PayoutInstruction {
+                funds_transfer_type: Some(PREPAID_TOP_UP_FUNDS_TRANSFER_TYPE.to_string()),
+                ..PayoutInstruction::default()
+            }),
+            billing_descriptor: None,
+            metadata: None,
+            extra: None,
+        }
+    }
+}
+
+/// Request body for a payout to a bank account
+#[derive(Serialize, Debug, Clone)]
+pub struct BankPayoutRequest {
+    /// The currency account the payout is funded from
+    pub source: PayoutSource,
+
+    /// The bank account to pay out to
+    pub destination: BankPayoutDestination,
+
+    /// The payout amount
+    pub amount: Amount,
+
+    /// The three-letter ISO currency code
+    pub currency: Currency,
+
+    /// A reference you can later use to identify this payout (<= 50
+    /// characters)
+    pub reference: Option<String>,
+
+    /// The sender of the payout
+    pub sender: Option<PayoutSender>,
+
+    /// Details about the payout instruction
+    pub instruction: Option<PayoutInstruction>,
+
+    /// The processing channel to be used for the payout (format: `pc_*`)
+    pub processing_channel_id: Option<ProcessingChannelId>,
+
+    /// Allows you to store additional information about a payout
+    pub metadata: Option<Metadata>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// The source of a bank payout
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum PayoutSource {
+    /// A currency account of your entity
+    #[serde(rename = "currency_account")]
+    CurrencyAccount {
+        /// The currency account identifier (format: `ca_*`)
+        id: String,
+    },
+}
+
+/// The destination of a bank payout
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum BankPayoutDestination {
+    /// A bank account
+    #[serde(rename = "bank_account")]
+    BankAccount(Box<BankAccountDestination>),
+
+    /// A stored bank account instrument
+    #[serde(rename = "id")]
+    Id {
+        /// The instrument's unique identifier (format: `src_*`)
+        id: String,
+    },
+}
+
+/// The details of the bank account a payout is sent to
+#[derive(Serialize, Debug, Clone)]
+pub struct BankAccountDestination {
+    /// The type of the account
+    pub account_type: Option<BankAccountType>,
+
+    /// The account number
+    pub account_number: Option<String>,
+
+    /// The code that identifies the bank (e.g. a sort code or routing
+    /// number)
+    pub bank_code: Option<String>,
+
+    /// The code that identifies the bank's branch
+    pub branch_code: Option<String>,
+
+    /// The International Bank Account Number
+    pub iban: Option<String>,
+
+    /// The Basic Bank Account Number
+    pub bban: Option<String>,
+
+    /// The 8 or 11 character SWIFT/BIC code of the bank
+    pub swift_bic: Option<String>,
+
+    /// The two-letter ISO country code of the account (2 characters)
+    pub country: String,
+
+    /// The three-letter ISO currency code of the account
+    pub currency: Currency,
+
+    /// The holder of the account
+    pub account_holder: AccountHolder,
+}
+
+/// The type of a bank account
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BankAccountType {
+    /// A savings account
+    Savings,
+
+    /// A current (checking) account
+    Current,
+
+    /// A cash account
+    Cash,
+}
+
+/// The holder of a bank account
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AccountHolder {
+    /// A person
+    Individual {
+        /// The account holder's first name
+        first_name: String,
+
+        /// The account holder's last name
+        last_name: String,
+
+        /// The account holder's billing address
+        billing_address: Option<Address>,
+    },
+
+    /// A company
+    Corporate {
+        /// The name of the company
+        company_name: String,
+
+        /// The company's billing address
+        billing_address: Option<Address>,
+    },
+}
+
+/// The sender of a payout or an Account Funding Transaction
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PayoutSender {
+    /// A person
+    Individual {
+        /// The sender's first name
+        first_name: String,
+
+        /// The sender's last name
+        last_name: String,
+
+        /// The sender's address
+        address: Option<Address>,
+
+        /// Your reference for the sender
+        reference: Option<String>,
+    },
+
+    /// A company
+    Corporate {
+        /// The name of the company
+        company_name: String,
+
+        /// The company's address
+        address: Option<Address>,
+
+        /// Your reference for the sender
+        reference: Option<String>,
+    },
+}
+
+/// Details about a payout instruction
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PayoutInstruction {
+    /// The purpose of the payout, e.g. `"family_support"` (<= 35
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    /// The type of funds transfer, e.g. `"FD"` for funds disbursement
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub funds_transfer_type: Option<String>,
+
+    /// The preferred payment scheme of a bank payout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<PayoutScheme>,
+
+    /// The identifier of a foreign exchange quote (format: `qte_*`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_id: Option<String>,
+}
+
+/// The payment scheme of a bank payout
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutScheme {
+    /// An international SWIFT transfer
+    Swift,
+
+    /// A transfer through the local clearing scheme
+    Local,
+
+    /// A transfer through the local instant payment scheme
+    Instant,
+}
@@ -0,0 +1,6 @@
+//! Identifiers of the entities, sub-entities and processing channels of an
+//! account, e.g. to configure which entity a client makes payments for
+//!
+//! See: [Platforms](https://docs.checkout.com/platforms)
+
+pub use crate::types::{EntityId, InvalidIdError, ProcessingChannelId, SubEntityId};
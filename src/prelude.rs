@@ -0,0 +1,11 @@
+//! The types most integrations need, to import with `use checkout::prelude::*;`
+//!
+//! The domain modules, e.g. [`payments`](crate::payments) and
+//! [`payouts`](crate::payouts), contain the remaining types.
+
+pub use crate::{
+    Amount, ApiResponse, CapturePaymentBody, Client, CreatePaymentRequest, CreatePaymentResponse,
+    Currency, Environment, Error, Money, PaymentAmount, PaymentDetails, PaymentRequestSource,
+    PaymentStatus, ProcessingChannelId, Reference, RefundPaymentBody, RequestOptions,
+    VoidPaymentBody, WebhookEvent, WebhookVerifier,
+};
@@ -0,0 +1,241 @@
+//! Recording of recent requests for support escalations
+//!
+//! Checkout support asks for the exact payloads and timestamps of the
+//! requests involved in an issue. A [`Recorder`] keeps the redacted requests
+//! of a client and the responses they received from a recent time window in
+//! memory, ready to be dumped as JSON when an error occurs.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use reqwest::{Error as ReqwestError, Response, ResponseBuilderExt};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    audit::{mask_account_number, CARD_OBJECTS},
+    mask_card_number, RequestSummary,
+};
+
+/// The placeholder of redacted values
+const REDACTED: &str = "[REDACTED]";
+
+/// The size in bytes of the largest response body that is recorded
+const MAX_RECORDED_BODY_SIZE: u64 = 64 * 1024;
+
+/// A recorded request and the response it received
+#[derive(Serialize, Debug, Clone)]
+pub struct RecordedExchange {
+    /// When the request completed
+    pub recorded_at: DateTime<Utc>,
+
+    /// The HTTP method of the request
+    pub method: String,
+
+    /// The URL the request was sent to
+    pub endpoint: String,
+
+    /// The idempotency key sent with the request, if any
+    pub idempotency_key: Option<String>,
+
+    /// The redacted request body, if any
+    pub request_body: Option<Value>,
+
+    /// The HTTP status of the response, if a response was received
+    pub status: Option<u16>,
+
+    /// The redacted response body, if a JSON response of at most 64 KiB was
+    /// received. Other bodies, e.g. report files or error pages of a proxy,
+    /// aren't recorded.
+    pub response_body: Option<Value>,
+
+    /// The `Cko-Request-Id` header of the response, if any
+    pub cko_request_id: Option<String>,
+
+    /// The `Cko-Version` header of the response, if any
+    pub api_version: Option<String>,
+
+    /// The time until the response was received in milliseconds, if a
+    /// response was received
+    pub latency_ms: Option<u128>,
+}
+
+/// Keeps the most recent requests of a client and their responses in a ring
+/// buffer
+///
+/// Card numbers and account numbers are masked and card verification values,
+/// tokens and passwords are removed before a request or response is
+/// recorded.
+///
+/// ```ignore
+/// let recorder = Recorder::new(100, Duration::from_secs(15 * 60));
+/// let client = client.with_recorder(recorder.clone());
+/// if let Err(err) = client.create_payment(&request).await {
+///     log::error!("{}: {}", err, recorder.dump_json());
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    exchanges: Arc<Mutex<VecDeque<RecordedExchange>>>,
+    capacity: usize,
+    window: Duration,
+}
+
+impl Recorder {
+    /// Creates a recorder that keeps at most `capacity` requests from the last
+    /// `window`
+    #[must_use]
+    pub fn new(capacity: usize, window: Duration) -> Recorder {
+        Recorder {
+            exchanges: Arc::default(),
+            capacity,
+            window,
+        }
+    }
+
+    pub(crate) fn record(
+        &self,
+        summary: &RequestSummary,
+        request_body: Option<Value>,
+        response_body: Option<Value>,
+    ) {
+        let exchange = RecordedExchange {
+            recorded_at: Utc::now(),
+            method: summary.method.to_string(),
+            endpoint: summary.endpoint.clone(),
+            idempotency_key: summary.idempotency_key.clone(),
+            request_body,
+            status: summary.status.map(|status| status.as_u16()),
+            response_body,
+            cko_request_id: summary.cko_request_id.clone(),
+            api_version: summary.api_version.clone(),
+            latency_ms: summary.latency.map(|latency| latency.as_millis()),
+        };
+
+        if let Ok(mut exchanges) = self.exchanges.lock() {
+            exchanges.push_back(exchange);
+            while exchanges.len() > self.capacity {
+                exchanges.pop_front();
+            }
+        }
+    }
+
+    /// Returns the recorded requests from the window, oldest first
+    #[must_use]
+    pub fn exchanges(&self) -> Vec<RecordedExchange> {
+        let cutoff = chrono::Duration::from_std(self.window)
+            .ok()
+            .and_then(|window| Utc::now().checked_sub_signed(window));
+
+        let Ok(mut exchanges) = self.exchanges.lock() else {
+            return Vec::new();
+        };
+        if let Some(cutoff) = cutoff {
+            while exchanges
+                .front()
+                .is_some_and(|exchange| exchange.recorded_at < cutoff)
+            {
+                exchanges.pop_front();
+            }
+        }
+        exchanges.iter().cloned().collect()
+    }
+
+    /// Returns the recorded requests from the window as a JSON array, e.g. to
+    /// attach to a support request
+    #[must_use]
+    pub fn dump_json(&self) -> String {
+        serde_json::to_string_pretty(&self.exchanges()).unwrap_or_default()
+    }
+
+    /// Discards all recorded requests
+    pub fn clear(&self) {
+        if let Ok(mut exchanges) = self.exchanges.lock() {
+            exchanges.clear();
+        }
+    }
+}
+
+/// Serializes a request body with its sensitive values masked or removed
+pub(crate) fn redacted_body<B>(body: &B) -> Option<Value>
+where
+    B: Serialize,
+{
+    let mut value = serde_json::to_value(body).ok()?;
+    redact(&mut value, None);
+    Some(value)
+}
+
+/// Whether the body of a response is recorded, i.e. it is JSON of a known
+/// length no larger than [`MAX_RECORDED_BODY_SIZE`]. Other bodies are left
+/// unread so that e.g. downloads keep streaming.
+pub(crate) fn is_recordable(response: &Response) -> bool {
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    is_json
+        && response
+            .content_length()
+            .is_some_and(|length| length <= MAX_RECORDED_BODY_SIZE)
+}
+
+/// Reads the body of a response to record it with its sensitive values
+/// masked or removed, returning an equivalent response to read it again.
+/// The recorded body is `None` if it isn't valid JSON.
+pub(crate) async fn buffer_response(
+    response: Response,
+) -> Result<(Response, Option<Value>), ReqwestError> {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version())
+        .url(response.url().clone());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    let body = response.bytes().await?;
+
+    let value = serde_json::from_slice(&body).ok().map(|mut value| {
+        redact(&mut value, None);
+        value
+    });
+    let response = builder
+        .body(body)
+        .expect("the parts of a received response are valid");
+    Ok((Response::from(response), value))
+}
+
+/// Masks or removes the sensitive values of `value`, the value of the field
+/// `parent` if it is a field of an object
+fn redact(value: &mut Value, parent: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            let is_card = parent.is_some_and(|parent| CARD_OBJECTS.contains(&parent));
+            for (key, value) in map.iter_mut() {
+                match (key.as_str(), &*value) {
+                    ("number", Value::String(number)) if is_card => {
+                        *value = Value::String(mask_card_number(number));
+                    }
+                    ("account_number" | "iban", Value::String(number)) => {
+                        *value = Value::String(mask_account_number(number));
+                    }
+                    ("cvv" | "token" | "password" | "access_token", Value::String(_)) => {
+                        *value = Value::String(REDACTED.to_string());
+                    }
+                    (key, _) => redact(value, Some(key)),
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                redact(value, parent);
+            }
+        }
+        _ => {}
+    }
+}
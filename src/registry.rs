@@ -0,0 +1,76 @@
+//! Clients for several Checkout entities or merchant accounts operated from
+//! one service
+
+use std::collections::HashMap;
+
+use reqwest::Client as ReqwestClient;
+
+use crate::{Client, Error};
+
+/// A set of configured clients, resolved by key at call time
+///
+/// The clients of a registry share the registry's connection pool, except
+/// those that were given an HTTP client of their own, e.g. with a proxy or
+/// [`ConnectionOptions`](crate::ConnectionOptions).
+///
+/// ```ignore
+/// let registry = ClientRegistry::new()
+///     .with_client("uk-entity", uk_client)
+///     .with_client("us-entity", us_client);
+/// registry.client("uk-entity")?.create_payment(&request).await?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClientRegistry {
+    http_client: ReqwestClient,
+    clients: HashMap<String, Client>,
+}
+
+impl ClientRegistry {
+    /// Creates an empty registry with a default connection pool
+    #[must_use]
+    pub fn new() -> ClientRegistry {
+        ClientRegistry::default()
+    }
+
+    /// Creates an empty registry whose clients share the connection pool of
+    /// `http_client`
+    #[must_use]
+    pub fn with_http_client(http_client: ReqwestClient) -> ClientRegistry {
+        ClientRegistry {
+            http_client,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Registers a client under `key`, replacing and returning any client
+    /// that was registered under the same key. The client keeps its HTTP
+    /// client if it was given one, and uses the registry's pool otherwise.
+    pub fn register(&mut self, key: impl Into<String>, client: Client) -> Option<Client> {
+        let client = client.share_http_client(&self.http_client);
+        self.clients.insert(key.into(), client)
+    }
+
+    /// Registers a client under `key`
+    #[must_use]
+    pub fn with_client(mut self, key: impl Into<String>, client: Client) -> ClientRegistry {
+        self.register(key, client);
+        self
+    }
+
+    /// Returns the client registered under `key`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownClient`] if no client is registered under
+    /// `key`
+    pub fn client(&self, key: &str) -> Result<&Client, Error> {
+        self.clients
+            .get(key)
+            .ok_or_else(|| Error::UnknownClient(key.to_string()))
+    }
+
+    /// Returns the keys of the registered clients
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+}
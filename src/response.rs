@@ -0,0 +1,83 @@
+//! Responses together with measurements of the request that produced them
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// A response body together with the request id, status, latency and number
+/// of attempts of the request, e.g. to build SLO dashboards
+#[derive(Debug, Clone)]
+pub struct ApiResponse<T> {
+    /// The deserialized response body
+    pub body: T,
+
+    /// The `Cko-Request-Id` header of the response, if any
+    pub request_id: Option<String>,
+
+    /// The HTTP status of the response
+    pub status: StatusCode,
+
+    /// The time until the response of the last attempt was received
+    pub latency: Duration,
+
+    /// The time until the response was received, including earlier attempts,
+    /// the backoff between them, renewing a rejected token and failing over
+    /// to another host
+    pub total_latency: Duration,
+
+    /// The number of times the request was attempted, i.e. one more than the
+    /// number of retries. Sending it again to another host or with a renewed
+    /// token doesn't count as an attempt.
+    pub attempts: u32,
+
+    /// The number of times the request was sent to another host because a
+    /// host could not be connected to
+    pub redirects: u32,
+
+    /// The idempotency key sent with every attempt of the request, if any.
+    /// Generated when retries are enabled and no key was set, persist it to
+    /// retry the request safely later. A failed request reports its
+    /// generated key in [`Error::Unconfirmed`](crate::Error::Unconfirmed).
+    pub idempotency_key: Option<String>,
+}
+
+impl<T> ApiResponse<T> {
+    /// Converts the body of the response, keeping the measurements
+    #[must_use]
+    pub fn map<U, F>(self, f: F) -> ApiResponse<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        ApiResponse {
+            body: f(self.body),
+            request_id: self.request_id,
+            status: self.status,
+            latency: self.latency,
+            total_latency: self.total_latency,
+            attempts: self.attempts,
+            redirects: self.redirects,
+            idempotency_key: self.idempotency_key,
+        }
+    }
+
+    /// Separates the body from the measurements
+    pub(crate) fn split(self) -> (T, ApiResponse<()>) {
+        let measurements = ApiResponse {
+            body: (),
+            request_id: self.request_id,
+            status: self.status,
+            latency: self.latency,
+            total_latency: self.total_latency,
+            attempts: self.attempts,
+            redirects: self.redirects,
+            idempotency_key: self.idempotency_key,
+        };
+        (self.body, measurements)
+    }
+
+    /// Returns the response body, discarding the measurements
+    #[must_use]
+    pub fn into_body(self) -> T {
+        self.body
+    }
+}
@@ -0,0 +1,219 @@
+//! Routing of payments across several processing channels, e.g. to spread
+//! volume between acquirers or to process each currency with a local MID
+//!
+//! A [`ChannelRouter`] holds the processing channels and the rules that make
+//! a channel eligible for a payment. Among the eligible channels, payments
+//! are distributed by weight. Use it with
+//! [`Client::create_routed_payment`](crate::Client::create_routed_payment).
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{
+    CardMetadataResponse, CardScheme, CreatePaymentResponse, Currency, ProcessingChannelId,
+};
+
+/// A processing channel and the payments it may process
+#[derive(Debug, Clone)]
+pub struct ProcessingChannel {
+    id: ProcessingChannelId,
+    weight: u32,
+    currencies: Vec<Currency>,
+    schemes: Vec<CardScheme>,
+    bin_countries: Vec<String>,
+}
+
+impl ProcessingChannel {
+    /// Creates a channel that may process any payment, receiving a share of
+    /// the payments proportional to `weight` among the eligible channels
+    #[must_use]
+    pub fn new(id: ProcessingChannelId, weight: u32) -> ProcessingChannel {
+        ProcessingChannel {
+            id,
+            weight,
+            currencies: Vec::new(),
+            schemes: Vec::new(),
+            bin_countries: Vec::new(),
+        }
+    }
+
+    /// Only routes payments in the currency to the channel. Can be called
+    /// several times to allow several currencies.
+    #[must_use]
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currencies.push(currency);
+        self
+    }
+
+    /// Only routes payments with cards of the scheme to the channel. Can be
+    /// called several times to allow several schemes.
+    #[must_use]
+    pub fn scheme(mut self, scheme: CardScheme) -> Self {
+        self.schemes.push(scheme);
+        self
+    }
+
+    /// Only routes payments with cards issued in the country (two-letter ISO
+    /// code) to the channel. Can be called several times to allow several
+    /// countries.
+    #[must_use]
+    pub fn bin_country(mut self, country: impl Into<String>) -> Self {
+        self.bin_countries.push(country.into());
+        self
+    }
+
+    /// Returns the channel's identifier
+    #[must_use]
+    pub fn id(&self) -> &ProcessingChannelId {
+        &self.id
+    }
+
+    /// Returns whether the channel may process the payment. A condition on a
+    /// scheme or country is not met if the payment's is unknown.
+    #[must_use]
+    pub fn accepts(&self, payment: &RoutingContext) -> bool {
+        let currency = self.currencies.is_empty() || self.currencies.contains(&payment.currency);
+        let scheme = self.schemes.is_empty()
+            || payment
+                .scheme
+                .as_ref()
+                .is_some_and(|scheme| self.schemes.contains(scheme));
+        let country = self.bin_countries.is_empty()
+            || payment.bin_country.as_deref().is_some_and(|country| {
+                self.bin_countries
+                    .iter()
+                    .any(|allowed| allowed.eq_ignore_ascii_case(country))
+            });
+        self.weight > 0 && currency && scheme && country
+    }
+}
+
+/// The attributes of a payment that channels are selected by
+#[derive(Debug, Clone)]
+pub struct RoutingContext {
+    /// The currency of the payment
+    pub currency: Currency,
+
+    /// The scheme of the card, if known
+    pub scheme: Option<CardScheme>,
+
+    /// The country the card was issued in (two-letter ISO code), if known
+    pub bin_country: Option<String>,
+}
+
+impl RoutingContext {
+    /// Creates the context of a payment in the currency with an unknown card
+    #[must_use]
+    pub fn new(currency: Currency) -> RoutingContext {
+        RoutingContext {
+            currency,
+            scheme: None,
+            bin_country: None,
+        }
+    }
+
+    /// Creates the context of a payment in the currency with the card, e.g.
+    /// from [`Client::get_card_metadata`](crate::Client::get_card_metadata)
+    #[must_use]
+    pub fn from_card_metadata(currency: Currency, card: &CardMetadataResponse) -> RoutingContext {
+        RoutingContext {
+            currency,
+            scheme: card.scheme.clone(),
+            bin_country: card.issuer_country.clone(),
+        }
+    }
+}
+
+/// A payment and the processing channel it was routed to
+#[derive(Debug, Clone)]
+pub struct RoutedPayment {
+    /// The identifier of the channel the payment was sent to
+    pub channel_id: ProcessingChannelId,
+
+    /// The response to the payment request
+    pub response: CreatePaymentResponse,
+}
+
+/// Selects a processing channel per payment
+///
+/// # Example
+///
+/// ```ignore
+/// let router = ChannelRouter::new()
+///     .channel(ProcessingChannel::new(config.eu_primary, 80).currency(Currency::EUR))
+///     .channel(ProcessingChannel::new(config.eu_secondary, 20).currency(Currency::EUR))
+///     .channel(ProcessingChannel::new("pc_us".parse()?, 1).currency(Currency::USD));
+///
+/// let context = RoutingContext::new(Currency::EUR);
+/// let routed = client.create_routed_payment(&router, &request, &context).await?;
+/// log::info!("{} routed to {}", routed.response.payment_id(), routed.channel_id);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChannelRouter {
+    channels: Vec<ProcessingChannel>,
+    credits: Arc<Mutex<Vec<i64>>>,
+    usage: Arc<Mutex<HashMap<ProcessingChannelId, u64>>>,
+}
+
+impl ChannelRouter {
+    /// Creates a router without channels
+    #[must_use]
+    pub fn new() -> ChannelRouter {
+        ChannelRouter::default()
+    }
+
+    /// Adds a channel
+    #[must_use]
+    pub fn channel(mut self, channel: ProcessingChannel) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    /// Returns the channel for the next payment, or `None` if no channel
+    /// accepts the payment. Payments are spread across the eligible channels
+    /// in proportion to their weights, interleaving the channels rather than
+    /// sending consecutive payments to the same one.
+    #[must_use]
+    pub fn select(&self, payment: &RoutingContext) -> Option<&ProcessingChannel> {
+        // Smooth weighted round-robin: every eligible channel earns its
+        // weight, and the richest channel is selected and pays the total
+        let mut credits = self.credits.lock().unwrap_or_else(PoisonError::into_inner);
+        credits.resize(self.channels.len(), 0);
+
+        let mut total = 0;
+        let mut selected: Option<usize> = None;
+        for (index, channel) in self.channels.iter().enumerate() {
+            if !channel.accepts(payment) {
+                continue;
+            }
+            let weight = i64::from(channel.weight);
+            credits[index] += weight;
+            total += weight;
+            if selected.is_none_or(|selected| credits[index] > credits[selected]) {
+                selected = Some(index);
+            }
+        }
+
+        let selected = selected?;
+        credits[selected] -= total;
+        Some(&self.channels[selected])
+    }
+
+    /// Returns the number of payments sent through each channel, by channel
+    /// identifier
+    #[must_use]
+    pub fn usage(&self) -> HashMap<ProcessingChannelId, u64> {
+        self.usage
+            .lock()
+            .map(|usage| usage.clone())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn record(&self, channel_id: &ProcessingChannelId) {
+        if let Ok(mut usage) = self.usage.lock() {
+            *usage.entry(channel_id.clone()).or_insert(0) += 1;
+        }
+    }
+}
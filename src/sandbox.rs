@@ -0,0 +1,311 @@
+//! Helpers to trigger specific Gateway response codes in the sandbox
+//!
+//! The sandbox declines card payments based on the last two digits of the
+//! amount in minor units, e.g. an amount of `100.05` USD (`10005`) is declined
+//! with `20005` (Declined - Do not honour). The trigger only applies to
+//! non-3D Secure payments with the default test card. Captures are declined
+//! the same way, based on the amount to capture.
+//!
+//! A [`WebhookSimulator`] makes the payments and actions that cause the
+//! sandbox to send a given webhook notification, e.g. to exercise webhook
+//! handlers end-to-end in CI.
+//!
+//! See: [Test cards and amounts](https://docs.checkout.com/testing/response-code-testing)
+
+use crate::{
+    Amount, CapturePaymentBody, Client, CreatePaymentRequest, Currency, Cvv, Environment, Error,
+    Money, PaymentRequestSource, VoidPaymentBody,
+};
+
+/// The default sandbox test card, which is approved unless the amount
+/// triggers a different response code
+pub const TEST_CARD_NUMBER: &str = "4242424242424242";
+
+/// A Gateway response code that can be forced in the sandbox
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResponseCodeTrigger {
+    /// `20005` - Declined - Do not honour
+    DoNotHonour,
+
+    /// `20012` - Invalid transaction
+    InvalidTransaction,
+
+    /// `20014` - Invalid card number
+    InvalidCardNumber,
+
+    /// `20051` - Insufficient funds
+    InsufficientFunds,
+
+    /// `20054` - Expired card
+    ExpiredCard,
+
+    /// `20062` - Restricted card
+    RestrictedCard,
+
+    /// `20087` - Bad track data (invalid CVV and/or expiry date)
+    BadTrackData,
+
+    /// `30004` - Pick up card (hard decline)
+    PickUpCard,
+
+    /// `30041` - Lost card, pick up (hard decline)
+    LostCard,
+}
+
+impl ResponseCodeTrigger {
+    /// Returns the response code the trigger forces
+    #[must_use]
+    pub fn response_code(self) -> &'static str {
+        match self {
+            ResponseCodeTrigger::DoNotHonour => "20005",
+            ResponseCodeTrigger::InvalidTransaction => "20012",
+            ResponseCodeTrigger::InvalidCardNumber => "20014",
+            ResponseCodeTrigger::InsufficientFunds => "20051",
+            ResponseCodeTrigger::ExpiredCard => "20054",
+            ResponseCodeTrigger::RestrictedCard => "20062",
+            ResponseCodeTrigger::BadTrackData => "20087",
+            ResponseCodeTrigger::PickUpCard => "30004",
+            ResponseCodeTrigger::LostCard => "30041",
+        }
+    }
+
+    /// Returns the two trailing digits of the amount (in minor units) that
+    /// force the response code
+    #[must_use]
+    pub fn amount_suffix(self) -> u64 {
+        match self {
+            ResponseCodeTrigger::DoNotHonour => 5,
+            ResponseCodeTrigger::InvalidTransaction => 12,
+            ResponseCodeTrigger::InvalidCardNumber => 14,
+            ResponseCodeTrigger::InsufficientFunds => 51,
+            ResponseCodeTrigger::ExpiredCard => 54,
+            ResponseCodeTrigger::RestrictedCard => 62,
+            ResponseCodeTrigger::BadTrackData => 87,
+            ResponseCodeTrigger::PickUpCard => 4,
+            ResponseCodeTrigger::LostCard => 41,
+        }
+    }
+
+    /// Returns an amount that forces the response code with the
+    /// [`test_card`], e.g. `10005` minor units (`100.05` USD) for
+    /// [`ResponseCodeTrigger::DoNotHonour`]
+    #[must_use]
+    pub fn amount(self) -> Amount {
+        Amount::from_minor_units(10_000 + self.amount_suffix())
+    }
+}
+
+/// Returns a card source for the default test card, which is approved unless
+/// the amount triggers a different response code
+#[must_use]
+pub fn test_card() -> PaymentRequestSource {
+    PaymentRequestSource::Card {
+        number: TEST_CARD_NUMBER.to_string(),
+        expiry_month: 6,
+        expiry_year: 2030,
+        name: None,
+        cvv: Some(Cvv::new("100")),
+        stored: None,
+        billing_address: None,
+        phone: None,
+    }
+}
+
+/// The amount the simulator authorizes, which is approved and leaves room to
+/// capture any trigger amount
+const AUTHORIZED_AMOUNT: u64 = 20_000;
+
+/// A payment or action made by a [`WebhookSimulator`] and the webhook
+/// notification the sandbox sends for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// The payment's unique identifier (format: `pay_*`)
+    pub payment_id: String,
+
+    /// The unique identifier of the action that causes the notification
+    /// (format: `act_*`), if the notification is for an action
+    pub action_id: Option<String>,
+
+    /// The type of the expected notification, e.g. `"payment_captured"`
+    pub event_type: &'static str,
+}
+
+/// Makes payments and actions in the sandbox that cause specific webhook
+/// notifications, e.g. to test webhook handlers without using the Dashboard
+///
+/// Notifications are sent asynchronously, so wait for the
+/// [`Simulation::event_type`] of the [`Simulation::payment_id`] to arrive.
+///
+/// # Example
+///
+/// ```ignore
+/// let simulator = WebhookSimulator::new(&client)?;
+/// let simulation = simulator.declined_capture(ResponseCodeTrigger::DoNotHonour).await?;
+/// let event = webhooks.wait_for(&simulation.payment_id, simulation.event_type).await;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct WebhookSimulator<'a> {
+    client: &'a Client,
+    currency: Currency,
+}
+
+impl<'a> WebhookSimulator<'a> {
+    /// Creates a simulator that makes payments in USD with the client
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::SandboxOnly`] if the client is not connected to the
+    /// sandbox
+    pub fn new(client: &'a Client) -> Result<WebhookSimulator<'a>, Error> {
+        match client.environment {
+            Environment::Sandbox => Ok(WebhookSimulator {
+                client,
+                currency: Currency::USD,
+            }),
+            Environment::Production => Err(Error::SandboxOnly),
+        }
+    }
+
+    /// Makes payments in the currency
+    #[must_use]
+    pub fn currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    /// Authorizes a payment without capturing it, which causes a
+    /// `payment_approved` notification
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment could not be made, or
+    /// [`Error::Declined`] if it was declined
+    pub async fn approved_payment(&self) -> Result<Simulation, Error> {
+        let payment_id = self.authorize().await?;
+        Ok(Simulation {
+            payment_id,
+            action_id: None,
+            event_type: "payment_approved",
+        })
+    }
+
+    /// Makes a payment that is declined with the trigger's response code,
+    /// which causes a `payment_declined` notification
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment could not be made
+    pub async fn declined_payment(
+        &self,
+        trigger: ResponseCodeTrigger,
+    ) -> Result<Simulation, Error> {
+        let request = self.payment_request(test_card(), trigger.amount());
+        let response = self.client.create_payment(&request).await?;
+        Ok(Simulation {
+            payment_id: response.payment_id().to_string(),
+            action_id: None,
+            event_type: "payment_declined",
+        })
+    }
+
+    /// Authorizes and captures a payment, which causes a `payment_captured`
+    /// notification once the capture is processed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment could not be made or captured
+    pub async fn captured_payment(&self) -> Result<Simulation, Error> {
+        self.capture(None, "payment_captured").await
+    }
+
+    /// Authorizes a payment and requests a capture that is declined with the
+    /// trigger's response code, which causes a `payment_capture_declined`
+    /// notification
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment could not be made or the capture was
+    /// rejected
+    pub async fn declined_capture(
+        &self,
+        trigger: ResponseCodeTrigger,
+    ) -> Result<Simulation, Error> {
+        let amount = Money {
+            amount: trigger.amount(),
+            currency: self.currency,
+        };
+        self.capture(Some(amount), "payment_capture_declined").await
+    }
+
+    /// Authorizes and voids a payment, which causes a `payment_voided`
+    /// notification
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment could not be made or voided
+    pub async fn voided_payment(&self) -> Result<Simulation, Error> {
+        let payment_id = self.authorize().await?;
+        let body = VoidPaymentBody {
+            reference: None,
+            metadata: None,
+        };
+        let response = self.client.void_payment(payment_id.clone(), &body).await?;
+        Ok(Simulation {
+            payment_id,
+            action_id: Some(response.action_id),
+            event_type: "payment_voided",
+        })
+    }
+
+    async fn capture(
+        &self,
+        amount: Option<Money>,
+        event_type: &'static str,
+    ) -> Result<Simulation, Error> {
+        let payment_id = self.authorize().await?;
+        let body = CapturePaymentBody {
+            amount,
+            capture_type: None,
+            reference: None,
+            metadata: None,
+            extra: None,
+        };
+        let response = self
+            .client
+            .capture_payment(payment_id.clone(), &body)
+            .await?;
+        Ok(Simulation {
+            payment_id,
+            action_id: Some(response.action_id),
+            event_type,
+        })
+    }
+
+    /// Authorizes an approved payment and returns its identifier
+    async fn authorize(&self) -> Result<String, Error> {
+        let request =
+            self.payment_request(test_card(), Amount::from_minor_units(AUTHORIZED_AMOUNT));
+        let response = self
+            .client
+            .create_payment(&request)
+            .await?
+            .ensure_approved()
+            .map_err(Error::Declined)?;
+        Ok(response.payment_id().to_string())
+    }
+
+    fn payment_request(
+        &self,
+        source: PaymentRequestSource,
+        amount: Amount,
+    ) -> CreatePaymentRequest {
+        let money = Money {
+            amount,
+            currency: self.currency,
+        };
+        CreatePaymentRequest {
+            capture: Some(false),
+            ..CreatePaymentRequest::moto_payment(source, money)
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Gateway response codes and scheme merchant advice codes
+//!
+//! See: [Response codes](https://docs.checkout.com/resources/codes/api-response-codes)
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// A Gateway response code, classified by its leading digit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseCode {
+    /// `10xxx` - The request was approved
+    Approved(u32),
+
+    /// `20xxx` - The issuer declined the request, but it may succeed if
+    /// retried later
+    SoftDecline(u32),
+
+    /// `30xxx` - The issuer declined the request and it should not be retried
+    HardDecline(u32),
+
+    /// `40xxx` - The request was blocked by a risk rule
+    RiskDecline(u32),
+
+    /// Any other response code
+    Other(String),
+}
+
+impl ResponseCode {
+    /// Returns whether the request was approved
+    #[must_use]
+    pub fn is_approved(&self) -> bool {
+        matches!(self, ResponseCode::Approved(_))
+    }
+
+    /// Returns whether the request was declined
+    #[must_use]
+    pub fn is_declined(&self) -> bool {
+        matches!(
+            self,
+            ResponseCode::SoftDecline(_)
+                | ResponseCode::HardDecline(_)
+                | ResponseCode::RiskDecline(_)
+        )
+    }
+}
+
+impl FromStr for ResponseCode {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let code = match s.parse::<u32>() {
+            Ok(code) if (10_000..100_000).contains(&code) => code,
+            _ => return Ok(ResponseCode::Other(s.to_string())),
+        };
+
+        Ok(match code / 10_000 {
+            1 => ResponseCode::Approved(code),
+            2 => ResponseCode::SoftDecline(code),
+            3 => ResponseCode::HardDecline(code),
+            4 => ResponseCode::RiskDecline(code),
+            _ => ResponseCode::Other(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for ResponseCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseCode::Approved(code)
+            | ResponseCode::SoftDecline(code)
+            | ResponseCode::HardDecline(code)
+            | ResponseCode::RiskDecline(code) => write!(f, "{code}"),
+            ResponseCode::Other(code) => f.write_str(code),
+        }
+    }
+}
+
+impl From<&str> for ResponseCode {
+    fn from(code: &str) -> ResponseCode {
+        match code.parse() {
+            Ok(code) => code,
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// A card scheme's advice on retrying a declined payment, which decides
+/// whether a recurring payment should be attempted again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MerchantAdviceCode {
+    /// `01` - Updated account information is available, e.g. a new card
+    NewAccountInformation,
+
+    /// `02` - The payment cannot be approved at this time, try again later
+    TryAgainLater,
+
+    /// `03` - Do not try again
+    DoNotTryAgain,
+
+    /// `04` - The token requirements were not fulfilled for the token type
+    TokenRequirementsNotFulfilled,
+
+    /// `21` - The cardholder cancelled the recurring payment, do not try
+    /// again
+    PaymentCancelled,
+
+    /// `24` - Retry after 1 hour
+    RetryAfterOneHour,
+
+    /// `25` - Retry after 24 hours
+    RetryAfterOneDay,
+
+    /// `26` - Retry after 2 days
+    RetryAfterTwoDays,
+
+    /// `27` - Retry after 4 days
+    RetryAfterFourDays,
+
+    /// `28` - Retry after 6 days
+    RetryAfterSixDays,
+
+    /// `29` - Retry after 8 days
+    RetryAfterEightDays,
+
+    /// `30` - Retry after 10 days
+    RetryAfterTenDays,
+
+    /// `40` - The card is a non-reloadable prepaid card, do not use it for
+    /// recurring payments
+    NonReloadablePrepaidCard,
+
+    /// `41` - The card is a single-use virtual card, do not use it for
+    /// recurring payments
+    SingleUseVirtualCard,
+
+    /// Any other advice code
+    Other(String),
+}
+
+impl MerchantAdviceCode {
+    /// Returns whether the scheme allows retrying the payment with the same
+    /// details
+    #[must_use]
+    pub fn allows_retry(&self) -> bool {
+        matches!(self, MerchantAdviceCode::TryAgainLater) || self.retry_after().is_some()
+    }
+
+    /// Returns how long to wait before retrying the payment, for the codes
+    /// that specify it
+    #[must_use]
+    pub fn retry_after(&self) -> Option<chrono::Duration> {
+        Some(match self {
+            MerchantAdviceCode::RetryAfterOneHour => chrono::Duration::hours(1),
+            MerchantAdviceCode::RetryAfterOneDay => chrono::Duration::days(1),
+            MerchantAdviceCode::RetryAfterTwoDays => chrono::Duration::days(2),
+            MerchantAdviceCode::RetryAfterFourDays => chrono::Duration::days(4),
+            MerchantAdviceCode::RetryAfterSixDays => chrono::Duration::days(6),
+            MerchantAdviceCode::RetryAfterEightDays => chrono::Duration::days(8),
+            MerchantAdviceCode::RetryAfterTenDays => chrono::Duration::days(10),
+            _ => return None,
+        })
+    }
+}
+
+impl From<&str> for MerchantAdviceCode {
+    fn from(code: &str) -> MerchantAdviceCode {
+        match code.trim() {
+            "01" => MerchantAdviceCode::NewAccountInformation,
+            "02" => MerchantAdviceCode::TryAgainLater,
+            "03" => MerchantAdviceCode::DoNotTryAgain,
+            "04" => MerchantAdviceCode::TokenRequirementsNotFulfilled,
+            "21" => MerchantAdviceCode::PaymentCancelled,
+            "24" => MerchantAdviceCode::RetryAfterOneHour,
+            "25" => MerchantAdviceCode::RetryAfterOneDay,
+            "26" => MerchantAdviceCode::RetryAfterTwoDays,
+            "27" => MerchantAdviceCode::RetryAfterFourDays,
+            "28" => MerchantAdviceCode::RetryAfterSixDays,
+            "29" => MerchantAdviceCode::RetryAfterEightDays,
+            "30" => MerchantAdviceCode::RetryAfterTenDays,
+            "40" => MerchantAdviceCode::NonReloadablePrepaidCard,
+            "41" => MerchantAdviceCode::SingleUseVirtualCard,
+            other => MerchantAdviceCode::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for MerchantAdviceCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MerchantAdviceCode::NewAccountInformation => "01",
+            MerchantAdviceCode::TryAgainLater => "02",
+            MerchantAdviceCode::DoNotTryAgain => "03",
+            MerchantAdviceCode::TokenRequirementsNotFulfilled => "04",
+            MerchantAdviceCode::PaymentCancelled => "21",
+            MerchantAdviceCode::RetryAfterOneHour => "24",
+            MerchantAdviceCode::RetryAfterOneDay => "25",
+            MerchantAdviceCode::RetryAfterTwoDays => "26",
+            MerchantAdviceCode::RetryAfterFourDays => "27",
+            MerchantAdviceCode::RetryAfterSixDays => "28",
+            MerchantAdviceCode::RetryAfterEightDays => "29",
+            MerchantAdviceCode::RetryAfterTenDays => "30",
+            MerchantAdviceCode::NonReloadablePrepaidCard => "40",
+            MerchantAdviceCode::SingleUseVirtualCard => "41",
+            MerchantAdviceCode::Other(code) => code,
+        })
+    }
+}
+
+/// A normalized reason for a decline, consistent across acquirers, e.g. to
+/// aggregate declines in analytics
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DeclineReason {
+    InsufficientFunds,
+    DoNotHonor,
+    StolenCard,
+    LostCard,
+    PickUpCard,
+    ExpiredCard,
+    InvalidCard,
+    InvalidCvv,
+    IncorrectPin,
+    RestrictedCard,
+    ClosedAccount,
+    LimitExceeded,
+    SuspectedFraud,
+    TransactionNotPermitted,
+    ReferToIssuer,
+    IssuerUnavailable,
+    RiskBlocked,
+
+    /// A decline that does not map to any other reason
+    Other,
+}
+
+impl DeclineReason {
+    /// Returns the normalized reason of a decline from its Gateway response
+    /// code, falling back to its response summary. Returns `None` if the
+    /// response code is not a decline.
+    #[must_use]
+    pub fn from_response(response_code: &str, response_summary: Option<&str>) -> Option<Self> {
+        let code = ResponseCode::from(response_code);
+        if !code.is_declined() {
+            return None;
+        }
+
+        let reason = match code {
+            ResponseCode::RiskDecline(_) => Some(DeclineReason::RiskBlocked),
+            _ => DeclineReason::of_code(response_code.trim()),
+        };
+
+        Some(
+            reason
+                .or_else(|| response_summary.and_then(DeclineReason::of_summary))
+                .unwrap_or(DeclineReason::Other),
+        )
+    }
+
+    fn of_code(code: &str) -> Option<Self> {
+        Some(match code {
+            "20051" => DeclineReason::InsufficientFunds,
+            "20005" => DeclineReason::DoNotHonor,
+            "30043" => DeclineReason::StolenCard,
+            "30041" => DeclineReason::LostCard,
+            "30004" | "30007" => DeclineReason::PickUpCard,
+            "20054" | "30033" => DeclineReason::ExpiredCard,
+            "20014" | "30015" => DeclineReason::InvalidCard,
+            "20087" => DeclineReason::InvalidCvv,
+            "20055" | "20075" => DeclineReason::IncorrectPin,
+            "20062" => DeclineReason::RestrictedCard,
+            "20046" => DeclineReason::ClosedAccount,
+            "20061" | "20065" => DeclineReason::LimitExceeded,
+            "20059" => DeclineReason::SuspectedFraud,
+            "20012" | "20057" | "20058" => DeclineReason::TransactionNotPermitted,
+            "20001" | "20002" => DeclineReason::ReferToIssuer,
+            "20068" | "20091" | "20096" => DeclineReason::IssuerUnavailable,
+            _ => return None,
+        })
+    }
+
+    fn of_summary(summary: &str) -> Option<Self> {
+        let summary = summary.to_ascii_lowercase();
+        let contains = |patterns: &[&str]| patterns.iter().any(|p| summary.contains(p));
+
+        Some(if contains(&["insufficient funds"]) {
+            DeclineReason::InsufficientFunds
+        } else if contains(&["do not honour", "do not honor"]) {
+            DeclineReason::DoNotHonor
+        } else if contains(&["stolen"]) {
+            DeclineReason::StolenCard
+        } else if contains(&["lost card"]) {
+            DeclineReason::LostCard
+        } else if contains(&["pick up", "pick-up"]) {
+            DeclineReason::PickUpCard
+        } else if contains(&["expired card", "card expired"]) {
+            DeclineReason::ExpiredCard
+        } else if contains(&["cvv", "cvc", "security code"]) {
+            DeclineReason::InvalidCvv
+        } else if contains(&["invalid card", "invalid account number"]) {
+            DeclineReason::InvalidCard
+        } else if contains(&["pin"]) {
+            DeclineReason::IncorrectPin
+        } else if contains(&["restricted"]) {
+            DeclineReason::RestrictedCard
+        } else if contains(&["closed account", "account closed"]) {
+            DeclineReason::ClosedAccount
+        } else if contains(&["limit exceeded", "exceeds withdrawal"]) {
+            DeclineReason::LimitExceeded
+        } else if contains(&["fraud"]) {
+            DeclineReason::SuspectedFraud
+        } else if contains(&["not permitted"]) {
+            DeclineReason::TransactionNotPermitted
+        } else if contains(&["refer to"]) {
+            DeclineReason::ReferToIssuer
+        } else if contains(&["unavailable", "timeout", "too late", "malfunction"]) {
+            DeclineReason::IssuerUnavailable
+        } else if contains(&["risk"]) {
+            DeclineReason::RiskBlocked
+        } else {
+            return None;
+        })
+    }
+
+    /// Returns the reason as it is logged, e.g. `"insufficient_funds"`
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DeclineReason::InsufficientFunds => "insufficient_funds",
+            DeclineReason::DoNotHonor => "do_not_honor",
+            DeclineReason::StolenCard => "stolen_card",
+            DeclineReason::LostCard => "lost_card",
+            DeclineReason::PickUpCard => "pick_up_card",
+            DeclineReason::ExpiredCard => "expired_card",
+            DeclineReason::InvalidCard => "invalid_card",
+            DeclineReason::InvalidCvv => "invalid_cvv",
+            DeclineReason::IncorrectPin => "incorrect_pin",
+            DeclineReason::RestrictedCard => "restricted_card",
+            DeclineReason::ClosedAccount => "closed_account",
+            DeclineReason::LimitExceeded => "limit_exceeded",
+            DeclineReason::SuspectedFraud => "suspected_fraud",
+            DeclineReason::TransactionNotPermitted => "transaction_not_permitted",
+            DeclineReason::ReferToIssuer => "refer_to_issuer",
+            DeclineReason::IssuerUnavailable => "issuer_unavailable",
+            DeclineReason::RiskBlocked => "risk_blocked",
+            DeclineReason::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for DeclineReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
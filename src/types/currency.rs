@@ -8,11 +8,49 @@ use serde::{Deserialize, Serialize};
 #[serde(transparent)]
 pub struct Amount(u64);
 
+/// An amount together with its currency
+#[derive(Copy, Clone, Debug)]
+pub struct Money {
+    /// The amount, scaled to the currency's minor units
+    pub amount: Amount,
+
+    /// The currency of the amount
+    pub currency: Currency,
+}
+
+impl Money {
+    /// Creates money from a decimal value and its currency
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be represented in the currency's
+    /// minor units, see [`Amount::from`]
+    pub fn new(currency: Currency, amount: BigDecimal) -> Result<Money, AmountError> {
+        Ok(Money {
+            amount: Amount::from(currency, amount)?,
+            currency,
+        })
+    }
+
+    /// Returns the amount as a decimal, scaled by the currency's exponent
+    #[must_use]
+    pub fn decimal(&self) -> BigDecimal {
+        self.amount.into(self.currency)
+    }
+}
+
+/// The date the currency list was last reviewed against Checkout's list of
+/// supported currencies
+pub const CURRENCY_LIST_VERSION: &str = "2026-10-01";
+
 /// These are the major currencies supported
 ///
+/// Codes that were retired by ISO 4217 are deprecated, see
+/// [`Currency::is_active`] and [`Currency::replacement`].
+///
 /// See [Currency Codes](https://docs.checkout.com/resources/codes/currency-codes)
 #[allow(missing_docs)]
-#[derive(Copy, Clone, Debug, Deserialize, Serialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 pub enum Currency {
     AED,
     AFN,
@@ -53,6 +91,7 @@ pub enum Currency {
     DKK,
     DOP,
     DZD,
+    #[deprecated(note = "retired on 2011-01-01, use EUR")]
     EEK,
     EGP,
     ERN,
@@ -70,6 +109,7 @@ pub enum Currency {
     GYD,
     HKD,
     HNL,
+    #[deprecated(note = "retired on 2023-01-01, use EUR")]
     HRK,
     HTG,
     HUF,
@@ -96,7 +136,9 @@ pub enum Currency {
     LKR,
     LRD,
     LSL,
+    #[deprecated(note = "retired on 2015-01-01, use EUR")]
     LTL,
+    #[deprecated(note = "retired on 2014-01-01, use EUR")]
     LVL,
     LYD,
     MAD,
@@ -106,7 +148,9 @@ pub enum Currency {
     MMK,
     MNT,
     MOP,
+    #[deprecated(note = "retired on 2018-01-01, use MRU")]
     MRO,
+    MRU,
     MUR,
     MVR,
     MWK,
@@ -139,10 +183,14 @@ pub enum Currency {
     SEK,
     SGD,
     SHP,
+    #[deprecated(note = "retired on 2024-07-01, use SLE")]
     SLL,
+    SLE,
     SOS,
     SRD,
+    #[deprecated(note = "retired on 2018-01-01, use STN")]
     STD,
+    STN,
     SVC,
     SYP,
     SZL,
@@ -171,15 +219,25 @@ pub enum Currency {
     YER,
     ZAR,
     ZMW,
+    #[deprecated(note = "retired on 2024-09-01, use ZWG")]
     ZWL,
+    ZWG,
 }
 
-impl Amount {
-    /// Creates the amount from the raw value and currency. The currency is
-    /// required since the value is encoded as a scaled integer, which is
-    /// different depending on the currency.
-    pub fn into(self, currency: Currency) -> BigDecimal {
-        match currency {
+#[allow(deprecated)]
+impl Currency {
+    /// Returns whether the currency is still in use, i.e. it was not retired
+    /// by ISO 4217
+    #[must_use]
+    pub fn is_active(self) -> bool {
+        self.replacement().is_none()
+    }
+
+    /// Returns the number of decimal places of the currency's minor units,
+    /// e.g. 2 for US Dollars
+    #[must_use]
+    pub fn exponent(self) -> u32 {
+        match self {
             Currency::BIF
             | Currency::CLF
             | Currency::DJF
@@ -195,77 +253,236 @@ impl Amount {
             | Currency::VUV
             | Currency::XAF
             | Currency::XOF
-            | Currency::XPF => {
-                // For the following currencies, the value is the same as the
-                // full charge amount. For example, amount = 100 is equal to
-                // 100 Japanese Yen.
-                BigDecimal::from(self.0)
-            }
+            | Currency::XPF => 0,
             Currency::BHD
             | Currency::IQD
             | Currency::JOD
             | Currency::KWD
             | Currency::LYD
             | Currency::OMR
-            | Currency::TND => {
-                // With the following currencies, divide the value by 1000 to
-                // work out the value amount. For example, value = 1000 is the
-                // same as 1 Bahraini Dinar.
-                BigDecimal::from(self.0) / BigDecimal::from(1000)
-            }
-            _ => {
-                // For all other currencies, divide the value by 100 to
-                // calculate the charge amount. For example, value = 100 is
-                // equivalent to 1 US Dollar.
-                BigDecimal::from(self.0) / BigDecimal::from(100)
-            }
+            | Currency::TND => 3,
+            _ => 2,
+        }
+    }
+
+    /// Returns the currency that replaced a retired currency
+    #[must_use]
+    pub fn replacement(self) -> Option<Currency> {
+        match self {
+            Currency::EEK | Currency::HRK | Currency::LTL | Currency::LVL => Some(Currency::EUR),
+            Currency::MRO => Some(Currency::MRU),
+            Currency::SLL => Some(Currency::SLE),
+            Currency::STD => Some(Currency::STN),
+            Currency::ZWL => Some(Currency::ZWG),
+            _ => None,
         }
     }
+}
+
+impl Amount {
+    /// Creates the amount from a raw value in the currency's minor units
+    #[must_use]
+    pub fn from_minor_units(value: u64) -> Amount {
+        Amount(value)
+    }
+
+    /// Returns the raw value of the amount in the currency's minor units
+    #[must_use]
+    pub fn minor_units(self) -> u64 {
+        self.0
+    }
 
     /// Creates the amount from the raw value and currency. The currency is
     /// required since the value is encoded as a scaled integer, which is
     /// different depending on the currency.
-    pub fn from(currency: Currency, amount: BigDecimal) -> Amount {
-        match currency {
-            Currency::BIF
-            | Currency::CLF
-            | Currency::DJF
-            | Currency::GNF
-            | Currency::ISK
-            | Currency::JPY
-            | Currency::KMF
-            | Currency::KRW
-            | Currency::PYG
-            | Currency::RWF
-            | Currency::UGX
-            | Currency::VND
-            | Currency::VUV
-            | Currency::XAF
-            | Currency::XOF
-            | Currency::XPF => {
-                // For the following currencies, the value is the same as the
-                // full charge amount. For example, amount = 100 is equal to
-                // 100 Japanese Yen.
-                Amount(amount.to_u64().unwrap())
-            }
-            Currency::BHD
-            | Currency::IQD
-            | Currency::JOD
-            | Currency::KWD
-            | Currency::LYD
-            | Currency::OMR
-            | Currency::TND => {
-                // With the following currencies, divide the value by 1000 to
-                // work out the value amount. For example, value = 1000 is the
-                // same as 1 Bahraini Dinar.
-                Amount((amount * BigDecimal::from(1000)).to_u64().unwrap())
+    pub fn into(self, currency: Currency) -> BigDecimal {
+        // E.g. 100 is 1 US Dollar, 100 Japanese Yen or 0.1 Bahraini Dinar
+        BigDecimal::from(self.0) / minor_units_per_unit(currency)
+    }
+
+    /// Creates the amount from the decimal value and currency. The currency is
+    /// required since the value is encoded as a scaled integer, which is
+    /// different depending on the currency.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value has more precision than the currency's
+    /// minor units allow, or if it is negative or too large. Use
+    /// [`Amount::from_rounded`] to round or truncate instead.
+    pub fn from(currency: Currency, amount: BigDecimal) -> Result<Amount, AmountError> {
+        Amount::from_rounded(currency, amount, RoundingPolicy::default())
+    }
+
+    /// Creates the amount from the decimal value and currency, handling
+    /// precision beyond the currency's minor units according to the policy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is negative or too large, or if it has
+    /// too much precision and the policy is [`RoundingPolicy::Error`]
+    pub fn from_rounded(
+        currency: Currency,
+        amount: BigDecimal,
+        policy: RoundingPolicy,
+    ) -> Result<Amount, AmountError> {
+        if amount < BigDecimal::from(0) {
+            return Err(AmountError::OutOfRange(amount));
+        }
+
+        let scaled = amount.clone() * minor_units_per_unit(currency);
+        let whole = match policy {
+            RoundingPolicy::Error => {
+                if scaled.with_scale(0) != scaled {
+                    return Err(AmountError::Precision(amount));
+                }
+                scaled
             }
-            _ => {
-                // For all other currencies, divide the value by 100 to
-                // calculate the charge amount. For example, value = 100 is
-                // equivalent to 1 US Dollar.
-                Amount((amount * BigDecimal::from(100)).to_u64().unwrap())
+            RoundingPolicy::Truncate => scaled.with_scale(0),
+            RoundingPolicy::RoundHalfUp => {
+                (scaled + BigDecimal::from(5) / BigDecimal::from(10)).with_scale(0)
             }
+        };
+
+        whole
+            .to_u64()
+            .map(Amount)
+            .ok_or(AmountError::OutOfRange(amount))
+    }
+}
+
+/// Returns the number of minor units in one unit of the currency, e.g. 100
+/// cents in a US Dollar
+fn minor_units_per_unit(currency: Currency) -> BigDecimal {
+    BigDecimal::from(10_u64.pow(currency.exponent()))
+}
+
+/// How to convert a decimal value with more precision than the currency's
+/// minor units, e.g. 10.999 USD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPolicy {
+    /// Fail the conversion (default)
+    #[default]
+    Error,
+
+    /// Round to the nearest minor unit, rounding halves up
+    RoundHalfUp,
+
+    /// Drop the extra precision
+    Truncate,
+}
+
+/// Could not convert a decimal value to an amount, contains the original
+/// value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmountError {
+    /// The value has more precision than the currency's minor units allow
+    Precision(BigDecimal),
+
+    /// The value is negative or too large
+    OutOfRange(BigDecimal),
+}
+
+/// Conversions from and to [`rust_decimal::Decimal`], for applications that
+/// use it instead of [`BigDecimal`]. The values are scaled without going
+/// through strings.
+#[cfg(feature = "rust_decimal")]
+impl Amount {
+    /// Creates the amount from the decimal value and currency, like
+    /// [`Amount::from`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value has more precision than the currency's
+    /// minor units allow, or if it is negative or too large
+    pub fn from_rust_decimal(
+        currency: Currency,
+        amount: rust_decimal::Decimal,
+    ) -> Result<Amount, AmountError> {
+        Amount::from_rust_decimal_rounded(currency, amount, RoundingPolicy::default())
+    }
+
+    /// Creates the amount from the decimal value and currency, handling
+    /// precision beyond the currency's minor units according to the policy,
+    /// like [`Amount::from_rounded`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value is negative or too large, or if it has
+    /// too much precision and the policy is [`RoundingPolicy::Error`]
+    pub fn from_rust_decimal_rounded(
+        currency: Currency,
+        amount: rust_decimal::Decimal,
+        policy: RoundingPolicy,
+    ) -> Result<Amount, AmountError> {
+        use rust_decimal::{prelude::ToPrimitive, Decimal, RoundingStrategy};
+
+        if amount.is_sign_negative() && !amount.is_zero() {
+            return Err(AmountError::OutOfRange(big_decimal(amount)));
         }
+
+        let exponent = currency.exponent();
+        let whole = match policy {
+            RoundingPolicy::Error => {
+                if amount.round_dp_with_strategy(exponent, RoundingStrategy::ToZero) != amount {
+                    return Err(AmountError::Precision(big_decimal(amount)));
+                }
+                amount
+            }
+            RoundingPolicy::Truncate => {
+                amount.round_dp_with_strategy(exponent, RoundingStrategy::ToZero)
+            }
+            RoundingPolicy::RoundHalfUp => {
+                amount.round_dp_with_strategy(exponent, RoundingStrategy::MidpointAwayFromZero)
+            }
+        };
+
+        whole
+            .checked_mul(Decimal::from(10_u64.pow(exponent)))
+            .and_then(|scaled| scaled.to_u64())
+            .map(Amount)
+            .ok_or_else(|| AmountError::OutOfRange(big_decimal(amount)))
+    }
+
+    /// Returns the decimal value of the amount in the currency, like
+    /// [`Amount::into`]
+    #[must_use]
+    pub fn to_rust_decimal(self, currency: Currency) -> rust_decimal::Decimal {
+        rust_decimal::Decimal::from_i128_with_scale(i128::from(self.0), currency.exponent())
     }
 }
+
+#[cfg(feature = "rust_decimal")]
+impl Money {
+    /// Creates money from a decimal value and its currency, like
+    /// [`Money::new`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value cannot be represented in the currency's
+    /// minor units, see [`Amount::from_rust_decimal`]
+    pub fn from_rust_decimal(
+        currency: Currency,
+        amount: rust_decimal::Decimal,
+    ) -> Result<Money, AmountError> {
+        Ok(Money {
+            amount: Amount::from_rust_decimal(currency, amount)?,
+            currency,
+        })
+    }
+
+    /// Returns the amount as a decimal, scaled by the currency's exponent
+    #[must_use]
+    pub fn to_rust_decimal(&self) -> rust_decimal::Decimal {
+        self.amount.to_rust_decimal(self.currency)
+    }
+}
+
+/// Converts a value that could not be converted to an amount for the
+/// [`AmountError`], which holds a [`BigDecimal`]
+#[cfg(feature = "rust_decimal")]
+fn big_decimal(amount: rust_decimal::Decimal) -> BigDecimal {
+    amount
+        .to_string()
+        .parse()
+        .unwrap_or_else(|_| BigDecimal::from(0))
+}
@@ -0,0 +1,107 @@
+//! Electronic Commerce Indicators (ECI)
+//!
+//! See: [ECI values](https://docs.checkout.com/payments/authenticate-payments/3d-secure/eci-values)
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The Electronic Commerce Indicator (ECI) security level of a payment
+///
+/// Visa, American Express, JCB, Diners and Cartes Bancaires use the `05`,
+/// `06` and `07` values, Mastercard and Maestro use `02`, `01` and `00`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Eci {
+    /// `05` - The cardholder was fully authenticated
+    VisaAuthenticated,
+
+    /// `06` - Authentication was attempted but not completed
+    VisaAttempted,
+
+    /// `07` - The payment was not authenticated
+    VisaNotAuthenticated,
+
+    /// `02` - The cardholder was fully authenticated
+    MastercardAuthenticated,
+
+    /// `01` - Authentication was attempted but not completed
+    MastercardAttempted,
+
+    /// `00` - The payment was not authenticated
+    MastercardNotAuthenticated,
+
+    /// A value that is not modeled by this library
+    Other(String),
+}
+
+impl Eci {
+    /// Returns the ECI as it is represented by the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Eci::VisaAuthenticated => "05",
+            Eci::VisaAttempted => "06",
+            Eci::VisaNotAuthenticated => "07",
+            Eci::MastercardAuthenticated => "02",
+            Eci::MastercardAttempted => "01",
+            Eci::MastercardNotAuthenticated => "00",
+            Eci::Other(eci) => eci,
+        }
+    }
+
+    /// Returns whether the cardholder was fully authenticated
+    #[must_use]
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self, Eci::VisaAuthenticated | Eci::MastercardAuthenticated)
+    }
+
+    /// Returns whether authentication was attempted but not completed
+    #[must_use]
+    pub fn is_attempted(&self) -> bool {
+        matches!(self, Eci::VisaAttempted | Eci::MastercardAttempted)
+    }
+}
+
+impl FromStr for Eci {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim() {
+            "05" | "5" => Eci::VisaAuthenticated,
+            "06" | "6" => Eci::VisaAttempted,
+            "07" | "7" => Eci::VisaNotAuthenticated,
+            "02" | "2" => Eci::MastercardAuthenticated,
+            "01" | "1" => Eci::MastercardAttempted,
+            "00" | "0" => Eci::MastercardNotAuthenticated,
+            other => Eci::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Eci {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for Eci {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Eci {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let eci = String::deserialize(deserializer)?;
+        match eci.parse() {
+            Ok(eci) => Ok(eci),
+            Err(never) => match never {},
+        }
+    }
+}
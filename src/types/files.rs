@@ -0,0 +1,19 @@
+//! Types for the Files API
+
+use serde::Deserialize;
+
+use super::links::Links;
+
+/// Response to upload a file
+#[derive(Deserialize, Debug, Clone)]
+pub struct UploadFileResponse {
+    /// The file's unique identifier (format: `file_*`), e.g. to attach it to
+    /// dispute evidence
+    pub id: String,
+
+    /// The links related to the file
+    ///
+    /// - Required: `"self"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
@@ -0,0 +1,72 @@
+//! Stable hashing of requests, e.g. to derive idempotency keys or deduplicate
+//! retried jobs in a payment outbox
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::CreatePaymentRequest;
+use crate::audit::{mask_card_number, CARD_OBJECTS};
+
+/// The version of the hashing scheme. It is part of the hashed input so that
+/// hashes from different schemes never collide.
+const STABLE_HASH_VERSION: &str = "checkout-stable-hash-v1";
+
+impl CreatePaymentRequest {
+    /// Returns a stable hex-encoded SHA-256 hash of the request
+    ///
+    /// The hash is computed over canonical JSON (object keys sorted, no
+    /// whitespace) of the request, excluding volatile fields that may differ
+    /// between retries of the same payment:
+    ///
+    /// - `capture_on`, which is usually derived from the current time
+    ///
+    /// Card numbers are masked and CVVs are removed before hashing, so the
+    /// hash does not reveal card details.
+    ///
+    /// # Stability
+    ///
+    /// The hash of a request only changes when the request's serialized form
+    /// changes, e.g. because a field is added to [`CreatePaymentRequest`]
+    /// with a value other than `null`. Changes to the hashing scheme itself
+    /// are considered breaking and only happen in a release that is
+    /// incompatible according to semver.
+    #[must_use]
+    pub fn stable_hash(&self) -> String {
+        let mut value = serde_json::to_value(self).unwrap_or(Value::Null);
+        if let Value::Object(map) = &mut value {
+            map.remove("capture_on");
+        }
+        sanitize(&mut value, None);
+
+        let mut hasher = Sha256::new();
+        hasher.update(STABLE_HASH_VERSION.as_bytes());
+        hasher.update(value.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Masks card numbers, removes CVVs and drops null values so that adding an
+/// optional field doesn't change the hash of requests that don't set it.
+/// `parent` is the field `value` is the value of, if any.
+fn sanitize(value: &mut Value, parent: Option<&str>) {
+    match value {
+        Value::Object(map) => {
+            map.remove("cvv");
+            map.retain(|_, value| !value.is_null());
+            if parent.is_some_and(|parent| CARD_OBJECTS.contains(&parent)) {
+                if let Some(Value::String(number)) = map.get_mut("number") {
+                    *number = mask_card_number(number);
+                }
+            }
+            for (key, value) in map.iter_mut() {
+                sanitize(value, Some(key));
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                sanitize(value, parent);
+            }
+        }
+        _ => {}
+    }
+}
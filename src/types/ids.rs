@@ -0,0 +1,247 @@
+//! Typed identifiers of Checkout resources
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// The unique identifier of a payment (format: `pay_*`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct PaymentId(pub String);
+
+/// The unique identifier of a payment action (format: `act_*`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(transparent)]
+pub struct ActionId(pub String);
+
+impl PaymentId {
+    /// Returns the identifier as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl ActionId {
+    /// Returns the identifier as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<PaymentId> for String {
+    fn from(id: PaymentId) -> String {
+        id.0
+    }
+}
+
+impl From<ActionId> for String {
+    fn from(id: ActionId) -> String {
+        id.0
+    }
+}
+
+impl fmt::Display for PaymentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for ActionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The unique identifier of a processing channel (format: `pc_*`)
+///
+/// Validated when parsed, e.g. from configuration, so that an identifier of
+/// another kind of resource is rejected at startup rather than by the API.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct ProcessingChannelId(String);
+
+/// The unique identifier of an entity (format: `ent_*`)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct EntityId(String);
+
+/// The unique identifier of a sub-entity of a platform (format: `ent_*`)
+///
+/// Sub-entities share the format of entities, the distinct type keeps the
+/// platform's own entity and its sub-entities apart.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct SubEntityId(String);
+
+/// An identifier does not have the expected prefix or contains characters
+/// other than ASCII letters and digits after it, contains the expected prefix
+/// and the original string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidIdError {
+    /// The prefix identifiers of the kind start with, e.g. `pc_`
+    pub prefix: &'static str,
+
+    /// The invalid identifier
+    pub id: String,
+}
+
+impl fmt::Display for InvalidIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid identifier {:?}: must be {} followed by letters and digits",
+            self.id, self.prefix
+        )
+    }
+}
+
+impl std::error::Error for InvalidIdError {}
+
+/// Checks that `id` is `prefix` followed by at least one ASCII letter or digit
+fn validate_id(id: String, prefix: &'static str) -> Result<String, InvalidIdError> {
+    let valid = id.strip_prefix(prefix).is_some_and(|suffix| {
+        !suffix.is_empty() && suffix.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+    if valid {
+        Ok(id)
+    } else {
+        Err(InvalidIdError { prefix, id })
+    }
+}
+
+impl ProcessingChannelId {
+    /// Creates an identifier after validating its format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identifier doesn't start with `pc_`
+    pub fn new(id: impl Into<String>) -> Result<ProcessingChannelId, InvalidIdError> {
+        validate_id(id.into(), "pc_").map(ProcessingChannelId)
+    }
+
+    /// Returns the identifier as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl EntityId {
+    /// Creates an identifier after validating its format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identifier doesn't start with `ent_`
+    pub fn new(id: impl Into<String>) -> Result<EntityId, InvalidIdError> {
+        validate_id(id.into(), "ent_").map(EntityId)
+    }
+
+    /// Returns the identifier as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl SubEntityId {
+    /// Creates an identifier after validating its format
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the identifier doesn't start with `ent_`
+    pub fn new(id: impl Into<String>) -> Result<SubEntityId, InvalidIdError> {
+        validate_id(id.into(), "ent_").map(SubEntityId)
+    }
+
+    /// Returns the identifier as a string slice
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ProcessingChannelId {
+    type Err = InvalidIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ProcessingChannelId::new(s)
+    }
+}
+
+impl FromStr for EntityId {
+    type Err = InvalidIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EntityId::new(s)
+    }
+}
+
+impl FromStr for SubEntityId {
+    type Err = InvalidIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SubEntityId::new(s)
+    }
+}
+
+impl TryFrom<String> for ProcessingChannelId {
+    type Error = InvalidIdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        ProcessingChannelId::new(value)
+    }
+}
+
+impl TryFrom<String> for EntityId {
+    type Error = InvalidIdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        EntityId::new(value)
+    }
+}
+
+impl TryFrom<String> for SubEntityId {
+    type Error = InvalidIdError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        SubEntityId::new(value)
+    }
+}
+
+impl From<ProcessingChannelId> for String {
+    fn from(id: ProcessingChannelId) -> String {
+        id.0
+    }
+}
+
+impl From<EntityId> for String {
+    fn from(id: EntityId) -> String {
+        id.0
+    }
+}
+
+impl From<SubEntityId> for String {
+    fn from(id: SubEntityId) -> String {
+        id.0
+    }
+}
+
+impl fmt::Display for ProcessingChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Display for SubEntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -33,6 +33,32 @@ pub struct Link {
     href: String,
 }
 
+impl Link {
+    /// Returns the link URL
+    #[must_use]
+    pub fn href(&self) -> &str {
+        &self.href
+    }
+}
+
+/// A page of a paginated list response whose `next` link points to the next
+/// page
+pub trait Paginated: serde::de::DeserializeOwned {
+    /// The type of the listed items
+    type Item;
+
+    /// Returns the links of the page
+    fn links(&self) -> Option<&Links>;
+
+    /// Consumes the page, returning its items
+    fn into_items(self) -> Vec<Self::Item>;
+
+    /// Returns the URL of the next page, if there is one
+    fn next_page_url(&self) -> Option<&str> {
+        self.links()?.get(NEXT_LINK).map(Link::href)
+    }
+}
+
 /// A link to the associated request for the current response
 pub const SELF_LINK: &str = "self";
 
@@ -58,5 +84,5 @@ pub const REDIRECT_LINK: &str = "redirect";
 /// A link to the next object
 ///
 /// This link allows you to move to the next page of results in the response.
-/// Responses are paginated at the payout level
+/// Responses are paginated at the payout level. See [`Paginated`]
 pub const NEXT_LINK: &str = "next";
@@ -0,0 +1,151 @@
+//! Types for looking up card metadata and payout eligibility
+//!
+//! See: [Card metadata](https://docs.checkout.com/payments/card-metadata)
+
+use serde::{Deserialize, Serialize};
+
+use super::{CardCategory, CardScheme, CardType};
+
+/// The card to look up
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CardMetadataSource {
+    /// A full card number
+    Card {
+        /// The card number (without separators)
+        number: String,
+    },
+
+    /// The card's Bank Identification Number (BIN)
+    Bin {
+        /// The BIN (6-8 characters)
+        bin: String,
+    },
+
+    /// A card token (format: `tok_*`)
+    Token {
+        /// The card token
+        token: String,
+    },
+
+    /// A stored card instrument (format: `src_*`)
+    Id {
+        /// The instrument's unique identifier
+        id: String,
+    },
+}
+
+/// The level of detail of the card metadata
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CardMetadataFormat {
+    /// Basic card details
+    Basic,
+
+    /// Basic card details and the card's payout eligibility
+    CardPayouts,
+}
+
+/// Request body to look up card metadata
+#[derive(Serialize, Debug, Clone)]
+pub struct CardMetadataRequest {
+    /// The card to look up
+    pub source: CardMetadataSource,
+
+    /// The level of detail of the response (default: basic)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<CardMetadataFormat>,
+}
+
+/// Response to look up card metadata
+#[derive(Deserialize, Debug, Clone)]
+pub struct CardMetadataResponse {
+    /// The card issuer's Bank Identification Number (BIN)
+    pub bin: String,
+
+    /// The card scheme
+    pub scheme: Option<CardScheme>,
+
+    /// The card type
+    pub card_type: Option<CardType>,
+
+    /// The card category
+    pub card_category: Option<CardCategory>,
+
+    /// The name of the card issuer
+    pub issuer: Option<String>,
+
+    /// The card issuer's country (two-letter ISO code) (2 characters)
+    pub issuer_country: Option<String>,
+
+    /// The issuer/card scheme product identifier
+    pub product_id: Option<String>,
+
+    /// The issuer/card scheme product type
+    pub product_type: Option<String>,
+
+    /// The payout eligibility of the card, returned for the `card_payouts`
+    /// format
+    pub card_payouts: Option<CardPayoutsEligibility>,
+}
+
+/// Whether and how fast a card can receive payouts
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayoutEligibility {
+    /// The card can't receive payouts
+    NotSupported,
+
+    /// The card can receive payouts within 2 business days
+    Standard,
+
+    /// The card can receive payouts within 30 minutes
+    FastFunds,
+
+    /// The eligibility is unknown
+    Unknown,
+}
+
+/// The payout eligibility of a card per kind of payout
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct CardPayoutsEligibility {
+    /// Domestic payouts that are not money transfers, e.g. marketplace
+    /// seller payouts
+    pub domestic_non_money_transfer: Option<PayoutEligibility>,
+
+    /// Cross-border payouts that are not money transfers
+    pub cross_border_non_money_transfer: Option<PayoutEligibility>,
+
+    /// Domestic gambling winnings
+    pub domestic_gambling: Option<PayoutEligibility>,
+
+    /// Cross-border gambling winnings
+    pub cross_border_gambling: Option<PayoutEligibility>,
+
+    /// Domestic money transfers
+    pub domestic_money_transfer: Option<PayoutEligibility>,
+
+    /// Cross-border money transfers
+    pub cross_border_money_transfer: Option<PayoutEligibility>,
+}
+
+impl CardPayoutsEligibility {
+    /// Returns the eligibility for payouts that are not money transfers,
+    /// e.g. marketplace seller payouts
+    #[must_use]
+    pub fn non_money_transfer(&self, cross_border: bool) -> PayoutEligibility {
+        let eligibility = if cross_border {
+            self.cross_border_non_money_transfer
+        } else {
+            self.domestic_non_money_transfer
+        };
+        eligibility.unwrap_or(PayoutEligibility::Unknown)
+    }
+
+    /// Returns whether an instant (fast funds) payout that is not a money
+    /// transfer is possible
+    #[must_use]
+    pub fn supports_fast_funds(&self, cross_border: bool) -> bool {
+        self.non_money_transfer(cross_border) == PayoutEligibility::FastFunds
+    }
+}
@@ -1,17 +1,48 @@
 #![allow(missing_docs)]
 
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{borrow::Cow, collections::HashMap, fmt, net::IpAddr, str::FromStr};
 
+pub mod codes;
 pub mod currency;
+pub mod eci;
+pub mod files;
+pub mod hashing;
+pub mod ids;
 pub mod links;
+pub mod metadata;
+pub mod query;
+pub mod reference;
+pub mod reports;
 pub mod requests;
 pub mod responses;
-
+pub mod schemes;
+pub mod scopes;
+pub mod secrets;
+
+pub use crate::customers::*;
+pub use crate::disputes::*;
+pub use crate::instruments::*;
+pub use crate::payments::*;
+pub use crate::payouts::*;
+pub use codes::*;
 pub use currency::*;
+pub use eci::*;
+pub use files::*;
+pub use ids::*;
 use links::Links;
+pub use links::Paginated;
+pub use metadata::*;
+pub use query::*;
+pub use reference::*;
+pub use reports::*;
 pub use requests::*;
 pub use responses::*;
+pub use schemes::*;
+pub use scopes::Scope;
+pub use secrets::*;
 
 /// The details of a payment
 #[derive(Deserialize, Debug, Clone)]
@@ -67,9 +98,10 @@ pub struct PaymentDetails {
     /// The shipping details
     pub shipping: Option<ShippingDescriptor>,
 
-    /// The IP address used to make the payment. Required for some risk checks
-    /// (<= 45 characters)
-    pub payment_ip: Option<String>,
+    /// The IP address used to make the payment. Addresses that can't be
+    /// parsed are ignored
+    #[serde(default, deserialize_with = "deserialize_lenient_ip")]
+    pub payment_ip: Option<IpAddr>,
 
     /// Information about the recipient of the payment's funds. Relevant for
     /// both Account Funding Transactions and VISA or MasterCard domestic UK
@@ -86,15 +118,21 @@ pub struct PaymentDetails {
     /// The final Electronic Commerce Indicator (ECI) security level used to
     /// authorize the payment. Applicable for 3D Secure, digital wallet, and
     /// network token payments
-    pub eci: Option<String>,
+    pub eci: Option<Eci>,
 
     /// The scheme transaction identifier
     pub scheme_id: Option<String>,
 
+    /// The date/time the authorization expires and can no longer be captured
+    pub expires_on: Option<DateTime<Utc>>,
+
     /// A summary of the payment's actions, returned when a session ID is used
     /// to get the payment details
     pub actions: Option<Vec<ActionSummary>>,
 
+    /// The payment's balances
+    pub balances: Option<PaymentBalances>,
+
     /// The links related to the payment
     ///
     /// - Required: `"self"`, `"actions"`
@@ -103,6 +141,138 @@ pub struct PaymentDetails {
     pub links: Option<Links>,
 }
 
+impl PaymentDetails {
+    /// Returns the most recent action of the payment, if the action summary
+    /// was returned (when getting the details by session ID)
+    #[must_use]
+    pub fn latest_action(&self) -> Option<&ActionSummary> {
+        // Actions are ordered by processing date, latest first
+        self.actions.as_ref()?.first()
+    }
+
+    /// Returns whether an approved capture appears in the action summary
+    #[must_use]
+    pub fn was_captured(&self) -> bool {
+        self.has_approved_action(&ActionType::Capture)
+    }
+
+    /// Returns whether an approved refund appears in the action summary
+    #[must_use]
+    pub fn was_refunded(&self) -> bool {
+        self.has_approved_action(&ActionType::Refund)
+    }
+
+    fn has_approved_action(&self, action_type: &ActionType) -> bool {
+        self.actions
+            .iter()
+            .flatten()
+            .any(|action| action.is_approved(action_type))
+    }
+
+    /// Returns the payment amount as a decimal, scaled by the currency's
+    /// exponent
+    #[must_use]
+    pub fn amount_decimal(&self) -> BigDecimal {
+        self.amount.into(self.currency)
+    }
+
+    /// Returns whether the authorization has expired at the given time, in
+    /// which case capturing it will fail
+    #[must_use]
+    pub fn authorization_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_on.is_some_and(|expires_on| expires_on <= now)
+    }
+
+    /// Returns the payment's metadata, which is empty if none was attached
+    #[must_use]
+    pub fn metadata(&self) -> Cow<'_, Metadata> {
+        self.metadata
+            .as_ref()
+            .map_or_else(|| Cow::Owned(Metadata::new()), Cow::Borrowed)
+    }
+
+    /// Returns the value of a metadata key, if it is set
+    #[must_use]
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata.as_ref()?.get(key).map(String::as_str)
+    }
+
+    /// Returns the user-defined field `udf1` to `udf5`, if it is set. Returns
+    /// `None` for any other `n`.
+    #[must_use]
+    pub fn udf(&self, n: u8) -> Option<&str> {
+        let key = UDF_KEYS.get(usize::from(n).checked_sub(1)?)?;
+        self.metadata_value(key)
+    }
+
+    /// Returns the last four digits of the recipient's account number, if
+    /// there is a recipient with an account number
+    #[must_use]
+    pub fn recipient_account_last4(&self) -> Option<&str> {
+        let number = self.recipient.as_ref()?.account_number.as_deref()?;
+        let start = number.char_indices().rev().nth(3).map_or(0, |(i, _)| i);
+        Some(&number[start..])
+    }
+
+    /// Returns the recipient's first and last names, separated by a space,
+    /// if there is a recipient with a name
+    #[must_use]
+    pub fn recipient_name(&self) -> Option<String> {
+        let recipient = self.recipient.as_ref()?;
+        let name = [&recipient.first_name, &recipient.last_name]
+            .iter()
+            .filter_map(|name| name.as_deref())
+            .filter(|name| !name.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if name.is_empty() {
+            None
+        } else {
+            Some(name)
+        }
+    }
+}
+
+/// The balances of a payment, in the payment currency's minor units
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct PaymentBalances {
+    /// The total amount that has been authorized
+    pub total_authorized: Amount,
+
+    /// The total amount that has been voided
+    pub total_voided: Amount,
+
+    /// The amount that can still be voided
+    pub available_to_void: Amount,
+
+    /// The total amount that has been captured
+    pub total_captured: Amount,
+
+    /// The amount that can still be captured
+    pub available_to_capture: Amount,
+
+    /// The total amount that has been refunded
+    pub total_refunded: Amount,
+
+    /// The amount that can still be refunded
+    pub available_to_refund: Amount,
+}
+
+/// Deserializes an IP address from a string of up to 45 characters, dropping
+/// any IPv6 zone identifier (e.g. `fe80::1%eth0`) and ignoring addresses that
+/// can't be parsed
+fn deserialize_lenient_ip<'de, D>(deserializer: D) -> Result<Option<IpAddr>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let ip: Option<String> = Option::deserialize(deserializer)?;
+    Ok(ip.and_then(|ip| {
+        let ip = ip.trim();
+        let ip = ip.split('%').next().unwrap_or(ip);
+        ip.parse().ok()
+    }))
+}
+
 /// The payment source type
 ///
 /// Note: To make a payment with full card details, you must be SAQ D PCI
@@ -129,7 +299,7 @@ pub enum PaymentRequestSource {
 
         /// The card verification value/code. 3 digits, except for Amex (4
         /// digits)
-        cvv: Option<String>,
+        cvv: Option<Cvv>,
 
         /// This must be set to true for payments that use stored card details
         /// (default: false)
@@ -150,6 +320,23 @@ pub enum PaymentRequestSource {
         /// The token retrieved by posting card details to `/tokens` beforehand
         token: String,
     },
+
+    /// An existing payment source, such as a stored card instrument
+    #[serde(rename = "id")]
+    Id {
+        /// The payment source identifier (format: `src_*`)
+        id: String,
+
+        /// The card verification value/code, if required by the source
+        cvv: Option<Cvv>,
+    },
+
+    /// The default payment instrument of an existing customer
+    #[serde(rename = "customer")]
+    Customer {
+        /// The customer's unique identifier (format: `cus_*`)
+        id: String,
+    },
 }
 
 /// The payout destination type
@@ -186,7 +373,7 @@ pub enum PaymentRequestDestination {
 }
 
 /// A type of payment
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PaymentType {
     /// A regular payment
     Regular,
@@ -197,6 +384,65 @@ pub enum PaymentType {
     /// A Merchant Offline Telephone Order
     #[serde(rename = "MOTO")]
     Moto,
+
+    /// One of a fixed number of payments for a single purchase, e.g. buy now
+    /// pay later
+    Installment,
+
+    /// A payment with stored card details at a time that is not fixed in
+    /// advance, e.g. an automatic top-up (unscheduled card-on-file)
+    Unscheduled,
+}
+
+/// Could not parse a value as one of an enum's API representations, contains
+/// the original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseVariantError(pub String);
+
+impl PaymentType {
+    /// Returns the payment type as it is represented by the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PaymentType::Regular => "Regular",
+            PaymentType::Recurring => "Recurring",
+            PaymentType::Moto => "MOTO",
+            PaymentType::Installment => "Installment",
+            PaymentType::Unscheduled => "Unscheduled",
+        }
+    }
+
+    /// Returns whether the payment type uses stored card details, in which
+    /// case the scheme transaction id of the first payment must be sent as
+    /// the `previous_payment_id` of subsequent merchant-initiated payments
+    #[must_use]
+    pub fn is_card_on_file(self) -> bool {
+        matches!(
+            self,
+            PaymentType::Recurring | PaymentType::Installment | PaymentType::Unscheduled
+        )
+    }
+}
+
+impl fmt::Display for PaymentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PaymentType {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Regular" => Ok(PaymentType::Regular),
+            "Recurring" => Ok(PaymentType::Recurring),
+            "MOTO" => Ok(PaymentType::Moto),
+            "Installment" => Ok(PaymentType::Installment),
+            "Unscheduled" => Ok(PaymentType::Unscheduled),
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
 }
 
 /// A phone number
@@ -210,7 +456,7 @@ pub struct PhoneNumber {
 }
 
 /// A physical address
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Address {
     /// The first line of the address (<= 200 characters)
     pub address_line1: Option<String>,
@@ -300,10 +546,13 @@ pub struct _3DSRequest {
     pub exemption: Option<ScaExemption>,
 }
 
+/// Information for 3D Secure payments, named without the leading underscore
+pub type ThreeDsRequest = _3DSRequest;
+
 /// A type of exemption from 3DS authentication
 ///
 /// See: [Possible SCA exemptions](https://docs.checkout.com/risk-management/sca-compliance-guide)
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ScaExemption {
     /// Payments below €30 are considered low-value and may be exempt. However,
     /// the customer’s bank may still trigger strong authentication if, within
@@ -324,12 +573,101 @@ pub enum ScaExemption {
     /// be exempt.
     #[serde(rename = "trusted_listing")]
     TrustedListing,
+
+    /// The acquirer or issuer assessed the payment as low-risk in real time.
+    /// Available up to a value that depends on the acquirer's fraud rate.
+    #[serde(rename = "transaction_risk_assessment")]
+    TransactionRiskAssessment,
+
+    /// Subsequent payments of a series with the same amount and payee, after
+    /// the first payment was authenticated
+    #[serde(rename = "recurring_operation")]
+    RecurringOperation,
+
+    /// The payment data was shared with the issuer for its risk assessment
+    #[serde(rename = "data_share")]
+    DataShare,
+
+    /// A scheme program for merchants and acquirers with low fraud rates,
+    /// e.g. Visa Trusted Listing
+    #[serde(rename = "low_risk_program")]
+    LowRiskProgram,
+
+    /// The authentication was delegated to a third party, e.g. a digital
+    /// wallet
+    #[serde(rename = "sca_delegation")]
+    ScaDelegation,
+
+    /// The payment is out of the scope of SCA, e.g. a one-leg-out or
+    /// merchant-initiated payment
+    #[serde(rename = "out_of_sca_scope")]
+    OutOfScaScope,
+
+    /// The issuer's access control server was unavailable
+    #[serde(rename = "3ds_outage")]
+    ThreeDsOutage,
+
+    /// Any other exemption
+    #[serde(rename = "other")]
+    Other,
+}
+
+impl ScaExemption {
+    /// Returns the exemption as it is represented by the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScaExemption::LowValue => "low_value",
+            ScaExemption::SecureCorporatePayment => "secure_corporate_payment",
+            ScaExemption::TrustedListing => "trusted_listing",
+            ScaExemption::TransactionRiskAssessment => "transaction_risk_assessment",
+            ScaExemption::RecurringOperation => "recurring_operation",
+            ScaExemption::DataShare => "data_share",
+            ScaExemption::LowRiskProgram => "low_risk_program",
+            ScaExemption::ScaDelegation => "sca_delegation",
+            ScaExemption::OutOfScaScope => "out_of_sca_scope",
+            ScaExemption::ThreeDsOutage => "3ds_outage",
+            ScaExemption::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ScaExemption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ScaExemption {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "low_value" => Ok(ScaExemption::LowValue),
+            "secure_corporate_payment" => Ok(ScaExemption::SecureCorporatePayment),
+            "trusted_listing" => Ok(ScaExemption::TrustedListing),
+            "transaction_risk_assessment" => Ok(ScaExemption::TransactionRiskAssessment),
+            "recurring_operation" => Ok(ScaExemption::RecurringOperation),
+            "data_share" => Ok(ScaExemption::DataShare),
+            "low_risk_program" => Ok(ScaExemption::LowRiskProgram),
+            "sca_delegation" => Ok(ScaExemption::ScaDelegation),
+            "out_of_sca_scope" => Ok(ScaExemption::OutOfScaScope),
+            "3ds_outage" => Ok(ScaExemption::ThreeDsOutage),
+            "other" => Ok(ScaExemption::Other),
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RiskRequest {
     /// Whether a risk assessment should be performed (default: true)
     pub enabled: bool,
+
+    /// The device session identifier collected by the Risk.js library, which
+    /// improves the accuracy of the assessment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_session_id: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -353,19 +691,37 @@ pub struct PaymentRecipient {
     pub last_name: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct PaymentProcessingDescriptor {
     /// Indicates whether the payment is an Account Funding Transaction
     pub aft: bool,
-    // /// Processing information required for dLocal payments.
-    // dlocal: Option<DLocalPaymentProcessing>,
+
+    /// The merchant category code (MCC) the payment is processed with, if it
+    /// differs from the one configured on your account (4 digits)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merchant_category_code: Option<String>,
+
+    /// The purpose of an Account Funding Transaction, e.g.
+    /// `"family_support"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+
+    /// The scheme to process a co-badged card with, e.g. the scheme the
+    /// customer chose at checkout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preferred_scheme: Option<PreferredScheme>,
+
+    /// Processing information required for dLocal payments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dlocal: Option<DLocalProcessing>,
 }
 
 /// Used to store metadata on customers, payments, disputes, etc.
 ///
 /// Allows you to store additional information about a transaction with custom
 /// fields and up to five user-defined fields (`udf1` to `udf5`), which can be
-/// used for reporting purposes. `udf1` is also used for some of our risk rules
+/// used for reporting purposes. `udf1` is also used for some of our risk rules.
+/// Set the user-defined fields with [`UserDefinedFields`].
 ///
 /// # Example
 ///
@@ -377,6 +733,19 @@ pub struct PaymentProcessingDescriptor {
 /// ```
 pub type Metadata = HashMap<String, String>;
 
+/// Fields that are sent along with a request body but are not yet modeled by
+/// this library. They are flattened into the top level of the body.
+///
+/// # Example
+///
+/// ```json
+/// {
+///     "amount": 1000,
+///     "new_field": { "enabled": true }
+/// }
+/// ```
+pub type ExtraFields = HashMap<String, serde_json::Value>;
+
 /// The response when a payment was processed successfully
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PaymentProcessed {
@@ -434,11 +803,14 @@ pub struct PaymentProcessed {
     /// The final Electronic Commerce Indicator (ECI) security level used to
     /// authorize the payment. Applicable for 3D Secure, digital wallet, and
     /// network token payments
-    pub eci: Option<String>,
+    pub eci: Option<Eci>,
 
     /// The scheme transaction identifier
     pub scheme_id: Option<String>,
 
+    /// The date/time the authorization expires and can no longer be captured
+    pub expires_on: Option<DateTime<Utc>>,
+
     /// The links related to the payment
     ///
     /// - Required: `"self"`, `"actions"`
@@ -447,6 +819,47 @@ pub struct PaymentProcessed {
     pub links: Option<Links>,
 }
 
+impl PaymentProcessed {
+    /// Returns the payment amount as a decimal, scaled by the currency's
+    /// exponent
+    #[must_use]
+    pub fn amount_decimal(&self) -> BigDecimal {
+        self.amount.into(self.currency)
+    }
+
+    /// Returns whether the authorization has expired at the given time, in
+    /// which case capturing it will fail
+    #[must_use]
+    pub fn authorization_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_on.is_some_and(|expires_on| expires_on <= now)
+    }
+
+    /// Returns the normalized reason the payment was declined, or `None` if
+    /// it was approved
+    #[must_use]
+    pub fn decline_reason(&self) -> Option<DeclineReason> {
+        if self.approved {
+            return None;
+        }
+        DeclineReason::from_response(&self.response_code, self.response_summary.as_deref())
+    }
+
+    /// Returns the scheme the payment was processed with, falling back to
+    /// the card's scheme. Use it to check that the preferred scheme of a
+    /// co-badged card was honored.
+    #[must_use]
+    pub fn processed_scheme(&self) -> Option<&CardScheme> {
+        let processed = self
+            .processing
+            .as_ref()
+            .and_then(|processing| processing.scheme.as_ref());
+        processed.or_else(|| match self.source.as_ref()? {
+            PaymentProcessedSource::Card { scheme, .. } => scheme.as_ref(),
+            _ => None,
+        })
+    }
+}
+
 /// The response when a payment is being processed asynchronously or further
 /// action is required
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -476,6 +889,51 @@ pub struct PendingPayment {
     pub links: Option<Links>,
 }
 
+/// Why a payment is pending, and what the frontend needs to do about it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PendingReason {
+    /// The customer must be redirected to authenticate with 3D Secure
+    ThreeDsRedirect,
+
+    /// The customer must be redirected to the alternative payment method,
+    /// e.g. to approve the payment in their banking app
+    ApmRedirect,
+
+    /// The payment is being processed asynchronously. Wait for its webhook
+    /// notification or poll its details.
+    AsyncProcessing,
+}
+
+impl PendingPayment {
+    /// Returns the URL the customer must be redirected to, if any. Links that
+    /// are not valid URLs are ignored.
+    #[must_use]
+    pub fn redirect_url(&self) -> Option<reqwest::Url> {
+        let link = self.links.as_ref()?.get(links::REDIRECT_LINK)?;
+        reqwest::Url::parse(link.href()).ok()
+    }
+
+    /// Returns whether the customer must be redirected to complete the
+    /// payment
+    #[must_use]
+    pub fn requires_redirect(&self) -> bool {
+        self.redirect_url().is_some()
+    }
+
+    /// Returns why the payment is pending, derived from its redirect link and
+    /// 3D Secure information
+    #[must_use]
+    pub fn pending_reason(&self) -> PendingReason {
+        if !self.requires_redirect() {
+            PendingReason::AsyncProcessing
+        } else if self.three_ds.is_some() {
+            PendingReason::ThreeDsRedirect
+        } else {
+            PendingReason::ApmRedirect
+        }
+    }
+}
+
 /// The status of the payment
 ///
 /// See: [Get Payment Details](https://docs.checkout.com/payments/manage-payments/get-payment-details)
@@ -498,6 +956,55 @@ pub enum PaymentStatus {
     Expired,
 }
 
+impl PaymentStatus {
+    /// Returns the status as it is represented by the API
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PaymentStatus::Authorized => "Authorized",
+            PaymentStatus::Pending => "Pending",
+            PaymentStatus::CardVerified => "Card Verified",
+            PaymentStatus::Voided => "Voided",
+            PaymentStatus::PartiallyCaptured => "Partially Captured",
+            PaymentStatus::Captured => "Captured",
+            PaymentStatus::PartiallyRefunded => "Partially Refunded",
+            PaymentStatus::Refunded => "Refunded",
+            PaymentStatus::Declined => "Declined",
+            PaymentStatus::Cancelled => "Cancelled",
+            PaymentStatus::Paid => "Paid",
+            PaymentStatus::Expired => "Expired",
+        }
+    }
+}
+
+impl fmt::Display for PaymentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for PaymentStatus {
+    type Err = ParseVariantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Authorized" => Ok(PaymentStatus::Authorized),
+            "Pending" => Ok(PaymentStatus::Pending),
+            "Card Verified" => Ok(PaymentStatus::CardVerified),
+            "Voided" => Ok(PaymentStatus::Voided),
+            "Partially Captured" => Ok(PaymentStatus::PartiallyCaptured),
+            "Captured" => Ok(PaymentStatus::Captured),
+            "Partially Refunded" => Ok(PaymentStatus::PartiallyRefunded),
+            "Refunded" => Ok(PaymentStatus::Refunded),
+            "Declined" => Ok(PaymentStatus::Declined),
+            "Cancelled" => Ok(PaymentStatus::Cancelled),
+            "Paid" => Ok(PaymentStatus::Paid),
+            "Expired" => Ok(PaymentStatus::Expired),
+            val => Err(ParseVariantError(val.to_owned())),
+        }
+    }
+}
+
 impl Default for PaymentStatus {
     fn default() -> PaymentStatus {
         PaymentStatus::Pending
@@ -535,6 +1042,45 @@ pub struct _3dsStatus {
     /// Specifies an exemption reason so that the payment is not processed
     /// using 3D Secure authentication
     pub exemption: Option<ScaExemption>,
+
+    /// The exemption that was applied by the card scheme or issuer, which
+    /// may differ from the requested exemption. `"none"` if no exemption was
+    /// applied. See [`_3dsStatus::sca_outcome`].
+    pub exemption_applied: Option<String>,
+
+    /// Whether the cardholder was challenged during authentication
+    pub challenged: Option<bool>,
+
+    /// The 3D Secure 2 authentication flow
+    pub flow_type: Option<_3dsFlowType>,
+
+    /// The amount that was authenticated, which may differ from the payment
+    /// amount
+    pub authentication_amount: Option<Amount>,
+
+    /// The reason the authentication response status was returned
+    pub authentication_status_reason: Option<String>,
+
+    /// The transaction identifier assigned by the 3D Secure 2 directory server
+    pub ds_transaction_id: Option<String>,
+
+    /// The risk score calculated by the issuer's access control server
+    pub score: Option<String>,
+}
+
+/// The 3D Secure 2 authentication flow
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum _3dsFlowType {
+    /// The cardholder was asked to authenticate
+    Challenged,
+
+    /// The issuer authenticated the cardholder without a challenge
+    Frictionless,
+
+    /// The authentication was delegated to a third party, such as a digital
+    /// wallet
+    FrictionlessDelegated,
 }
 
 /// The 3D Secure enrollment status
@@ -578,6 +1124,56 @@ pub enum _3dsAuthenticationStatus {
 pub struct RiskResults {
     /// Whether or not the payment was flagged by a risk check
     pub flagged: bool,
+
+    /// The risk score of the payment (0-100), if available
+    pub score: Option<u32>,
+
+    /// The decision of the pre-authorization risk assessment, if available
+    pub decision: Option<RiskDecision>,
+
+    /// The risk rules triggered by the payment, if available
+    #[serde(default)]
+    pub triggered_rules: Vec<RiskRule>,
+}
+
+impl RiskResults {
+    /// Returns whether the payment should be reviewed before fulfilling the
+    /// order, i.e. it was flagged or the assessment asked for a challenge
+    #[must_use]
+    pub fn requires_review(&self) -> bool {
+        self.flagged || self.decision == Some(RiskDecision::Challenge)
+    }
+}
+
+/// The decision of a risk assessment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskDecision {
+    /// The payment can proceed
+    Approve,
+
+    /// The payment should be declined
+    Decline,
+
+    /// The customer should be challenged, e.g. with 3D Secure
+    Challenge,
+
+    /// A decision that is not modeled by this library
+    #[serde(other)]
+    Other,
+}
+
+/// A risk rule triggered by a payment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RiskRule {
+    /// The identifier of the rule
+    pub id: Option<String>,
+
+    /// The name of the rule
+    pub name: Option<String>,
+
+    /// The decision the rule made
+    pub decision: Option<RiskDecision>,
 }
 
 /// The processed payment's source type
@@ -586,7 +1182,7 @@ pub struct RiskResults {
 /// card token (token`, source ID, etc.), this will be `card`; otherwise it
 /// will be the name of the alternative payment method
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "type", remote = "Self")]
 pub enum PaymentProcessedSource {
     /// A debit/credit/etc card
     #[serde(rename = "card")]
@@ -612,7 +1208,7 @@ pub enum PaymentProcessedSource {
         name: Option<String>,
 
         /// The card scheme
-        scheme: Option<String>,
+        scheme: Option<CardScheme>,
 
         /// The last four digits of the card number
         last4: String,
@@ -658,11 +1254,212 @@ pub enum PaymentProcessedSource {
         /// Apple Pay, Google Pay)
         payment_account_reference: Option<String>,
     },
+
+    /// A `PayPal` account
+    #[serde(rename = "paypal")]
+    PayPal {
+        /// The `PayPal` order or payer identifier
+        id: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A Klarna payment
+    #[serde(rename = "klarna")]
+    Klarna {
+        /// The Klarna order identifier
+        id: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// An iDEAL bank transfer
+    #[serde(rename = "ideal")]
+    Ideal {
+        /// The description shown on the customer's bank statement
+        description: Option<String>,
+
+        /// The BIC of the customer's bank
+        bic: Option<String>,
+
+        /// The masked IBAN of the customer's account
+        iban: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A Sofort bank transfer
+    #[serde(rename = "sofort")]
+    Sofort {
+        /// The BIC of the customer's bank
+        bic: Option<String>,
+
+        /// The masked IBAN of the customer's account
+        iban: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A SEPA Direct Debit
+    #[serde(rename = "sepa")]
+    Sepa {
+        /// The payment source identifier that can be used for subsequent
+        /// payments
+        id: Option<String>,
+
+        /// The reference of the direct debit mandate
+        mandate_reference: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// An ACH Direct Debit
+    #[serde(rename = "ach")]
+    Ach {
+        /// The payment source identifier that can be used for subsequent
+        /// payments
+        id: Option<String>,
+
+        /// The type of the account
+        account_type: Option<String>,
+
+        /// The last four digits of the account number
+        last4: Option<String>,
+
+        /// Uniquely identifies the account number
+        fingerprint: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// An Alipay payment
+    #[serde(rename = "alipay")]
+    Alipay {
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A Giropay bank transfer
+    #[serde(rename = "giropay")]
+    Giropay {
+        /// The BIC of the customer's bank
+        bic: Option<String>,
+
+        /// The masked IBAN of the customer's account
+        iban: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A Bancontact payment
+    #[serde(rename = "bancontact")]
+    Bancontact {
+        /// The BIC of the customer's bank
+        bic: Option<String>,
+
+        /// The masked IBAN of the customer's account
+        iban: Option<String>,
+
+        /// Additional fields returned for the source
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A source type that is not modeled by this library
+    #[serde(skip)]
+    Other {
+        /// The source type
+        ty: String,
+
+        /// The source as it was returned
+        raw: serde_json::Value,
+    },
+}
+
+impl PaymentProcessedSource {
+    const TYPES: &'static [&'static str] = &[
+        "card",
+        "paypal",
+        "klarna",
+        "ideal",
+        "sofort",
+        "sepa",
+        "ach",
+        "alipay",
+        "giropay",
+        "bancontact",
+    ];
+}
+
+impl Serialize for PaymentProcessedSource {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            PaymentProcessedSource::Other { raw, .. } => raw.serialize(serializer),
+            source => PaymentProcessedSource::serialize(source, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PaymentProcessedSource {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_tagged(
+            deserializer,
+            PaymentProcessedSource::TYPES,
+            PaymentProcessedSource::deserialize,
+            |ty, raw| PaymentProcessedSource::Other { ty, raw },
+        )
+    }
+}
+
+/// Deserializes an internally tagged enum whose tags are listed in `types`,
+/// falling back to `other` for tags that are not modeled by this library
+fn deserialize_tagged<'de, D, T>(
+    deserializer: D,
+    types: &[&str],
+    known: fn(serde_json::Value) -> Result<T, serde_json::Error>,
+    other: fn(String, serde_json::Value) -> T,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = serde_json::Value::deserialize(deserializer)?;
+    let ty = raw
+        .get("type")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+
+    if types.contains(&ty.as_str()) {
+        known(raw).map_err(serde::de::Error::custom)
+    } else {
+        Ok(other(ty, raw))
+    }
 }
 
 /// The processed payment's destination type
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "type", remote = "Self")]
 pub enum PaymentProcessedDestination {
     /// A debit/credit/etc card
     #[serde(rename = "card")]
@@ -688,7 +1485,7 @@ pub enum PaymentProcessedDestination {
         name: Option<String>,
 
         /// The card scheme
-        scheme: Option<String>,
+        scheme: Option<CardScheme>,
 
         /// The last four digits of the card number
         last4: String,
@@ -704,8 +1501,8 @@ pub enum PaymentProcessedDestination {
         /// The card type
         card_type: Option<CardType>,
 
-        /// The card category
-        card_category: CardCategory,
+        /// The card category, if known
+        card_category: Option<CardCategory>,
 
         /// The name of the card issuer
         issuer: Option<String>,
@@ -734,10 +1531,87 @@ pub enum PaymentProcessedDestination {
         /// Apple Pay, Google Pay)
         payment_account_reference: Option<String>,
     },
+
+    /// A bank account, with its identifiers masked
+    #[serde(rename = "bank_account")]
+    BankAccount {
+        /// The instrument identifier of the account, if it was stored
+        id: Option<String>,
+
+        /// The type of the account
+        account_type: Option<BankAccountType>,
+
+        /// The masked account number
+        account_number: Option<String>,
+
+        /// The code that identifies the bank (e.g. a sort code or routing
+        /// number)
+        bank_code: Option<String>,
+
+        /// The masked International Bank Account Number
+        iban: Option<String>,
+
+        /// The 8 or 11 character SWIFT/BIC code of the bank
+        swift_bic: Option<String>,
+
+        /// The two-letter ISO country code of the account (2 characters)
+        country: Option<String>,
+
+        /// The three-letter ISO currency code of the account
+        currency: Option<Currency>,
+
+        /// Uniquely identifies the account, e.g. to reconcile payouts to the
+        /// same account
+        fingerprint: Option<String>,
+
+        /// Additional fields returned for the destination
+        #[serde(flatten)]
+        extra: ExtraFields,
+    },
+
+    /// A destination type that is not modeled by this library
+    #[serde(skip)]
+    Other {
+        /// The destination type
+        ty: String,
+
+        /// The destination as it was returned
+        raw: serde_json::Value,
+    },
+}
+
+impl PaymentProcessedDestination {
+    const TYPES: &'static [&'static str] = &["card", "bank_account"];
+}
+
+impl Serialize for PaymentProcessedDestination {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            PaymentProcessedDestination::Other { raw, .. } => raw.serialize(serializer),
+            destination => PaymentProcessedDestination::serialize(destination, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PaymentProcessedDestination {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_tagged(
+            deserializer,
+            PaymentProcessedDestination::TYPES,
+            PaymentProcessedDestination::deserialize,
+            |ty, raw| PaymentProcessedDestination::Other { ty, raw },
+        )
+    }
 }
 
 /// A card's type
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum CardType {
     Credit,
@@ -749,14 +1623,71 @@ pub enum CardType {
 }
 
 /// A card's category
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "UPPERCASE")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CardCategory {
     Consumer,
     Commercial,
+
+    /// A category that is not modeled by this library, e.g. `"ALL"`
+    Other(String),
+}
+
+impl CardCategory {
+    /// Returns the category as it is represented by the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            CardCategory::Consumer => "CONSUMER",
+            CardCategory::Commercial => "COMMERCIAL",
+            CardCategory::Other(category) => category,
+        }
+    }
+}
+
+impl FromStr for CardCategory {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().to_ascii_uppercase().as_str() {
+            "CONSUMER" => CardCategory::Consumer,
+            "COMMERCIAL" => CardCategory::Commercial,
+            _ => CardCategory::Other(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for CardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CardCategory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CardCategory {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let category = String::deserialize(deserializer)?;
+        match category.parse() {
+            Ok(category) => Ok(category),
+            Err(never) => match never {},
+        }
+    }
 }
 
 /// Identifying fields for a customer
+///
+/// Use [`Client::get_customer`](crate::Client::get_customer) to get the
+/// customer's phone, metadata and stored instruments as [`CustomerDetails`].
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CustomerInfo {
     /// The customer's unique identifier. This can be passed as a source when
@@ -778,84 +1709,46 @@ pub struct PaymentProcessingInfo {
 
     /// A unique identifier for the transaction generated by the acquirer
     pub acquirer_transaction_id: Option<String>,
-}
-
-/// A shortened summary of a payment action
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ActionSummary {
-    /// The unique identifier of the payment action (format: `act_*`)
-    id: String,
 
-    /// The type of action
-    #[serde(rename = "type")]
-    ty: String,
-
-    /// The Gateway response code
-    response_code: String,
-
-    /// The Gateway response summary
-    response_summary: Option<String>,
-}
-
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Action {
-    /// The unique identifier of the payment action (format: `act_*`)
-    id: String,
-
-    /// The type of action
-    #[serde(rename = "type")]
-    ty: String,
-
-    /// The date/time the action was processed
-    processed_on: String,
-
-    // The action amount
-    amount: u64,
-
-    /// Whether the action was successful
-    approved: Option<bool>,
+    /// A unique identifier for the capture that is submitted to the card
+    /// scheme during processing
+    pub acquirer_reference_number: Option<String>,
 
-    /// The acquirer authorization code for cards
-    auth_code: Option<String>,
+    /// The scheme's advice on whether and when to retry a declined payment
+    pub merchant_advice_code: Option<String>,
 
-    /// The Gateway response code
-    response_code: String,
+    /// A description of the merchant advice code
+    pub merchant_advice_code_description: Option<String>,
 
-    /// The Gateway response summary
-    response_summary: Option<String>,
+    /// The identifier of the merchant with the card scheme
+    pub scheme_merchant_id: Option<String>,
 
-    /// Your reference for the action
-    reference: Option<String>,
+    /// The scheme the payment was processed with, which for a co-badged
+    /// card may differ from the card's scheme
+    pub scheme: Option<CardScheme>,
 
-    /// Returns information related to the processing of the payment
-    processing: Option<ActionProcessingInfo>,
+    /// The status of the payment with the alternative payment method partner
+    pub partner_status: Option<String>,
 
-    /// A set of key-value pairs that you can attach to an action
-    metadata: Metadata,
-}
+    /// The transaction identifier of the alternative payment method partner
+    pub partner_transaction_id: Option<String>,
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ActionProcessingInfo {
-    /// A unique identifier for the authorization that is submitted to the card
-    /// scheme during processing
-    pub retrieval_reference_number: Option<String>,
+    /// The order identifier of the alternative payment method partner
+    pub partner_order_id: Option<String>,
 
-    /// A unique identifier for the capture that is submitted to the card
-    /// scheme during processing
-    pub acquirer_reference_number: Option<String>,
+    /// The session identifier of the alternative payment method partner
+    pub partner_session_id: Option<String>,
 
-    /// A unique identifier for the transaction generated by the acquirer
-    pub acquirer_transaction_id: Option<String>,
+    /// The payment identifier of the alternative payment method partner
+    pub partner_payment_id: Option<String>,
 }
 
-/// The type of an action
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub enum ActionType {
-    Authorization,
-    #[serde(rename = "Card Verification")]
-    CardVerification,
-    Void,
-    Capture,
-    Refund,
-    Payout,
+impl PaymentProcessingInfo {
+    /// Returns the classified merchant advice code of a declined payment
+    #[must_use]
+    pub fn merchant_advice(&self) -> Option<MerchantAdviceCode> {
+        self.merchant_advice_code
+            .as_deref()
+            .map(MerchantAdviceCode::from)
+    }
 }
@@ -0,0 +1,343 @@
+//! Typed query parameters for list endpoints
+//!
+//! # Example
+//!
+//! ```ignore
+//! let filter = DisputeFilter::default()
+//!     .status(DisputeStatus::EvidenceRequired)
+//!     .from(from)
+//!     .limit(50);
+//! ```
+
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer};
+
+use super::{ActionId, Currency, DisputeStatus, EntityId, PaymentId};
+
+/// Query parameters to filter disputes
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DisputeFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<DateTime<Utc>>,
+
+    #[serde(
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_statuses"
+    )]
+    statuses: Vec<DisputeStatus>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_reference: Option<String>,
+}
+
+impl DisputeFilter {
+    /// The number of disputes to return (1-250, default: 50)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> DisputeFilter {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The number of disputes to skip
+    #[must_use]
+    pub fn skip(mut self, skip: u32) -> DisputeFilter {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Only return disputes last updated at or after this time
+    #[must_use]
+    pub fn from(mut self, from: DateTime<Utc>) -> DisputeFilter {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only return disputes last updated before this time
+    #[must_use]
+    pub fn to(mut self, to: DateTime<Utc>) -> DisputeFilter {
+        self.to = Some(to);
+        self
+    }
+
+    /// Only return disputes with this status. Can be called multiple times to
+    /// match any of several statuses
+    #[must_use]
+    pub fn status(mut self, status: DisputeStatus) -> DisputeFilter {
+        self.statuses.push(status);
+        self
+    }
+
+    /// Only return disputes of this payment (format: `pay_*`)
+    #[must_use]
+    pub fn payment_id(mut self, payment_id: String) -> DisputeFilter {
+        self.payment_id = Some(payment_id);
+        self
+    }
+
+    /// Only return disputes of payments with this reference
+    #[must_use]
+    pub fn payment_reference(mut self, payment_reference: String) -> DisputeFilter {
+        self.payment_reference = Some(payment_reference);
+        self
+    }
+}
+
+fn serialize_statuses<S>(statuses: &[DisputeStatus], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let statuses: Vec<&str> = statuses.iter().map(|status| status.as_str()).collect();
+    serializer.serialize_str(&statuses.join(","))
+}
+
+/// Query parameters to filter events
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct EventFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skip: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<DateTime<Utc>>,
+}
+
+impl EventFilter {
+    /// Only return events of this payment (format: `pay_*`)
+    #[must_use]
+    pub fn payment_id(mut self, payment_id: String) -> EventFilter {
+        self.payment_id = Some(payment_id);
+        self
+    }
+
+    /// Only return events of payments with this reference
+    #[must_use]
+    pub fn reference(mut self, reference: String) -> EventFilter {
+        self.reference = Some(reference);
+        self
+    }
+
+    /// The number of events to return (default: 10)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> EventFilter {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The number of events to skip
+    #[must_use]
+    pub fn skip(mut self, skip: u32) -> EventFilter {
+        self.skip = Some(skip);
+        self
+    }
+
+    /// Only return events created at or after this time
+    #[must_use]
+    pub fn from(mut self, from: DateTime<Utc>) -> EventFilter {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only return events created before this time
+    #[must_use]
+    pub fn to(mut self, to: DateTime<Utc>) -> EventFilter {
+        self.to = Some(to);
+        self
+    }
+}
+
+/// Query parameters to filter reports
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct ReportFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_after: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_before: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_id: Option<EntityId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination_token: Option<String>,
+}
+
+impl ReportFilter {
+    /// Only return reports created at or after this time
+    #[must_use]
+    pub fn created_after(mut self, created_after: DateTime<Utc>) -> ReportFilter {
+        self.created_after = Some(created_after);
+        self
+    }
+
+    /// Only return reports created before this time
+    #[must_use]
+    pub fn created_before(mut self, created_before: DateTime<Utc>) -> ReportFilter {
+        self.created_before = Some(created_before);
+        self
+    }
+
+    /// Only return reports of this entity
+    #[must_use]
+    pub fn entity_id(mut self, entity_id: EntityId) -> ReportFilter {
+        self.entity_id = Some(entity_id);
+        self
+    }
+
+    /// The number of reports to return (1-100)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> ReportFilter {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The token of the page to return, taken from the `next` link of the
+    /// previous page
+    #[must_use]
+    pub fn pagination_token(mut self, pagination_token: String) -> ReportFilter {
+        self.pagination_token = Some(pagination_token);
+        self
+    }
+}
+
+/// Query parameters to filter payout statements
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct PayoutStatementFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payout_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payout_currency: Option<Currency>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+}
+
+impl PayoutStatementFilter {
+    /// Only return statements issued at or after this time
+    #[must_use]
+    pub fn from(mut self, from: DateTime<Utc>) -> PayoutStatementFilter {
+        self.from = Some(from);
+        self
+    }
+
+    /// Only return statements issued before this time
+    #[must_use]
+    pub fn to(mut self, to: DateTime<Utc>) -> PayoutStatementFilter {
+        self.to = Some(to);
+        self
+    }
+
+    /// Only return the statement of this payout
+    #[must_use]
+    pub fn payout_id(mut self, payout_id: String) -> PayoutStatementFilter {
+        self.payout_id = Some(payout_id);
+        self
+    }
+
+    /// Only return statements with payouts in this currency
+    #[must_use]
+    pub fn payout_currency(mut self, payout_currency: Currency) -> PayoutStatementFilter {
+        self.payout_currency = Some(payout_currency);
+        self
+    }
+
+    /// The number of statements to return (1-100)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> PayoutStatementFilter {
+        self.limit = Some(limit);
+        self
+    }
+}
+
+/// Query parameters to select financial actions, by payment or action
+///
+/// # Example
+///
+/// ```ignore
+/// let filter = FinancialActionFilter::payment(payment_id).limit(100);
+/// ```
+#[derive(Serialize, Debug, Clone)]
+pub struct FinancialActionFilter {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payment_id: Option<PaymentId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action_id: Option<ActionId>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pagination_token: Option<String>,
+}
+
+impl FinancialActionFilter {
+    /// Returns the financial actions of a payment
+    #[must_use]
+    pub fn payment(payment_id: PaymentId) -> FinancialActionFilter {
+        FinancialActionFilter {
+            payment_id: Some(payment_id),
+            action_id: None,
+            limit: None,
+            pagination_token: None,
+        }
+    }
+
+    /// Returns the financial actions of an action, e.g. a capture
+    #[must_use]
+    pub fn action(action_id: ActionId) -> FinancialActionFilter {
+        FinancialActionFilter {
+            payment_id: None,
+            action_id: Some(action_id),
+            limit: None,
+            pagination_token: None,
+        }
+    }
+
+    /// The number of financial actions to return (1-100)
+    #[must_use]
+    pub fn limit(mut self, limit: u32) -> FinancialActionFilter {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// The token of the page to return, taken from the `next` link of the
+    /// previous page
+    #[must_use]
+    pub fn pagination_token(mut self, pagination_token: String) -> FinancialActionFilter {
+        self.pagination_token = Some(pagination_token);
+        self
+    }
+}
@@ -0,0 +1,101 @@
+//! References that identify payments, payouts and actions in your systems
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+
+/// The maximum length of a reference
+pub const MAX_REFERENCE_LENGTH: usize = 50;
+
+/// A reference of at most 50 characters that only contains characters card
+/// schemes accept: ASCII letters and digits, `-`, `_`, `.` and `/`
+///
+/// Use it wherever a request takes a `reference`, e.g.
+/// `reference: Some(Reference::generate("order-")?.into())`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Reference(String);
+
+/// A reference is empty, too long or contains characters card schemes don't
+/// accept, contains the original string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidReferenceError(pub String);
+
+impl fmt::Display for InvalidReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid reference {:?}: must be 1-{} characters of [A-Za-z0-9-_./]",
+            self.0, MAX_REFERENCE_LENGTH
+        )
+    }
+}
+
+impl std::error::Error for InvalidReferenceError {}
+
+impl Reference {
+    /// Creates a reference after validating its length and characters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reference is empty, longer than 50 characters
+    /// or contains characters card schemes don't accept
+    pub fn new(reference: impl Into<String>) -> Result<Reference, InvalidReferenceError> {
+        let reference = reference.into();
+        let valid_chars = reference
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+
+        if reference.is_empty() || reference.len() > MAX_REFERENCE_LENGTH || !valid_chars {
+            Err(InvalidReferenceError(reference))
+        } else {
+            Ok(Reference(reference))
+        }
+    }
+
+    /// Generates a collision-resistant reference from a prefix and a ULID,
+    /// e.g. `order-01FZ8R2Y0Q7X9K3M5N4P6T8V1W`. ULIDs sort by creation time.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the prefix is longer than 24 characters or
+    /// contains characters card schemes don't accept
+    pub fn generate(prefix: &str) -> Result<Reference, InvalidReferenceError> {
+        Reference::new(format!("{}{}", prefix, Ulid::new()))
+    }
+
+    /// Returns the reference as a string
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for Reference {
+    type Err = InvalidReferenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Reference::new(s)
+    }
+}
+
+impl TryFrom<String> for Reference {
+    type Error = InvalidReferenceError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Reference::new(value)
+    }
+}
+
+impl From<Reference> for String {
+    fn from(reference: Reference) -> String {
+        reference.0
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
@@ -0,0 +1,182 @@
+//! Types for listing financial reports
+//!
+//! See: [Reports](https://docs.checkout.com/reports)
+
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{
+    links::{Links, Paginated},
+    Currency,
+};
+
+/// A report that was generated for your account
+#[derive(Deserialize, Debug, Clone)]
+pub struct Report {
+    /// The report's unique identifier (format: `rpt_*`)
+    pub id: String,
+
+    /// The date/time the report was created
+    pub created_on: DateTime<Utc>,
+
+    /// The date/time the report was last modified
+    pub last_modified_on: Option<DateTime<Utc>>,
+
+    /// The type of the report, e.g. `"PayoutStatement"`
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// A description of the report
+    pub description: Option<String>,
+
+    /// The start of the period covered by the report
+    pub from: Option<DateTime<Utc>>,
+
+    /// The end of the period covered by the report
+    pub to: Option<DateTime<Utc>>,
+
+    /// The files of the report
+    #[serde(default)]
+    pub files: Vec<ReportFile>,
+
+    /// The links related to the report
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// A file of a report
+#[derive(Deserialize, Debug, Clone)]
+pub struct ReportFile {
+    /// The file's unique identifier (format: `file_*`)
+    pub id: String,
+
+    /// The name of the file
+    pub filename: String,
+
+    /// The format of the file, e.g. `"CSV"`
+    pub format: String,
+
+    /// The links related to the file
+    ///
+    /// - Required: `"self"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// Response to list reports
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetReportsResponse {
+    /// The number of reports on the page
+    pub count: u32,
+
+    /// The maximum number of reports on a page
+    pub limit: u32,
+
+    /// The reports
+    #[serde(default)]
+    pub data: Vec<Report>,
+
+    /// The links related to the page
+    ///
+    /// - Optional: `"next"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+impl Paginated for GetReportsResponse {
+    type Item = Report;
+
+    fn links(&self) -> Option<&Links> {
+        self.links.as_ref()
+    }
+
+    fn into_items(self) -> Vec<Report> {
+        self.data
+    }
+}
+
+/// A statement of the payouts of a period, from the reconciliation API
+#[derive(Deserialize, Debug, Clone)]
+pub struct PayoutStatement {
+    /// The statement's unique identifier
+    pub id: String,
+
+    /// The start of the period covered by the statement
+    pub period_start: Option<DateTime<Utc>>,
+
+    /// The end of the period covered by the statement
+    pub period_end: Option<DateTime<Utc>>,
+
+    /// The date/time the statement was issued
+    pub date: Option<DateTime<Utc>>,
+
+    /// The payouts of the statement, one per currency
+    #[serde(default)]
+    pub payouts: Vec<StatementPayout>,
+
+    /// The links related to the statement
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// A payout of a [`PayoutStatement`]
+#[derive(Deserialize, Debug, Clone)]
+pub struct StatementPayout {
+    /// The payout's unique identifier
+    pub id: String,
+
+    /// The status of the payout, e.g. `"Paid"`
+    pub status: Option<String>,
+
+    /// The currency of the payout
+    pub currency: Option<Currency>,
+
+    /// The amount carried forward from the previous period
+    pub carried_forward_amount: Option<BigDecimal>,
+
+    /// The amount of the current period
+    pub current_period_amount: Option<BigDecimal>,
+
+    /// The amount paid out
+    pub net_amount: Option<BigDecimal>,
+
+    /// The fee deducted for the payout
+    pub payout_fee: Option<BigDecimal>,
+
+    /// The date/time the payout was made
+    pub date: Option<DateTime<Utc>>,
+
+    /// The links related to the payout
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// Response to list payout statements
+#[derive(Deserialize, Debug, Clone)]
+pub struct GetPayoutStatementsResponse {
+    /// The number of statements on the page
+    pub count: u32,
+
+    /// The statements
+    #[serde(default)]
+    pub data: Vec<PayoutStatement>,
+
+    /// The links related to the page
+    ///
+    /// - Optional: `"next"`
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+impl Paginated for GetPayoutStatementsResponse {
+    type Item = PayoutStatement;
+
+    fn links(&self) -> Option<&Links> {
+        self.links.as_ref()
+    }
+
+    fn into_items(self) -> Vec<PayoutStatement> {
+        self.data
+    }
+}
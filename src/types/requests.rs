@@ -1,4 +1,14 @@
-use super::*;
+use std::net::IpAddr;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{ser::SerializeMap, Deserialize, Serialize, Serializer};
+
+use super::{
+    _3DSRequest, Amount, BillingDescriptor, CaptureType, Currency, CustomerDescriptor, ExtraFields,
+    Metadata, Money, PaymentProcessingDescriptor, PaymentRecipient, PaymentRequestDestination,
+    PaymentRequestSource, PaymentType, PayoutSender, PreferredScheme, ProcessingChannelId,
+    RiskRequest, ShippingDescriptor,
+};
 
 /// The request body to be used to authenticate
 #[derive(Serialize, Debug, Clone)]
@@ -31,14 +41,12 @@ pub struct CreatePaymentRequest {
     /// The destination of the payout. Use to pay out to a card.
     pub destination: Option<PaymentRequestDestination>,
 
-    /// The payment amount. The exact format depends on the currency. Omit the
-    /// amount or provide a value of 0 to perform a card verification.
+    /// The payment amount and currency, or a card verification without an
+    /// amount
     ///
     /// See: [Calculating the value](https://docs.checkout.com/resources/calculating-the-value)
-    pub amount: Option<Amount>,
-
-    /// The three-letter ISO country code
-    pub currency: Currency,
+    #[serde(flatten)]
+    pub amount: PaymentAmount,
 
     /// This must be specified for card payments where the cardholder is not
     /// present (i.e., recurring or mail order / telephone order) (default:
@@ -64,7 +72,10 @@ pub struct CreatePaymentRequest {
 
     /// A timestamp (ISO 8601 code) that determines when the payment should be
     /// captured. Providing this field will automatically set capture to true
-    pub capture_on: Option<String>,
+    ///
+    /// Use [`CreatePaymentRequest::capture_at`] or
+    /// [`CreatePaymentRequest::capture_after`] to make sure it is in the future.
+    pub capture_on: Option<DateTime<Utc>>,
 
     /// The customer's details
     pub customer: Option<CustomerDescriptor>,
@@ -100,8 +111,12 @@ pub struct CreatePaymentRequest {
     pub failure_url: Option<String>,
 
     /// The IP address used to make the payment. Required for some risk checks
-    /// (<= 45 characters)
-    pub payment_ip: Option<String>,
+    pub payment_ip: Option<IpAddr>,
+
+    /// The sender of the funds of an Account Funding Transaction. Use
+    /// [`AftRequest`] to make sure the data card schemes require is present.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender: Option<PayoutSender>,
 
     /// Information about the recipient of the payment's funds. Relevant for
     /// both Account Funding Transactions and VISA or MasterCard domestic UK
@@ -111,23 +126,220 @@ pub struct CreatePaymentRequest {
     /// and [Requirements for financial institutions](https://docs.checkout.com/risk-management/requirements-for-financial-institutions)
     pub recipient: Option<PaymentRecipient>,
 
+    /// The processing channel to process the payment with (format: `pc_*`).
+    /// Defaults to the channel configured for the credentials. See
+    /// [`ChannelRouter`] to select a channel per payment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_channel_id: Option<ProcessingChannelId>,
+
     /// Use the processing object to influence or override the data sent during
     /// card processing
     pub processing: Option<PaymentProcessingDescriptor>,
 
+    /// The store and terminal the payment was taken at, for in-store and
+    /// MOTO payments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store: Option<StoreDescriptor>,
+
+    /// The order's line items, e.g. the products, shipping fee and surcharge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Vec<PaymentItem>>,
+
     /// Allows you to store additional information about a transaction with
     /// custom fields and up to five user-defined fields (`udf1` to `udf5`),
     /// which can be used for reporting purposes. `udf1` is also used for some
     /// of our risk rules.
     pub metadata: Option<Metadata>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// The store and terminal a payment was taken at
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct StoreDescriptor {
+    /// Your identifier of the store (<= 50 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// The name of the store (<= 100 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The identifier of the terminal the payment was taken on (<= 50
+    /// characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terminal_id: Option<String>,
+
+    /// The sales channel the payment was taken through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<SalesChannel>,
+}
+
+/// A line item of an order
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaymentItem {
+    /// The type of the item
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub ty: Option<ItemType>,
+
+    /// The descriptive name of the item (<= 255 characters)
+    pub name: String,
+
+    /// The number of units of the item
+    pub quantity: u32,
+
+    /// The price of a unit of the item
+    pub unit_price: Amount,
+
+    /// Your reference for the item, e.g. a SKU (<= 255 characters)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+
+    /// The total price of the item, including tax and discounts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_amount: Option<Amount>,
+
+    /// The tax included in the total price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_amount: Option<Amount>,
+
+    /// The discount applied to the total price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount_amount: Option<Amount>,
+}
+
+/// The type of a line item
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemType {
+    Physical,
+    Digital,
+    Discount,
+    ShippingFee,
+    SalesTax,
+    Surcharge,
+    StoreCredit,
+    GiftCard,
+}
+
+/// The sales channel of a payment
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SalesChannel {
+    Ecommerce,
+    InStore,
+    Moto,
+    Mobile,
+}
+
+/// The amount of a payment request
+///
+/// A payment request without an amount (or with an amount of 0) performs a
+/// card verification instead of a payment. Making the choice explicit
+/// prevents a forgotten amount from silently becoming a verification.
+#[derive(Debug, Clone, Copy)]
+pub enum PaymentAmount {
+    /// Charge the given amount
+    Charge(Money),
+
+    /// Verify the card without charging it
+    Verify(Currency),
+}
+
+impl PaymentAmount {
+    /// Returns the amount that is charged, which is zero for a verification
+    #[must_use]
+    pub fn amount(&self) -> Amount {
+        match self {
+            PaymentAmount::Charge(money) => money.amount,
+            PaymentAmount::Verify(_) => Amount::from_minor_units(0),
+        }
+    }
+
+    /// Returns the currency of the payment
+    #[must_use]
+    pub fn currency(&self) -> Currency {
+        match self {
+            PaymentAmount::Charge(money) => money.currency,
+            PaymentAmount::Verify(currency) => *currency,
+        }
+    }
+
+    /// Returns whether the request is a card verification
+    #[must_use]
+    pub fn is_verification(&self) -> bool {
+        match self {
+            PaymentAmount::Charge(money) => money.amount.minor_units() == 0,
+            PaymentAmount::Verify(_) => true,
+        }
+    }
+}
+
+impl Serialize for PaymentAmount {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(2))?;
+        map.serialize_entry("amount", &self.amount())?;
+        map.serialize_entry("currency", &self.currency())?;
+        map.end()
+    }
+}
+
+/// The requested capture time is not in the future, contains the requested
+/// time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureScheduleError(pub DateTime<Utc>);
+
+impl CreatePaymentRequest {
+    /// Schedules the payment to be captured at the given time
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the time is not in the future
+    pub fn capture_at(&mut self, when: DateTime<Utc>) -> Result<(), CaptureScheduleError> {
+        if when <= Utc::now() {
+            return Err(CaptureScheduleError(when));
+        }
+
+        self.capture_on = Some(when);
+        Ok(())
+    }
+
+    /// Schedules the payment to be captured after the given delay, e.g.
+    /// `capture_after(Duration::days(3))`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the delay is not positive
+    pub fn capture_after(&mut self, delay: Duration) -> Result<(), CaptureScheduleError> {
+        self.capture_at(Utc::now() + delay)
+    }
+
+    /// Routes a co-badged card through the scheme, e.g. the one the customer
+    /// chose at checkout. Check [`PaymentProcessed::processed_scheme`] to
+    /// see which scheme was used.
+    pub fn prefer_scheme(&mut self, scheme: PreferredScheme) {
+        self.processing
+            .get_or_insert_with(PaymentProcessingDescriptor::default)
+            .preferred_scheme = Some(scheme);
+    }
 }
 
 /// Body used in the request to capture a payment
 #[derive(Serialize, Debug, Clone)]
 pub struct CapturePaymentBody {
-    /// The amount to capture. If not specified, the full payment amount will
-    /// be captured
-    pub amount: Option<u64>,
+    /// The amount to capture, in the currency of the payment. If not
+    /// specified, the full payment amount will be captured
+    #[serde(serialize_with = "serialize_action_amount")]
+    pub amount: Option<Money>,
+
+    /// Whether more captures will follow for the payment (default: Final).
+    /// Requires multi-capture to be enabled on your account
+    pub capture_type: Option<CaptureType>,
 
     /// A reference you can later use to identify this capture request
     pub reference: Option<String>,
@@ -136,14 +348,19 @@ pub struct CapturePaymentBody {
     /// This can be useful for storing additional information in a structured
     /// format
     pub metadata: Option<Metadata>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
 }
 
 /// Body used in the request to refund a payment
 #[derive(Serialize, Debug, Clone)]
 pub struct RefundPaymentBody {
-    /// The amount to refund. If not specified, the full payment amount will
-    /// be refunded
-    pub amount: Option<u64>,
+    /// The amount to refund, in the currency of the payment. If not
+    /// specified, the full payment amount will be refunded
+    #[serde(serialize_with = "serialize_action_amount")]
+    pub amount: Option<Money>,
 
     /// A reference you can later use to identify this refund request
     pub reference: Option<String>,
@@ -152,6 +369,21 @@ pub struct RefundPaymentBody {
     /// This can be useful for storing additional information in a structured
     /// format
     pub metadata: Option<Metadata>,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
+}
+
+/// Serializes the amount of a capture or refund in minor units. Actions are
+/// in the currency of their payment, so the currency is not sent.
+// `serialize_with` passes a reference to the field, so it takes `&Option`
+#[allow(clippy::ref_option)]
+fn serialize_action_amount<S>(amount: &Option<Money>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    amount.map(|money| money.amount).serialize(serializer)
 }
 
 /// Body used in the request to void a payment
@@ -172,4 +404,12 @@ pub struct CreateInstrumentBody {
     /// The instrument type
     #[serde(rename = "type")]
     ty: String,
+
+    /// Additional fields to send that are not yet modeled by this library
+    #[serde(flatten)]
+    pub extra: Option<ExtraFields>,
 }
+
+/// An empty request body, for endpoints that take no parameters
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct EmptyBody {}
@@ -1,10 +1,19 @@
-use super::*;
+use std::time::{Duration, Instant, SystemTime};
+
+use secrecy::SecretString;
+use serde::Deserialize;
+
+use super::{
+    links::Links, scopes, Action, PaymentDetails, PaymentProcessed, PaymentStatus, PendingPayment,
+    Scope,
+};
 
 /// The response for a successful authentication
 #[derive(Deserialize, Debug, Clone)]
 pub struct OAuthTokenResponse {
-    /// The token to be used to access endpoints
-    pub access_token: String,
+    /// The token to be used to access endpoints. It is zeroized when dropped
+    /// and redacted from `Debug` output.
+    pub access_token: SecretString,
 
     /// Seconds until expiration
     pub expires_in: u32,
@@ -16,7 +25,45 @@ pub struct OAuthTokenResponse {
     ///
     /// See [Authentication](https://api-reference.checkout.com/preview/crusoe/#section/Authentication)
     /// for possible scopes
-    pub scope: String,
+    #[serde(deserialize_with = "scopes::deserialize_scopes")]
+    pub scope: Vec<Scope>,
+
+    /// When the token was received
+    #[serde(skip, default = "Instant::now")]
+    received: Instant,
+
+    /// When the token was received, as a wall-clock time
+    #[serde(skip, default = "SystemTime::now")]
+    received_at: SystemTime,
+}
+
+impl OAuthTokenResponse {
+    /// Returns when the token expires, e.g. to schedule its refresh. Computed
+    /// from `expires_in` and the time the response was decoded.
+    #[must_use]
+    pub fn expires_at(&self) -> Instant {
+        self.received + Duration::from_secs(self.expires_in.into())
+    }
+
+    /// Returns when the token expires as a wall-clock time, e.g. to log it
+    #[must_use]
+    pub fn expires_at_system_time(&self) -> SystemTime {
+        self.received_at + Duration::from_secs(self.expires_in.into())
+    }
+
+    /// Returns whether the token expires within `duration`, e.g. to alert on
+    /// tokens that are about to expire
+    #[must_use]
+    pub fn expires_within(&self, duration: Duration) -> bool {
+        Instant::now() + duration >= self.expires_at()
+    }
+
+    /// Returns whether the token can access the endpoints of `scope`, either
+    /// through the scope itself or one of its parents
+    #[must_use]
+    pub fn has_scope(&self, scope: &Scope) -> bool {
+        self.scope.iter().any(|granted| granted.covers(scope))
+    }
 }
 
 /// Response to create a payment
@@ -31,6 +78,49 @@ pub enum CreatePaymentResponse {
     Pending(PendingPayment),
 }
 
+impl CreatePaymentResponse {
+    /// Returns the payment's unique identifier (format: `pay_*`)
+    #[must_use]
+    pub fn payment_id(&self) -> &str {
+        match self {
+            CreatePaymentResponse::Processed(payment) => &payment.id,
+            CreatePaymentResponse::Pending(payment) => &payment.id,
+        }
+    }
+
+    /// Returns whether the response is for a card verification, i.e. a
+    /// request without an amount
+    #[must_use]
+    pub fn is_card_verified(&self) -> bool {
+        let status = match self {
+            CreatePaymentResponse::Processed(payment) => payment.status,
+            CreatePaymentResponse::Pending(payment) => payment.status,
+        };
+        status == PaymentStatus::CardVerified
+    }
+
+    /// Returns the response if the payment was approved or is still pending,
+    /// or a [`DeclineError`](crate::DeclineError) describing the decline
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payment was processed but not approved
+    pub fn ensure_approved(self) -> Result<CreatePaymentResponse, crate::DeclineError> {
+        match self {
+            CreatePaymentResponse::Processed(payment) if !payment.approved => {
+                Err(crate::DeclineError {
+                    kind: crate::DeclineKind::from_response_code(&payment.response_code),
+                    merchant_advice: payment.merchant_advice(),
+                    payment_id: payment.id,
+                    response_code: payment.response_code,
+                    response_summary: payment.response_summary,
+                })
+            }
+            response => Ok(response),
+        }
+    }
+}
+
 /// Response to get payment details
 pub type GetPaymentDetailsResponse = PaymentDetails;
 
@@ -40,12 +130,20 @@ pub type GetPaymentActionsResponse = Vec<Action>;
 /// Response to capture a payment
 #[derive(Deserialize, Debug, Clone)]
 pub struct CapturePaymentResponse {
-    /// The unique identifier for the capture action (format: `act_*`)
+    /// The unique identifier for the capture action (format: `act_*`). With
+    /// multi-capture, each capture of a payment has its own action, which is
+    /// referenced by the capture's webhook notifications
     pub action_id: String,
 
     /// Your reference for the capture request
     pub reference: Option<String>,
 
+    /// The unique identifiers of the child actions of a multi-capture
+    /// (format: `act_*`), which the capture's webhook notifications may
+    /// reference in place of [`action_id`](CapturePaymentResponse::action_id)
+    #[serde(default)]
+    pub child_action_ids: Vec<String>,
+
     /// The links related to the capture
     ///
     /// - Required: `"payment"`
@@ -54,6 +152,21 @@ pub struct CapturePaymentResponse {
     pub links: Option<Links>,
 }
 
+impl CapturePaymentResponse {
+    /// Returns whether the given action belongs to the capture, either as
+    /// the capture action itself or one of its child actions
+    #[must_use]
+    pub fn has_action(&self, action_id: &str) -> bool {
+        self.action_id == action_id || self.child_action_ids.iter().any(|id| id == action_id)
+    }
+
+    /// Returns the URL of the captured payment
+    #[must_use]
+    pub fn payment_url(&self) -> Option<&str> {
+        Some(self.links.as_ref()?.get("payment")?.href())
+    }
+}
+
 /// Response to refund a payment
 #[derive(Deserialize, Debug, Clone)]
 pub struct RefundPaymentResponse {
@@ -0,0 +1,136 @@
+//! Card schemes
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A card scheme, as returned in the `scheme` field of a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CardScheme {
+    Visa,
+    Mastercard,
+    Amex,
+    Discover,
+    Diners,
+    Jcb,
+    Mada,
+    CartesBancaires,
+    Maestro,
+    UnionPay,
+
+    /// A scheme that is not modeled by this library
+    Other(String),
+}
+
+impl CardScheme {
+    /// Returns the scheme as it is represented by the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            CardScheme::Visa => "Visa",
+            CardScheme::Mastercard => "Mastercard",
+            CardScheme::Amex => "Amex",
+            CardScheme::Discover => "Discover",
+            CardScheme::Diners => "Diners Club International",
+            CardScheme::Jcb => "JCB",
+            CardScheme::Mada => "Mada",
+            CardScheme::CartesBancaires => "Cartes Bancaires",
+            CardScheme::Maestro => "Maestro",
+            CardScheme::UnionPay => "UnionPay",
+            CardScheme::Other(scheme) => scheme,
+        }
+    }
+
+    /// Returns whether the scheme is American Express
+    #[must_use]
+    pub fn is_amex(&self) -> bool {
+        *self == CardScheme::Amex
+    }
+
+    /// Returns the number of digits of the scheme's card verification value
+    #[must_use]
+    pub fn cvv_length(&self) -> usize {
+        if self.is_amex() {
+            4
+        } else {
+            3
+        }
+    }
+}
+
+impl FromStr for CardScheme {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect::<String>()
+            .to_ascii_lowercase();
+
+        Ok(match normalized.as_str() {
+            "visa" => CardScheme::Visa,
+            "mastercard" => CardScheme::Mastercard,
+            "amex" | "americanexpress" => CardScheme::Amex,
+            "discover" => CardScheme::Discover,
+            "diners" | "dinersclub" | "dinersclubinternational" => CardScheme::Diners,
+            "jcb" => CardScheme::Jcb,
+            "mada" => CardScheme::Mada,
+            "cartesbancaires" => CardScheme::CartesBancaires,
+            "maestro" => CardScheme::Maestro,
+            "unionpay" | "chinaunionpay" => CardScheme::UnionPay,
+            _ => CardScheme::Other(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for CardScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for CardScheme {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CardScheme {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scheme = String::deserialize(deserializer)?;
+        match scheme.parse() {
+            Ok(scheme) => Ok(scheme),
+            Err(never) => match never {},
+        }
+    }
+}
+
+/// The scheme to route a co-badged card through, e.g. Cartes Bancaires
+/// instead of Visa for a card that carries both brands
+///
+/// In the EU, the cardholder's choice of scheme on a co-badged card must be
+/// honored.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PreferredScheme {
+    Visa,
+    Mastercard,
+    CartesBancaires,
+}
+
+impl From<PreferredScheme> for CardScheme {
+    fn from(scheme: PreferredScheme) -> CardScheme {
+        match scheme {
+            PreferredScheme::Visa => CardScheme::Visa,
+            PreferredScheme::Mastercard => CardScheme::Mastercard,
+            PreferredScheme::CartesBancaires => CardScheme::CartesBancaires,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! OAuth scopes
+//!
+//! See: [Authentication](https://api-reference.checkout.com/#section/Authentication)
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+
+/// An OAuth scope, which determines what endpoints a token can access
+///
+/// Scopes are hierarchical: a token with the `gateway` scope can access the
+/// endpoints of `gateway:payment`, for instance. Sub-scopes are represented
+/// by [`Scope::Other`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Scope {
+    Gateway,
+    Vault,
+    Disputes,
+    Files,
+    Transfers,
+    Accounts,
+    Balances,
+    Reports,
+    Marketplace,
+    Fx,
+
+    /// A scope that is not modeled by this library, e.g. `disputes:view`
+    Other(String),
+}
+
+impl Scope {
+    /// Returns the scope as it is represented by the API
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Scope::Gateway => "gateway",
+            Scope::Vault => "vault",
+            Scope::Disputes => "disputes",
+            Scope::Files => "files",
+            Scope::Transfers => "transfers",
+            Scope::Accounts => "accounts",
+            Scope::Balances => "balances",
+            Scope::Reports => "reports",
+            Scope::Marketplace => "marketplace",
+            Scope::Fx => "fx",
+            Scope::Other(scope) => scope,
+        }
+    }
+
+    /// Returns whether a token with this scope can access the endpoints of
+    /// `scope`, i.e. whether both are the same or this scope is a parent of
+    /// `scope`
+    #[must_use]
+    pub fn covers(&self, scope: &Scope) -> bool {
+        let (this, other) = (self.as_str(), scope.as_str());
+        other == this || (other.starts_with(this) && other[this.len()..].starts_with(':'))
+    }
+}
+
+impl FromStr for Scope {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "gateway" => Scope::Gateway,
+            "vault" => Scope::Vault,
+            "disputes" => Scope::Disputes,
+            "files" => Scope::Files,
+            "transfers" => Scope::Transfers,
+            "accounts" => Scope::Accounts,
+            "balances" => Scope::Balances,
+            "reports" => Scope::Reports,
+            "marketplace" => Scope::Marketplace,
+            "fx" => Scope::Fx,
+            other => Scope::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Deserializes a space-separated list of scopes
+pub(crate) fn deserialize_scopes<'de, D>(deserializer: D) -> Result<Vec<Scope>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let scopes = String::deserialize(deserializer)?;
+    Ok(scopes
+        .split_whitespace()
+        .map(|scope| match scope.parse() {
+            Ok(scope) => scope,
+            Err(never) => match never {},
+        })
+        .collect())
+}
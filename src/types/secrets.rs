@@ -0,0 +1,70 @@
+//! Sensitive values that are scrubbed from memory when dropped and never
+//! printed
+
+use std::fmt;
+
+use secrecy::{ExposeSecret, SecretString};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A card verification value/code
+///
+/// The value is zeroized when dropped and redacted from `Debug` output. It is
+/// only revealed when serialized into a request.
+#[derive(Clone)]
+pub struct Cvv(SecretString);
+
+impl Cvv {
+    /// Creates a card verification value
+    #[must_use]
+    pub fn new(cvv: impl Into<String>) -> Cvv {
+        Cvv(SecretString::new(cvv.into()))
+    }
+
+    /// Returns the number of digits of the value
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.expose_secret().len()
+    }
+
+    /// Returns whether the value is empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.expose_secret().is_empty()
+    }
+}
+
+impl From<String> for Cvv {
+    fn from(cvv: String) -> Cvv {
+        Cvv::new(cvv)
+    }
+}
+
+impl From<&str> for Cvv {
+    fn from(cvv: &str) -> Cvv {
+        Cvv::new(cvv)
+    }
+}
+
+impl fmt::Debug for Cvv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cvv([REDACTED])")
+    }
+}
+
+impl Serialize for Cvv {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(self.0.expose_secret())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cvv {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(Cvv::new)
+    }
+}
@@ -0,0 +1,215 @@
+//! Validated uploads to the Files API, e.g. of dispute evidence
+//!
+//! Files are checked against the API's size and type limits before they are
+//! sent, so that oversized or unsupported files fail with a clear error
+//! instead of an opaque `413 Payload Too Large`.
+//!
+//! See: [Files](https://api-reference.checkout.com/#tag/Files)
+
+use std::{
+    fmt,
+    path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use reqwest::{
+    multipart::{Form, Part},
+    Body,
+};
+
+/// The maximum size of a file accepted by the Files API, in bytes (4 MB)
+pub const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// The size of the chunks the progress of an upload is reported for
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A file could not be uploaded because it is invalid
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum UploadError {
+    /// The file is empty
+    #[error("the file is empty")]
+    Empty,
+
+    /// The file is larger than the maximum size
+    #[error("the file has {size} bytes, at most {max} are allowed")]
+    TooLarge {
+        /// The size of the file in bytes
+        size: u64,
+
+        /// The maximum size in bytes
+        max: u64,
+    },
+
+    /// The file is neither a JPEG, PNG nor PDF file
+    #[error("unsupported file type of {0:?}, expected a JPEG, PNG or PDF file")]
+    UnsupportedType(String),
+}
+
+/// A callback that receives the number of bytes sent and the total size of
+/// an upload as the upload progresses
+#[derive(Clone)]
+pub struct UploadProgress(Arc<dyn Fn(u64, u64) + Send + Sync>);
+
+impl UploadProgress {
+    /// Creates a new progress callback
+    #[must_use]
+    pub fn new<F>(callback: F) -> UploadProgress
+    where
+        F: Fn(u64, u64) + Send + Sync + 'static,
+    {
+        UploadProgress(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for UploadProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UploadProgress")
+    }
+}
+
+/// A file to upload
+#[derive(Clone, Debug)]
+pub struct FileUpload {
+    filename: String,
+    content: Bytes,
+    purpose: String,
+    max_size: u64,
+    progress: Option<UploadProgress>,
+}
+
+impl FileUpload {
+    /// Creates an upload of dispute evidence
+    #[must_use]
+    pub fn new(filename: impl Into<String>, content: impl Into<Bytes>) -> FileUpload {
+        FileUpload {
+            filename: filename.into(),
+            content: content.into(),
+            purpose: "dispute_evidence".to_string(),
+            max_size: MAX_FILE_SIZE,
+            progress: None,
+        }
+    }
+
+    /// Sets the purpose of the file (default: `"dispute_evidence"`)
+    #[must_use]
+    pub fn purpose(mut self, purpose: impl Into<String>) -> FileUpload {
+        self.purpose = purpose.into();
+        self
+    }
+
+    /// Sets the maximum size of the file in bytes, e.g. to enforce a lower
+    /// limit than [`MAX_FILE_SIZE`]
+    #[must_use]
+    pub fn max_size(mut self, max_size: u64) -> FileUpload {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Reports the progress of the upload to `progress`
+    #[must_use]
+    pub fn on_progress(mut self, progress: UploadProgress) -> FileUpload {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Returns the size of the file in bytes
+    #[must_use]
+    pub fn size(&self) -> u64 {
+        self.content.len() as u64
+    }
+
+    /// Returns the media type of the file, detected from its content and
+    /// falling back to its file extension
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is neither a JPEG, PNG nor PDF file
+    pub fn content_type(&self) -> Result<&'static str, UploadError> {
+        let content = &self.content[..];
+        if content.starts_with(b"%PDF") {
+            return Ok("application/pdf");
+        } else if content.starts_with(b"\x89PNG") {
+            return Ok("image/png");
+        } else if content.starts_with(b"\xFF\xD8\xFF") {
+            return Ok("image/jpeg");
+        }
+
+        let extension = Path::new(&self.filename)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("pdf") => Ok("application/pdf"),
+            Some("png") => Ok("image/png"),
+            Some("jpg" | "jpeg") => Ok("image/jpeg"),
+            _ => Err(UploadError::UnsupportedType(self.filename.clone())),
+        }
+    }
+
+    /// Checks that the file can be uploaded
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is empty, too large or of an unsupported
+    /// type
+    pub fn validate(&self) -> Result<(), UploadError> {
+        if self.content.is_empty() {
+            return Err(UploadError::Empty);
+        }
+        if self.size() > self.max_size {
+            return Err(UploadError::TooLarge {
+                size: self.size(),
+                max: self.max_size,
+            });
+        }
+        self.content_type().map(|_| ())
+    }
+
+    pub(crate) fn into_form(self) -> Result<Form, UploadError> {
+        self.validate()?;
+        let content_type = self.content_type()?;
+        let size = self.size();
+
+        let body = Body::wrap_stream(ProgressStream {
+            content: self.content,
+            sent: 0,
+            progress: self.progress,
+        });
+        let part = Part::stream_with_length(body, size)
+            .file_name(self.filename)
+            .mime_str(content_type)
+            .expect("the detected content type is valid");
+
+        Ok(Form::new().text("purpose", self.purpose).part("file", part))
+    }
+}
+
+/// Yields the content of an upload in chunks, reporting the progress
+struct ProgressStream {
+    content: Bytes,
+    sent: usize,
+    progress: Option<UploadProgress>,
+}
+
+impl Stream for ProgressStream {
+    type Item = Result<Bytes, std::io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let total = self.content.len();
+        if self.sent >= total {
+            return Poll::Ready(None);
+        }
+
+        let end = total.min(self.sent + CHUNK_SIZE);
+        let chunk = self.content.slice(self.sent..end);
+        self.sent = end;
+        if let Some(progress) = &self.progress {
+            (progress.0)(end as u64, total as u64);
+        }
+        Poll::Ready(Some(Ok(chunk)))
+    }
+}
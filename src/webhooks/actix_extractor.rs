@@ -0,0 +1,44 @@
+use std::{future::Future, pin::Pin};
+
+use actix_web::{
+    dev::Payload, error, http::StatusCode, web::Bytes, Error, FromRequest, HttpRequest,
+};
+use serde::de::DeserializeOwned;
+
+use super::{CheckoutWebhook, WebhookError, WebhookVerifier, CKO_SIGNATURE_HEADER};
+
+impl<T> FromRequest for CheckoutWebhook<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let verifier = req.app_data::<WebhookVerifier>().cloned();
+        let signature = req
+            .headers()
+            .get(CKO_SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = Bytes::from_request(req, payload);
+
+        Box::pin(async move {
+            let verifier = verifier.ok_or_else(|| {
+                error::ErrorInternalServerError("WebhookVerifier is not registered as app data")
+            })?;
+            let body = body.await?;
+
+            verifier
+                .verify(&body, signature.as_deref())
+                .map(CheckoutWebhook)
+                .map_err(|err| match err {
+                    // Acknowledge duplicates so that Checkout stops retrying them
+                    WebhookError::Duplicate(_) => {
+                        error::InternalError::new(err, StatusCode::OK).into()
+                    }
+                    _ => error::ErrorBadRequest(err),
+                })
+        })
+    }
+}
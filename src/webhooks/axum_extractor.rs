@@ -0,0 +1,46 @@
+use axum::{
+    async_trait,
+    body::{Bytes, HttpBody},
+    extract::{Extension, FromRequest, RequestParts},
+    http::StatusCode,
+    BoxError,
+};
+use serde::de::DeserializeOwned;
+
+use super::{CheckoutWebhook, WebhookError, WebhookVerifier, CKO_SIGNATURE_HEADER};
+
+#[async_trait]
+impl<T, B> FromRequest<B> for CheckoutWebhook<T>
+where
+    T: DeserializeOwned,
+    B: HttpBody + Send,
+    B::Data: Send,
+    B::Error: Into<BoxError>,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let Extension(verifier) = Extension::<WebhookVerifier>::from_request(req)
+            .await
+            .map_err(|err| (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?;
+
+        let signature = req
+            .headers()
+            .get(CKO_SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let body = Bytes::from_request(req)
+            .await
+            .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+
+        verifier
+            .verify(&body, signature.as_deref())
+            .map(CheckoutWebhook)
+            .map_err(|err| match err {
+                // Acknowledge duplicates so that Checkout stops retrying them
+                WebhookError::Duplicate(_) => (StatusCode::OK, err.to_string()),
+                _ => (StatusCode::BAD_REQUEST, err.to_string()),
+            })
+    }
+}
@@ -0,0 +1,306 @@
+//! Verification and deserialization of webhook notifications
+//!
+//! Checkout signs the body of every webhook notification with the secret key
+//! of the webhook and sends the signature in the `Cko-Signature` header as a
+//! hex-encoded HMAC-SHA256.
+//!
+//! See: [Webhooks](https://docs.checkout.com/workflows/webhooks)
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use crate::types::links::Links;
+
+pub use crate::types::{
+    CaptureEventData, CaptureWebhookEvent, EventFilter, RiskEventData, RiskEventType,
+    RiskWebhookEvent, SessionEventData, SessionEventType, SessionWebhookEvent,
+};
+
+#[cfg(feature = "actix")]
+mod actix_extractor;
+#[cfg(feature = "axum")]
+mod axum_extractor;
+
+/// The header containing the signature of a webhook notification
+pub const CKO_SIGNATURE_HEADER: &str = "cko-signature";
+
+/// A webhook notification
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookEvent<T> {
+    /// The event's unique identifier (format: `evt_*`)
+    pub id: String,
+
+    /// The type of the event, e.g. `"payment_approved"`
+    #[serde(rename = "type")]
+    pub ty: String,
+
+    /// The version of the event
+    pub version: Option<String>,
+
+    /// The date/time the event occurred
+    pub created_on: DateTime<Utc>,
+
+    /// The data of the event
+    pub data: T,
+
+    /// The links related to the event
+    #[serde(rename = "_links")]
+    pub links: Option<Links>,
+}
+
+/// A webhook notification could not be verified or deserialized
+#[derive(thiserror::Error, Debug)]
+pub enum WebhookError {
+    /// The `Cko-Signature` header is missing
+    #[error("missing signature")]
+    MissingSignature,
+
+    /// The signature does not match the body
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    /// The event was created outside of the accepted tolerance
+    #[error("event created on {0} is outside of the accepted tolerance")]
+    Expired(DateTime<Utc>),
+
+    /// The body is not a valid event
+    #[error("invalid body: {0}")]
+    InvalidBody(#[from] serde_json::Error),
+
+    /// The event was already processed, contains the event id
+    #[error("event {0} was already processed")]
+    Duplicate(String),
+}
+
+/// Keeps track of processed webhook events so that deliveries retried by
+/// Checkout are only handled once
+///
+/// Implement this trait on top of your own database to deduplicate events
+/// across processes; [`MemoryEventStore`] only deduplicates within a process.
+pub trait EventStore: Send + Sync {
+    /// Marks an event as processed. Returns `false` if the event was already
+    /// marked as processed.
+    fn mark_processed(&self, event_id: &str) -> bool;
+}
+
+/// An in-memory [`EventStore`] that remembers events for a limited time
+#[derive(Debug)]
+pub struct MemoryEventStore {
+    retention: std::time::Duration,
+    processed: Mutex<HashMap<String, Instant>>,
+}
+
+impl MemoryEventStore {
+    /// Creates a store that remembers events for `retention`. This should be
+    /// at least as long as the tolerance of the [`WebhookVerifier`].
+    #[must_use]
+    pub fn new(retention: std::time::Duration) -> MemoryEventStore {
+        MemoryEventStore {
+            retention,
+            processed: Mutex::default(),
+        }
+    }
+}
+
+impl EventStore for MemoryEventStore {
+    fn mark_processed(&self, event_id: &str) -> bool {
+        let mut processed = match self.processed.lock() {
+            Ok(processed) => processed,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let retention = self.retention;
+        processed.retain(|_, processed_at| processed_at.elapsed() < retention);
+
+        if processed.contains_key(event_id) {
+            false
+        } else {
+            processed.insert(event_id.to_string(), Instant::now());
+            true
+        }
+    }
+}
+
+/// Compares two byte strings in constant time, e.g. signatures or tokens
+///
+/// Comparing secrets with `==` returns as soon as a byte differs, which leaks
+/// how much of a guess was correct through the time taken. Only the lengths
+/// of the strings are not kept secret.
+#[must_use]
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// The secret key of a webhook, used to sign and verify notifications
+///
+/// The secret is never printed and can't be compared with `==`, use
+/// [`WebhookSecret::verify`] to check a signature.
+#[derive(Clone)]
+pub struct WebhookSecret(SecretString);
+
+impl WebhookSecret {
+    /// Creates a webhook secret
+    #[must_use]
+    pub fn new(secret: SecretString) -> WebhookSecret {
+        WebhookSecret(secret)
+    }
+
+    fn mac(&self, body: &[u8]) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.0.expose_secret().as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        mac
+    }
+
+    /// Returns the hex-encoded signature of `body`, e.g. to sign notifications
+    /// in tests
+    #[must_use]
+    pub fn sign(&self, body: &[u8]) -> String {
+        hex::encode(self.mac(body).finalize().into_bytes())
+    }
+
+    /// Returns whether the hex-encoded `signature` is the signature of
+    /// `body`, comparing them in constant time
+    #[must_use]
+    pub fn verify(&self, body: &[u8], signature: &str) -> bool {
+        match hex::decode(signature.trim()) {
+            Ok(signature) => self.mac(body).verify_slice(&signature).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+impl From<SecretString> for WebhookSecret {
+    fn from(secret: SecretString) -> WebhookSecret {
+        WebhookSecret::new(secret)
+    }
+}
+
+impl fmt::Debug for WebhookSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebhookSecret([REDACTED])")
+    }
+}
+
+/// Verifies and deserializes webhook notifications
+#[derive(Clone)]
+pub struct WebhookVerifier {
+    secret: WebhookSecret,
+    tolerance: Option<Duration>,
+    event_store: Option<Arc<dyn EventStore>>,
+}
+
+impl fmt::Debug for WebhookVerifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WebhookVerifier")
+            .field("secret", &self.secret)
+            .field("tolerance", &self.tolerance)
+            .field("event_store", &self.event_store.is_some())
+            .finish()
+    }
+}
+
+impl WebhookVerifier {
+    /// Creates a verifier for the secret key of a webhook
+    #[must_use]
+    pub fn new(secret: impl Into<WebhookSecret>) -> WebhookVerifier {
+        WebhookVerifier {
+            secret: secret.into(),
+            tolerance: None,
+            event_store: None,
+        }
+    }
+
+    /// Rejects events created longer than `tolerance` ago (or further than
+    /// `tolerance` in the future), e.g. to limit replays
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Duration) -> WebhookVerifier {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    /// Rejects events that were already processed according to `store`
+    #[must_use]
+    pub fn with_event_store(mut self, store: Arc<dyn EventStore>) -> WebhookVerifier {
+        self.event_store = Some(store);
+        self
+    }
+
+    /// Checks that `signature` is the signature of `body`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is missing or does not match
+    pub fn verify_signature(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<(), WebhookError> {
+        let signature = signature.ok_or(WebhookError::MissingSignature)?;
+        if self.secret.verify(body, signature) {
+            Ok(())
+        } else {
+            Err(WebhookError::InvalidSignature)
+        }
+    }
+
+    /// Verifies the signature of a webhook notification and deserializes it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature is missing or invalid, the body is
+    /// not a valid event, the event is outside of the tolerance, or the event
+    /// was already processed
+    pub fn verify<T>(
+        &self,
+        body: &[u8],
+        signature: Option<&str>,
+    ) -> Result<WebhookEvent<T>, WebhookError>
+    where
+        T: DeserializeOwned,
+    {
+        self.verify_signature(body, signature)?;
+
+        let event: WebhookEvent<T> = serde_json::from_slice(body)?;
+        if let Some(tolerance) = self.tolerance {
+            let age = Utc::now() - event.created_on;
+            if age > tolerance || -age > tolerance {
+                return Err(WebhookError::Expired(event.created_on));
+            }
+        }
+
+        if let Some(store) = &self.event_store {
+            if !store.mark_processed(&event.id) {
+                return Err(WebhookError::Duplicate(event.id));
+            }
+        }
+
+        Ok(event)
+    }
+}
+
+/// A verified webhook notification, usable as an extractor with the `axum`
+/// and `actix` features
+///
+/// The [`WebhookVerifier`] must be registered as an `axum` extension or as
+/// `actix` app data. Requests that fail verification are rejected with
+/// `400 Bad Request`.
+///
+/// ```ignore
+/// async fn webhook(CheckoutWebhook(event): CheckoutWebhook<serde_json::Value>) {
+///     println!("received {}", event.ty);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct CheckoutWebhook<T>(pub WebhookEvent<T>);